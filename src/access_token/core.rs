@@ -0,0 +1,214 @@
+use crate::access_token::model::{
+    build_last_used_key, hash_token, parse_token_id, AccessTokenDo, AccessTokenDto, AccessTokenReq,
+    AccessTokenResult, LAST_USED_KEY_SUFFIX,
+};
+use crate::common::constant::ACCESS_TOKEN_TABLE_NAME;
+use crate::common::model::TokenSession;
+use crate::now_second_i32;
+use crate::raft::db::route::TableRoute;
+use crate::raft::db::table::TableManagerReq;
+use actix::prelude::*;
+use bean_factory::{bean, BeanFactory, FactoryData, Inject};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 最近使用时间的同步间隔,避免每次请求都写raft
+const LAST_USED_FLUSH_INTERVAL_SECOND: u64 = 60;
+
+///
+/// 个人访问令牌管理器
+/// 令牌数据通过raft同步到各节点,本地只做内存校验
+#[bean(inject)]
+#[derive(Default)]
+pub struct AccessTokenManager {
+    tokens: HashMap<Arc<String>, Arc<AccessTokenDo>>,
+    /// 已同步的最近使用时间
+    last_used_map: HashMap<Arc<String>, i64>,
+    /// 待同步的最近使用时间
+    pending_used_map: HashMap<Arc<String>, i64>,
+    raft_table_route: Option<Arc<TableRoute>>,
+}
+
+impl AccessTokenManager {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    fn check(&mut self, token: &str, now: i64) -> Option<Arc<TokenSession>> {
+        let id = parse_token_id(token)?;
+        let item = self.tokens.get(&Arc::new(id.to_owned()))?;
+        if item.token_hash != hash_token(token) || item.is_expired(now) {
+            return None;
+        }
+        self.pending_used_map.insert(item.id.clone(), now);
+        Some(item.build_session())
+    }
+
+    fn notify_change(&mut self, key: Vec<u8>, value: Option<Vec<u8>>) {
+        let key = String::from_utf8_lossy(&key).to_string();
+        if let Some(id) = key.strip_suffix(LAST_USED_KEY_SUFFIX) {
+            let id = Arc::new(id.to_owned());
+            match value.and_then(|v| String::from_utf8_lossy(&v).parse::<i64>().ok()) {
+                Some(last_used_time) => {
+                    self.last_used_map.insert(id, last_used_time);
+                }
+                None => {
+                    self.last_used_map.remove(&id);
+                }
+            }
+            return;
+        }
+        let id = Arc::new(key);
+        match value {
+            Some(value) => match AccessTokenDo::from_bytes(&value) {
+                Ok(item) => {
+                    self.tokens.insert(id, Arc::new(item));
+                }
+                Err(err) => {
+                    log::error!("parse access token error:{}", err);
+                }
+            },
+            None => {
+                self.tokens.remove(&id);
+                self.pending_used_map.remove(&id);
+            }
+        }
+    }
+
+    fn query_list(&self, username: &Option<Arc<String>>) -> Vec<AccessTokenDto> {
+        let mut list: Vec<AccessTokenDto> = self
+            .tokens
+            .values()
+            .filter(|e| username.as_ref().map(|u| u == &e.username).unwrap_or(true))
+            .map(|e| {
+                let last_used_time = self.last_used_map.get(&e.id).cloned().unwrap_or_default();
+                AccessTokenDto::new(e, last_used_time)
+            })
+            .collect();
+        list.sort_by_key(|e| std::cmp::Reverse(e.create_time));
+        list
+    }
+
+    fn flush_last_used(&mut self, ctx: &mut Context<Self>) {
+        let raft_table_route = if let Some(v) = &self.raft_table_route {
+            v.clone()
+        } else {
+            return;
+        };
+        let mut reqs = vec![];
+        for (id, last_used_time) in self.pending_used_map.drain() {
+            reqs.push(TableManagerReq::Set {
+                table_name: ACCESS_TOKEN_TABLE_NAME.clone(),
+                key: build_last_used_key(&id).into_bytes(),
+                value: last_used_time.to_string().into_bytes(),
+                last_seq_id: None,
+            });
+        }
+        if reqs.is_empty() {
+            return;
+        }
+        async move {
+            for req in reqs {
+                if let Err(err) = raft_table_route.request(req).await {
+                    log::warn!("update access token last used time error:{}", err);
+                }
+            }
+        }
+        .into_actor(self)
+        .map(|_, _, _| {})
+        .spawn(ctx);
+    }
+
+    fn flush_heartbeat(&self, ctx: &mut Context<Self>) {
+        ctx.run_later(
+            Duration::from_secs(LAST_USED_FLUSH_INTERVAL_SECOND),
+            |act, ctx| {
+                act.flush_last_used(ctx);
+                act.flush_heartbeat(ctx);
+            },
+        );
+    }
+}
+
+impl Actor for AccessTokenManager {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        log::info!("AccessTokenManager started");
+        self.flush_heartbeat(ctx);
+    }
+}
+
+impl Inject for AccessTokenManager {
+    type Context = Context<Self>;
+
+    fn inject(
+        &mut self,
+        factory_data: FactoryData,
+        _factory: BeanFactory,
+        _ctx: &mut Self::Context,
+    ) {
+        self.raft_table_route = factory_data.get_bean();
+    }
+}
+
+impl Handler<AccessTokenReq> for AccessTokenManager {
+    type Result = anyhow::Result<AccessTokenResult>;
+
+    fn handle(&mut self, msg: AccessTokenReq, _ctx: &mut Self::Context) -> Self::Result {
+        match msg {
+            AccessTokenReq::Check(token) => Ok(AccessTokenResult::Session(
+                self.check(&token, now_second_i32() as i64),
+            )),
+            AccessTokenReq::NotifyChange { key, value } => {
+                self.notify_change(key, value);
+                Ok(AccessTokenResult::None)
+            }
+            AccessTokenReq::QueryList { username } => {
+                Ok(AccessTokenResult::List(self.query_list(&username)))
+            }
+            AccessTokenReq::Query(id) => {
+                Ok(AccessTokenResult::Token(self.tokens.get(&id).cloned()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::access_token::model::{build_token, check_scopes, AccessTokenScope};
+
+    #[test]
+    fn check_token() {
+        let mut manager = AccessTokenManager::new();
+        let token = build_token("t1", "secret");
+        let item = AccessTokenDo {
+            id: Arc::new("t1".to_owned()),
+            name: Arc::new("ci".to_owned()),
+            username: Arc::new("admin".to_owned()),
+            token_hash: hash_token(&token),
+            scopes: Arc::new(vec![AccessTokenScope {
+                namespace: Arc::new("dev".to_owned()),
+                read: true,
+                write: false,
+            }]),
+            expire_time: 1000,
+            ..Default::default()
+        };
+        manager.notify_change(b"t1".to_vec(), Some(item.to_bytes().unwrap()));
+        let session = manager.check(&token, 100).unwrap();
+        assert_eq!(session.username.as_str(), "admin");
+        let scopes = session.access_scopes.clone().unwrap();
+        assert!(check_scopes(&scopes, "dev", false));
+        assert!(!check_scopes(&scopes, "dev", true));
+        assert!(!check_scopes(&scopes, "prod", false));
+        assert!(manager.check(&build_token("t1", "other"), 100).is_none());
+        assert!(manager.check(&token, 2000).is_none());
+        manager.notify_change(b"t1#used".to_vec(), Some(b"100".to_vec()));
+        assert_eq!(manager.query_list(&None)[0].last_used_time, 100);
+        manager.notify_change(b"t1".to_vec(), None);
+        assert!(manager.check(&token, 100).is_none());
+    }
+}
@@ -0,0 +1,205 @@
+use crate::common::model::privilege::NamespacePrivilegeGroup;
+use crate::common::model::TokenSession;
+use actix::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+/// 个人访问令牌前缀,用于和登录生成的临时token区分
+pub const ACCESS_TOKEN_PREFIX: &str = "rnt_";
+/// 表示全部命名空间
+pub const ALL_NAMESPACE: &str = "*";
+/// 最近使用时间单独存储,key为{id}#used;避免同步使用时间时覆盖令牌变更
+pub const LAST_USED_KEY_SUFFIX: &str = "#used";
+
+///
+/// 访问令牌权限范围,按命名空间分别设置读写权限
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessTokenScope {
+    pub namespace: Arc<String>,
+    #[serde(default)]
+    pub read: bool,
+    #[serde(default)]
+    pub write: bool,
+}
+
+impl AccessTokenScope {
+    fn match_namespace(&self, namespace: &str) -> bool {
+        self.namespace.as_str() == ALL_NAMESPACE
+            || normalize_namespace(&self.namespace) == normalize_namespace(namespace)
+    }
+
+    pub fn check(&self, namespace: &str, is_write: bool) -> bool {
+        self.match_namespace(namespace) && (if is_write { self.write } else { self.read })
+    }
+}
+
+/// 默认命名空间可能为空或public
+fn normalize_namespace(namespace: &str) -> &str {
+    if namespace == "public" {
+        ""
+    } else {
+        namespace
+    }
+}
+
+/// 校验访问令牌是否有命名空间的读(写)权限
+pub fn check_scopes(scopes: &[AccessTokenScope], namespace: &str, is_write: bool) -> bool {
+    scopes.iter().any(|e| e.check(namespace, is_write))
+}
+
+///
+/// 访问令牌持久化对象,只保存令牌的hash值
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessTokenDo {
+    pub id: Arc<String>,
+    pub name: Arc<String>,
+    pub username: Arc<String>,
+    pub token_hash: String,
+    pub scopes: Arc<Vec<AccessTokenScope>>,
+    /// 过期时间,单位秒;0表示不过期
+    pub expire_time: i64,
+    pub create_time: i64,
+}
+
+impl AccessTokenDo {
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn from_bytes(v: &[u8]) -> anyhow::Result<Self> {
+        Ok(serde_json::from_slice(v)?)
+    }
+
+    pub fn is_expired(&self, now_second: i64) -> bool {
+        self.expire_time > 0 && self.expire_time < now_second
+    }
+
+    pub fn build_session(&self) -> Arc<TokenSession> {
+        Arc::new(TokenSession {
+            username: self.username.clone(),
+            access_scopes: Some(self.scopes.clone()),
+            ..Default::default()
+        })
+    }
+}
+
+///
+/// 访问令牌展示对象,不包含令牌hash
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessTokenDto {
+    pub id: Arc<String>,
+    pub name: Arc<String>,
+    pub username: Arc<String>,
+    pub scopes: Arc<Vec<AccessTokenScope>>,
+    pub expire_time: i64,
+    pub create_time: i64,
+    pub last_used_time: i64,
+    /// 只在创建时返回一次令牌原文
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+impl AccessTokenDto {
+    pub fn new(value: &AccessTokenDo, last_used_time: i64) -> Self {
+        Self {
+            id: value.id.clone(),
+            name: value.name.clone(),
+            username: value.username.clone(),
+            scopes: value.scopes.clone(),
+            expire_time: value.expire_time,
+            create_time: value.create_time,
+            last_used_time,
+            token: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessTokenParam {
+    pub id: Option<Arc<String>>,
+    pub name: Option<Arc<String>>,
+    pub scopes: Option<Vec<AccessTokenScope>>,
+    /// 过期时间,单位秒;空或0表示不过期
+    pub expire_time: Option<i64>,
+}
+
+impl AccessTokenParam {
+    ///
+    /// 校验创建参数;令牌的权限范围不能超过创建者的命名空间权限
+    pub fn check(&self, namespace_privilege: &NamespacePrivilegeGroup) -> anyhow::Result<()> {
+        if self.name.as_ref().map(|e| e.is_empty()).unwrap_or(true) {
+            return Err(anyhow::anyhow!("name is empty"));
+        }
+        let scopes = match &self.scopes {
+            Some(scopes) if !scopes.is_empty() => scopes,
+            _ => return Err(anyhow::anyhow!("scopes is empty")),
+        };
+        for scope in scopes {
+            let allowed = if scope.namespace.as_str() == ALL_NAMESPACE {
+                namespace_privilege.is_all()
+            } else {
+                namespace_privilege.check_permission(&scope.namespace)
+            };
+            if !allowed {
+                return Err(anyhow::anyhow!(
+                    "user has no permission of namespace: {}",
+                    &scope.namespace
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 令牌格式: rnt_{id}_{secret}
+pub fn build_token(id: &str, secret: &str) -> String {
+    format!("{}{}_{}", ACCESS_TOKEN_PREFIX, id, secret)
+}
+
+pub fn is_access_token(token: &str) -> bool {
+    token.starts_with(ACCESS_TOKEN_PREFIX)
+}
+
+pub fn parse_token_id(token: &str) -> Option<&str> {
+    token
+        .strip_prefix(ACCESS_TOKEN_PREFIX)
+        .and_then(|v| v.split_once('_'))
+        .map(|(id, _)| id)
+}
+
+pub fn build_last_used_key(id: &str) -> String {
+    format!("{}{}", id, LAST_USED_KEY_SUFFIX)
+}
+
+pub fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+#[derive(Message, Clone, Debug)]
+#[rtype(result = "anyhow::Result<AccessTokenResult>")]
+pub enum AccessTokenReq {
+    /// 校验令牌,并记录最近使用时间
+    Check(Arc<String>),
+    /// raft数据变更通知
+    NotifyChange {
+        key: Vec<u8>,
+        value: Option<Vec<u8>>,
+    },
+    QueryList {
+        username: Option<Arc<String>>,
+    },
+    Query(Arc<String>),
+}
+
+#[derive(Clone, Debug)]
+pub enum AccessTokenResult {
+    None,
+    Session(Option<Arc<TokenSession>>),
+    List(Vec<AccessTokenDto>),
+    Token(Option<Arc<AccessTokenDo>>),
+}
@@ -0,0 +1,556 @@
+use crate::alert::model::{
+    AlertChannel, AlertHistoryDo, AlertHistoryParam, AlertReq, AlertResult, AlertRuleDo,
+    AlertRuleType, AlertStatus,
+};
+use crate::alert::notify::{AlertNotifier, SmtpConfig};
+use crate::common::constant::ALERT_HISTORY_TABLE_NAME;
+use crate::common::AppSysConfig;
+use crate::health::core::HealthManager;
+use crate::health::model::{HealthManagerRequest, HealthManagerResponse};
+use crate::metrics::core::MetricsManager;
+use crate::metrics::labeled::MetricsLabels;
+use crate::metrics::metrics_key::MetricsKey;
+use crate::metrics::model::{MetricsRequest, MetricsResponse};
+use crate::naming::core::{NamingActor, NamingCmd, NamingResult};
+use crate::naming::model::ServiceKey;
+use crate::raft::db::route::TableRoute;
+use crate::raft::db::table::TableManagerReq;
+use crate::raft::NacosRaft;
+use actix::prelude::*;
+use bean_factory::{bean, BeanFactory, FactoryData, Inject};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+/// 集群保留的告警历史最大条数,超过后由主节点删除最早的记录
+const MAX_ALERT_HISTORY: usize = 1000;
+
+#[derive(Debug, Default, Clone)]
+struct AlertState {
+    match_count: u32,
+    firing: bool,
+}
+
+/// 一个规则在一次检查中的取值,target为告警对象
+type RuleSamples = (Arc<String>, Vec<(Arc<String>, f64)>);
+
+///
+/// 告警规则管理与检查
+/// 规则与告警历史通过raft同步到各节点;服务实例与raft复制延迟规则只在主节点检查,
+/// 本节点的监控指标与模块健康规则在每个节点检查
+#[bean(inject)]
+pub struct AlertManager {
+    rules: HashMap<Arc<String>, Arc<AlertRuleDo>>,
+    history: BTreeMap<Arc<String>, Arc<AlertHistoryDo>>,
+    /// rule_id -> target -> 状态
+    states: HashMap<Arc<String>, HashMap<Arc<String>, AlertState>>,
+    raft: Option<Weak<NacosRaft>>,
+    metrics_manager: Option<Addr<MetricsManager>>,
+    naming_actor: Option<Addr<NamingActor>>,
+    health_manager: Option<Addr<HealthManager>>,
+    raft_table_route: Option<Arc<TableRoute>>,
+    notifier: AlertNotifier,
+    node_id: u64,
+    eval_interval_second: u64,
+}
+
+impl AlertManager {
+    pub fn new(sys_config: &AppSysConfig) -> Self {
+        Self {
+            rules: Default::default(),
+            history: Default::default(),
+            states: Default::default(),
+            raft: None,
+            metrics_manager: None,
+            naming_actor: None,
+            health_manager: None,
+            raft_table_route: None,
+            notifier: AlertNotifier::new(SmtpConfig::from_sys_config(sys_config)),
+            node_id: sys_config.raft_node_id,
+            eval_interval_second: sys_config.alert_eval_interval_second,
+        }
+    }
+
+    fn is_leader(&self) -> bool {
+        if let Some(raft) = self.raft.as_ref().and_then(|e| e.upgrade()) {
+            raft.metrics().borrow().state.is_leader()
+        } else {
+            false
+        }
+    }
+
+    fn notify_rule_change(&mut self, key: Vec<u8>, value: Option<Vec<u8>>) {
+        let id = Arc::new(String::from_utf8_lossy(&key).to_string());
+        match value {
+            Some(value) => match AlertRuleDo::from_bytes(&value) {
+                Ok(item) => {
+                    // 规则变更后重新计算状态
+                    self.states.remove(&id);
+                    self.rules.insert(id, Arc::new(item));
+                }
+                Err(err) => {
+                    log::error!("parse alert rule error:{}", err);
+                }
+            },
+            None => {
+                self.states.remove(&id);
+                self.rules.remove(&id);
+            }
+        }
+    }
+
+    fn notify_history_change(&mut self, key: Vec<u8>, value: Option<Vec<u8>>) {
+        let id = Arc::new(String::from_utf8_lossy(&key).to_string());
+        match value {
+            Some(value) => match AlertHistoryDo::from_bytes(&value) {
+                Ok(item) => {
+                    self.history.insert(id, Arc::new(item));
+                }
+                Err(err) => {
+                    log::error!("parse alert history error:{}", err);
+                }
+            },
+            None => {
+                self.history.remove(&id);
+            }
+        }
+    }
+
+    fn query_rule_list(&self) -> Vec<Arc<AlertRuleDo>> {
+        let mut list: Vec<Arc<AlertRuleDo>> = self.rules.values().cloned().collect();
+        list.sort_by_key(|e| std::cmp::Reverse(e.create_time));
+        list
+    }
+
+    fn query_history_page(&self, param: &AlertHistoryParam) -> (usize, Vec<Arc<AlertHistoryDo>>) {
+        let page_size = param.page_size.unwrap_or(20).max(1);
+        let offset = (param.page_no.unwrap_or(1).max(1) - 1) * page_size;
+        let list: Vec<&Arc<AlertHistoryDo>> = self
+            .history
+            .values()
+            .rev()
+            .filter(|e| {
+                param
+                    .rule_id
+                    .as_ref()
+                    .map(|v| v.is_empty() || v == &e.rule_id)
+                    .unwrap_or(true)
+                    && param.status.map(|v| v == e.status).unwrap_or(true)
+            })
+            .collect();
+        let total = list.len();
+        let page = list
+            .into_iter()
+            .skip(offset)
+            .take(page_size)
+            .cloned()
+            .collect();
+        (total, page)
+    }
+
+    fn active_rules(&self, is_leader: bool) -> Vec<Arc<AlertRuleDo>> {
+        self.rules
+            .values()
+            .filter(|e| e.enabled && (is_leader || !e.rule_type.is_leader_only()))
+            .cloned()
+            .collect()
+    }
+
+    async fn collect_samples(
+        rules: Vec<Arc<AlertRuleDo>>,
+        node_id: u64,
+        metrics_manager: Option<Addr<MetricsManager>>,
+        naming_actor: Option<Addr<NamingActor>>,
+        health_manager: Option<Addr<HealthManager>>,
+    ) -> anyhow::Result<Vec<RuleSamples>> {
+        let need_metrics = rules.iter().any(|e| {
+            matches!(
+                e.rule_type,
+                AlertRuleType::Metrics | AlertRuleType::RaftReplicationLag
+            )
+        });
+        let (values, labeled_gauges) = match (&metrics_manager, need_metrics) {
+            (Some(metrics_manager), true) => {
+                match metrics_manager.send(MetricsRequest::QueryValues).await?? {
+                    MetricsResponse::Values {
+                        values,
+                        labeled_gauges,
+                    } => (values, labeled_gauges),
+                    _ => (HashMap::new(), vec![]),
+                }
+            }
+            _ => (HashMap::new(), vec![]),
+        };
+        let need_health = rules
+            .iter()
+            .any(|e| e.rule_type == AlertRuleType::HealthModule);
+        let module_status = match (&health_manager, need_health) {
+            (Some(health_manager), true) => {
+                match health_manager
+                    .send(HealthManagerRequest::ModuleStatus)
+                    .await??
+                {
+                    HealthManagerResponse::ModuleStatusResult(list) => list,
+                    _ => vec![],
+                }
+            }
+            _ => vec![],
+        };
+        let node_target = Arc::new(format!("node_{}", node_id));
+        let mut result = Vec::with_capacity(rules.len());
+        for rule in rules {
+            let samples = match rule.rule_type {
+                AlertRuleType::ServiceHealthyInstance => {
+                    let (target, count) =
+                        Self::query_healthy_instance(&rule, naming_actor.as_ref()).await?;
+                    vec![(target, count as f64)]
+                }
+                AlertRuleType::RaftReplicationLag => Self::raft_lag_samples(&labeled_gauges),
+                AlertRuleType::Metrics => rule
+                    .metrics_key
+                    .as_ref()
+                    .and_then(|key| MetricsKey::of_key(key))
+                    .and_then(|key| values.get(&key))
+                    .map(|v| vec![(node_target.clone(), *v as f64)])
+                    .unwrap_or_default(),
+                AlertRuleType::HealthModule => module_status
+                    .iter()
+                    .map(|(check_type, status)| {
+                        let target = Arc::new(format!("node_{}/{}", node_id, check_type.name()));
+                        let value = if status.is_error() { 1f64 } else { 0f64 };
+                        (target, value)
+                    })
+                    .collect(),
+            };
+            result.push((rule.id.clone(), samples));
+        }
+        Ok(result)
+    }
+
+    async fn query_healthy_instance(
+        rule: &AlertRuleDo,
+        naming_actor: Option<&Addr<NamingActor>>,
+    ) -> anyhow::Result<(Arc<String>, usize)> {
+        let namespace = rule.namespace.clone().unwrap_or_default();
+        let group_name = rule
+            .group_name
+            .clone()
+            .filter(|e| !e.is_empty())
+            .unwrap_or_else(|| Arc::new("DEFAULT_GROUP".to_owned()));
+        let service_name = rule.service_name.clone().unwrap_or_default();
+        let target = Arc::new(format!(
+            "{}/{}/{}",
+            if namespace.is_empty() {
+                "public"
+            } else {
+                namespace.as_str()
+            },
+            &group_name,
+            &service_name
+        ));
+        let mut count = 0;
+        if let Some(naming_actor) = naming_actor {
+            let key = ServiceKey::new_by_arc(namespace, group_name, service_name);
+            if let NamingResult::InstanceList(list) = naming_actor
+                .send(NamingCmd::QueryAllInstanceList(key))
+                .await??
+            {
+                count = list.iter().filter(|e| e.healthy && e.enabled).count();
+            }
+        }
+        Ok((target, count))
+    }
+
+    fn raft_lag_samples(
+        labeled_gauges: &[(MetricsKey, MetricsLabels, f32)],
+    ) -> Vec<(Arc<String>, f64)> {
+        labeled_gauges
+            .iter()
+            .filter(|(key, _, _)| key == &MetricsKey::RaftReplicationLag)
+            .map(|(_, labels, v)| {
+                let node_id = labels.get_value("node_id").unwrap_or_default();
+                (Arc::new(format!("node_{}", node_id)), *v as f64)
+            })
+            .collect()
+    }
+
+    ///
+    /// 根据检查结果更新告警状态,返回需要通知的触发与恢复事件
+    fn apply_samples(
+        &mut self,
+        samples: Vec<RuleSamples>,
+    ) -> Vec<(AlertHistoryDo, Vec<AlertChannel>)> {
+        let node_id = self.node_id;
+        let mut events = vec![];
+        let mut checked_rules = HashMap::with_capacity(samples.len());
+        for (rule_id, list) in samples {
+            let rule = match self.rules.get(&rule_id) {
+                Some(rule) => rule.clone(),
+                None => continue,
+            };
+            checked_rules.insert(rule_id.clone(), ());
+            let states = self.states.entry(rule_id).or_default();
+            let mut current = HashMap::with_capacity(list.len());
+            for (target, value) in list {
+                let state = states.entry(target.clone()).or_default();
+                if rule.compare_op.compare(value, rule.threshold) {
+                    state.match_count += 1;
+                    if !state.firing && state.match_count >= rule.for_count.max(1) {
+                        state.firing = true;
+                        events.push((
+                            AlertHistoryDo::new(
+                                &rule,
+                                target.clone(),
+                                AlertStatus::Firing,
+                                value,
+                                node_id,
+                            ),
+                            rule.channels.clone(),
+                        ));
+                    }
+                } else {
+                    if state.firing {
+                        events.push((
+                            AlertHistoryDo::new(
+                                &rule,
+                                target.clone(),
+                                AlertStatus::Resolved,
+                                value,
+                                node_id,
+                            ),
+                            rule.channels.clone(),
+                        ));
+                    }
+                    state.match_count = 0;
+                    state.firing = false;
+                }
+                current.insert(target, ());
+            }
+            // 告警对象已不存在(如从节点被移除),按恢复处理
+            states.retain(|target, state| {
+                if current.contains_key(target) {
+                    return true;
+                }
+                if state.firing {
+                    events.push((
+                        AlertHistoryDo::new(
+                            &rule,
+                            target.clone(),
+                            AlertStatus::Resolved,
+                            0f64,
+                            node_id,
+                        ),
+                        rule.channels.clone(),
+                    ));
+                }
+                false
+            });
+        }
+        // 本次未检查的规则(已停用或节点不再是主节点)清除状态
+        self.states.retain(|k, _| checked_rules.contains_key(k));
+        events
+    }
+
+    fn fire_events(
+        &self,
+        events: Vec<(AlertHistoryDo, Vec<AlertChannel>)>,
+        ctx: &mut Context<Self>,
+    ) {
+        for (event, channels) in events {
+            let notifier = self.notifier.clone();
+            let table_route = self.raft_table_route.clone();
+            async move {
+                notifier.notify(&channels, &event).await;
+                if let Some(table_route) = table_route {
+                    let req = TableManagerReq::Set {
+                        table_name: ALERT_HISTORY_TABLE_NAME.clone(),
+                        key: event.id.as_bytes().to_vec(),
+                        value: event.to_bytes()?,
+                        last_seq_id: None,
+                    };
+                    table_route.request(req).await?;
+                }
+                Ok::<(), anyhow::Error>(())
+            }
+            .into_actor(self)
+            .map(|r, _, _| {
+                if let Err(err) = r {
+                    log::warn!("save alert history error,{}", err);
+                }
+            })
+            .spawn(ctx);
+        }
+    }
+
+    fn trim_history(&self, ctx: &mut Context<Self>) {
+        if self.history.len() <= MAX_ALERT_HISTORY {
+            return;
+        }
+        let table_route = match self.raft_table_route.clone() {
+            Some(v) => v,
+            None => return,
+        };
+        let keys: Vec<Arc<String>> = self
+            .history
+            .keys()
+            .take(self.history.len() - MAX_ALERT_HISTORY)
+            .cloned()
+            .collect();
+        async move {
+            for key in keys {
+                let req = TableManagerReq::Remove {
+                    table_name: ALERT_HISTORY_TABLE_NAME.clone(),
+                    key: key.as_bytes().to_vec(),
+                };
+                table_route.request(req).await?;
+            }
+            Ok::<(), anyhow::Error>(())
+        }
+        .into_actor(self)
+        .map(|r, _, _| {
+            if let Err(err) = r {
+                log::warn!("trim alert history error,{}", err);
+            }
+        })
+        .spawn(ctx);
+    }
+
+    fn evaluate(&mut self, ctx: &mut Context<Self>) {
+        let is_leader = self.is_leader();
+        if is_leader {
+            self.trim_history(ctx);
+        }
+        let rules = self.active_rules(is_leader);
+        if rules.is_empty() {
+            self.states.clear();
+            self.hb(ctx);
+            return;
+        }
+        let node_id = self.node_id;
+        let metrics_manager = self.metrics_manager.clone();
+        let naming_actor = self.naming_actor.clone();
+        let health_manager = self.health_manager.clone();
+        async move {
+            Self::collect_samples(
+                rules,
+                node_id,
+                metrics_manager,
+                naming_actor,
+                health_manager,
+            )
+            .await
+        }
+        .into_actor(self)
+        .map(|r, act, ctx| {
+            match r {
+                Ok(samples) => {
+                    let events = act.apply_samples(samples);
+                    act.fire_events(events, ctx);
+                }
+                Err(err) => {
+                    log::warn!("evaluate alert rules error,{}", err);
+                }
+            }
+            act.hb(ctx);
+        })
+        .spawn(ctx);
+    }
+
+    fn hb(&self, ctx: &mut Context<Self>) {
+        ctx.run_later(
+            Duration::from_secs(self.eval_interval_second),
+            |act, ctx| {
+                act.evaluate(ctx);
+            },
+        );
+    }
+}
+
+impl Actor for AlertManager {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _ctx: &mut Self::Context) {
+        log::info!("AlertManager started");
+    }
+}
+
+impl Inject for AlertManager {
+    type Context = Context<Self>;
+
+    fn inject(
+        &mut self,
+        factory_data: FactoryData,
+        _factory: BeanFactory,
+        ctx: &mut Self::Context,
+    ) {
+        let raft: Option<Arc<NacosRaft>> = factory_data.get_bean();
+        self.raft = raft.map(|e| Arc::downgrade(&e));
+        self.metrics_manager = factory_data.get_actor();
+        self.naming_actor = factory_data.get_actor();
+        self.health_manager = factory_data.get_actor();
+        self.raft_table_route = factory_data.get_bean();
+        self.hb(ctx);
+    }
+}
+
+impl Handler<AlertReq> for AlertManager {
+    type Result = anyhow::Result<AlertResult>;
+
+    fn handle(&mut self, msg: AlertReq, _ctx: &mut Self::Context) -> Self::Result {
+        match msg {
+            AlertReq::NotifyRuleChange { key, value } => {
+                self.notify_rule_change(key, value);
+                Ok(AlertResult::None)
+            }
+            AlertReq::NotifyHistoryChange { key, value } => {
+                self.notify_history_change(key, value);
+                Ok(AlertResult::None)
+            }
+            AlertReq::QueryRuleList => Ok(AlertResult::RuleList(self.query_rule_list())),
+            AlertReq::QueryRule(id) => Ok(AlertResult::Rule(self.rules.get(&id).cloned())),
+            AlertReq::QueryHistoryPage(param) => {
+                let (total, list) = self.query_history_page(&param);
+                Ok(AlertResult::HistoryPage(total, list))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alert::model::AlertCompareOp;
+
+    #[test]
+    fn alert_firing_and_resolved() {
+        let mut manager = AlertManager::new(&AppSysConfig::default());
+        let rule = AlertRuleDo {
+            id: Arc::new("r1".to_owned()),
+            name: Arc::new("grpc_conn".to_owned()),
+            rule_type: AlertRuleType::Metrics,
+            compare_op: AlertCompareOp::Gt,
+            threshold: 100f64,
+            for_count: 2,
+            channels: vec![AlertChannel::Log],
+            enabled: true,
+            ..Default::default()
+        };
+        manager.notify_rule_change(b"r1".to_vec(), Some(rule.to_bytes().unwrap()));
+        let target = Arc::new("node_1".to_owned());
+        let sample = |v: f64| vec![(rule.id.clone(), vec![(target.clone(), v)])];
+        assert!(manager.apply_samples(sample(120f64)).is_empty());
+        let events = manager.apply_samples(sample(130f64));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0.status, AlertStatus::Firing);
+        assert!(manager.apply_samples(sample(140f64)).is_empty());
+        let events = manager.apply_samples(sample(50f64));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0.status, AlertStatus::Resolved);
+        manager.apply_samples(sample(120f64));
+        manager.apply_samples(sample(120f64));
+        // 告警对象消失时恢复
+        let events = manager.apply_samples(vec![(rule.id.clone(), vec![])]);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].0.status, AlertStatus::Resolved);
+    }
+}
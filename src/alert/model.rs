@@ -0,0 +1,331 @@
+use crate::now_millis_i64;
+use actix::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AlertRuleType {
+    /// 服务健康实例数,只在主节点检查
+    #[default]
+    ServiceHealthyInstance,
+    /// raft从节点日志复制延迟条数,只在主节点检查
+    RaftReplicationLag,
+    /// 本节点监控指标,如grpc链接数
+    Metrics,
+    /// 本节点HealthManager模块健康状态,模块异常时取值为1
+    HealthModule,
+}
+
+impl AlertRuleType {
+    pub fn get_value(&self) -> &'static str {
+        match self {
+            AlertRuleType::ServiceHealthyInstance => "serviceHealthyInstance",
+            AlertRuleType::RaftReplicationLag => "raftReplicationLag",
+            AlertRuleType::Metrics => "metrics",
+            AlertRuleType::HealthModule => "healthModule",
+        }
+    }
+
+    /// 集群级别的规则只在主节点检查,避免各节点重复告警
+    pub fn is_leader_only(&self) -> bool {
+        matches!(
+            self,
+            AlertRuleType::ServiceHealthyInstance | AlertRuleType::RaftReplicationLag
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AlertCompareOp {
+    #[default]
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl AlertCompareOp {
+    pub fn compare(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            AlertCompareOp::Gt => value > threshold,
+            AlertCompareOp::Ge => value >= threshold,
+            AlertCompareOp::Lt => value < threshold,
+            AlertCompareOp::Le => value <= threshold,
+        }
+    }
+
+    pub fn get_symbol(&self) -> &'static str {
+        match self {
+            AlertCompareOp::Gt => ">",
+            AlertCompareOp::Ge => ">=",
+            AlertCompareOp::Lt => "<",
+            AlertCompareOp::Le => "<=",
+        }
+    }
+}
+
+///
+/// 告警通知渠道
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum AlertChannel {
+    Webhook {
+        url: Arc<String>,
+        /// HMAC-SHA256签名密钥,与webhook订阅的签名方式一致
+        #[serde(default)]
+        secret: Option<Arc<String>>,
+    },
+    Email {
+        to: Vec<Arc<String>>,
+    },
+    Log,
+}
+
+impl AlertChannel {
+    pub fn check(&self) -> anyhow::Result<()> {
+        match self {
+            AlertChannel::Webhook { url, .. } => {
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    return Err(anyhow::anyhow!(
+                        "webhook url must start with http:// or https://"
+                    ));
+                }
+            }
+            AlertChannel::Email { to } => {
+                if to.is_empty() || to.iter().any(|e| !e.contains('@')) {
+                    return Err(anyhow::anyhow!("email receiver is invalid"));
+                }
+            }
+            AlertChannel::Log => {}
+        }
+        Ok(())
+    }
+}
+
+///
+/// 告警规则
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertRuleDo {
+    pub id: Arc<String>,
+    pub name: Arc<String>,
+    pub rule_type: AlertRuleType,
+    /// 服务健康实例规则的服务信息,命名空间为空表示public
+    pub namespace: Option<Arc<String>>,
+    pub group_name: Option<Arc<String>>,
+    pub service_name: Option<Arc<String>>,
+    /// 监控指标规则的指标名
+    pub metrics_key: Option<Arc<String>>,
+    pub compare_op: AlertCompareOp,
+    pub threshold: f64,
+    /// 连续满足条件的检查次数达到该值才触发告警
+    pub for_count: u32,
+    pub channels: Vec<AlertChannel>,
+    pub enabled: bool,
+    pub op_user: Option<Arc<String>>,
+    pub create_time: i64,
+    pub update_time: i64,
+}
+
+impl AlertRuleDo {
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn from_bytes(v: &[u8]) -> anyhow::Result<Self> {
+        Ok(serde_json::from_slice(v)?)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AlertStatus {
+    #[default]
+    Firing,
+    Resolved,
+}
+
+impl AlertStatus {
+    pub fn get_value(&self) -> &'static str {
+        match self {
+            AlertStatus::Firing => "firing",
+            AlertStatus::Resolved => "resolved",
+        }
+    }
+}
+
+///
+/// 告警触发与恢复记录,同时作为通知内容
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertHistoryDo {
+    pub id: Arc<String>,
+    pub rule_id: Arc<String>,
+    pub rule_name: Arc<String>,
+    pub rule_type: AlertRuleType,
+    /// 告警对象,如服务名、从节点、指标所在节点、模块名
+    pub target: Arc<String>,
+    pub status: AlertStatus,
+    pub value: f64,
+    pub threshold: f64,
+    pub compare_op: AlertCompareOp,
+    /// 检查规则的节点
+    pub node_id: u64,
+    pub event_time: i64,
+}
+
+impl AlertHistoryDo {
+    pub fn new(
+        rule: &AlertRuleDo,
+        target: Arc<String>,
+        status: AlertStatus,
+        value: f64,
+        node_id: u64,
+    ) -> Self {
+        let event_time = now_millis_i64();
+        // 以时间开头,按key排序即为时间顺序
+        let id = Arc::new(format!(
+            "{:016x}-{}",
+            event_time,
+            uuid::Uuid::new_v4().simple()
+        ));
+        Self {
+            id,
+            rule_id: rule.id.clone(),
+            rule_name: rule.name.clone(),
+            rule_type: rule.rule_type,
+            target,
+            status,
+            value,
+            threshold: rule.threshold,
+            compare_op: rule.compare_op,
+            node_id,
+            event_time,
+        }
+    }
+
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn from_bytes(v: &[u8]) -> anyhow::Result<Self> {
+        Ok(serde_json::from_slice(v)?)
+    }
+
+    pub fn get_title(&self) -> String {
+        format!(
+            "[rnacos alert][{}] {} - {}",
+            self.status.get_value(),
+            &self.rule_name,
+            &self.target
+        )
+    }
+
+    pub fn get_message(&self) -> String {
+        format!(
+            "rule:{}, type:{}, target:{}, status:{}, value:{}, condition: {} {}, node:{}",
+            &self.rule_name,
+            self.rule_type.get_value(),
+            &self.target,
+            self.status.get_value(),
+            self.value,
+            self.compare_op.get_symbol(),
+            self.threshold,
+            self.node_id
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertRuleParam {
+    pub id: Option<Arc<String>>,
+    pub name: Option<Arc<String>>,
+    pub rule_type: Option<AlertRuleType>,
+    pub namespace: Option<Arc<String>>,
+    pub group_name: Option<Arc<String>>,
+    pub service_name: Option<Arc<String>>,
+    pub metrics_key: Option<Arc<String>>,
+    pub compare_op: Option<AlertCompareOp>,
+    pub threshold: Option<f64>,
+    pub for_count: Option<u32>,
+    pub channels: Option<Vec<AlertChannel>>,
+    pub enabled: Option<bool>,
+}
+
+impl AlertRuleParam {
+    pub fn get_namespace(&self) -> Option<Arc<String>> {
+        match &self.namespace {
+            Some(v) if v.as_str() == "public" => Some(Arc::new(String::new())),
+            Some(v) => Some(v.clone()),
+            None => None,
+        }
+    }
+
+    pub fn check(&self) -> anyhow::Result<()> {
+        if self.name.as_ref().map(|e| e.is_empty()).unwrap_or(true) {
+            return Err(anyhow::anyhow!("name is empty"));
+        }
+        match self.rule_type {
+            Some(AlertRuleType::ServiceHealthyInstance) => {
+                if self
+                    .service_name
+                    .as_ref()
+                    .map(|e| e.is_empty())
+                    .unwrap_or(true)
+                {
+                    return Err(anyhow::anyhow!("serviceName is empty"));
+                }
+            }
+            Some(AlertRuleType::Metrics) => {
+                let key = self.metrics_key.as_ref().map(|e| e.as_str()).unwrap_or("");
+                if crate::metrics::metrics_key::MetricsKey::of_key(key).is_none() {
+                    return Err(anyhow::anyhow!("metricsKey is invalid: {}", key));
+                }
+            }
+            Some(_) => {}
+            None => return Err(anyhow::anyhow!("ruleType is empty")),
+        }
+        if self.threshold.is_none() {
+            return Err(anyhow::anyhow!("threshold is empty"));
+        }
+        for channel in self.channels.iter().flatten() {
+            channel.check()?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertHistoryParam {
+    pub rule_id: Option<Arc<String>>,
+    pub status: Option<AlertStatus>,
+    pub page_no: Option<usize>,
+    pub page_size: Option<usize>,
+}
+
+#[derive(Message, Clone, Debug)]
+#[rtype(result = "anyhow::Result<AlertResult>")]
+pub enum AlertReq {
+    NotifyRuleChange {
+        key: Vec<u8>,
+        value: Option<Vec<u8>>,
+    },
+    NotifyHistoryChange {
+        key: Vec<u8>,
+        value: Option<Vec<u8>>,
+    },
+    QueryRuleList,
+    QueryRule(Arc<String>),
+    QueryHistoryPage(AlertHistoryParam),
+}
+
+pub enum AlertResult {
+    None,
+    RuleList(Vec<Arc<AlertRuleDo>>),
+    Rule(Option<Arc<AlertRuleDo>>),
+    HistoryPage(usize, Vec<Arc<AlertHistoryDo>>),
+}
@@ -0,0 +1,215 @@
+use crate::alert::model::{AlertChannel, AlertHistoryDo};
+use crate::common::AppSysConfig;
+use crate::webhook::model::{sign_payload, WEBHOOK_EVENT_HEADER, WEBHOOK_SIGNATURE_HEADER};
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::sync::Arc;
+use std::time::Duration;
+
+const NOTIFY_TIMEOUT_SECOND: u64 = 5;
+pub const ALERT_EVENT_NAME: &str = "alert";
+
+#[derive(Clone, Debug, Default)]
+pub struct SmtpConfig {
+    pub host: Arc<String>,
+    pub port: u16,
+    pub username: Arc<String>,
+    pub password: Arc<String>,
+    pub from: Arc<String>,
+    pub tls: Arc<String>,
+}
+
+impl SmtpConfig {
+    pub fn from_sys_config(sys_config: &AppSysConfig) -> Self {
+        Self {
+            host: sys_config.alert_smtp_host.clone(),
+            port: sys_config.alert_smtp_port,
+            username: sys_config.alert_smtp_username.clone(),
+            password: sys_config.alert_smtp_password.clone(),
+            from: sys_config.alert_smtp_from.clone(),
+            tls: sys_config.alert_smtp_tls.clone(),
+        }
+    }
+
+    fn build_transport(&self) -> anyhow::Result<AsyncSmtpTransport<Tokio1Executor>> {
+        let builder = match self.tls.to_ascii_lowercase().as_str() {
+            "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(&self.host)?,
+            "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&self.host)?,
+            _ => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(self.host.as_str()),
+        };
+        let mut builder = builder
+            .port(self.port)
+            .timeout(Some(Duration::from_secs(NOTIFY_TIMEOUT_SECOND)));
+        if !self.username.is_empty() {
+            builder = builder.credentials(Credentials::new(
+                self.username.as_ref().to_owned(),
+                self.password.as_ref().to_owned(),
+            ));
+        }
+        Ok(builder.build())
+    }
+}
+
+///
+/// 告警通知发送
+#[derive(Clone)]
+pub struct AlertNotifier {
+    client: reqwest::Client,
+    smtp_config: Arc<SmtpConfig>,
+}
+
+impl AlertNotifier {
+    pub fn new(smtp_config: SmtpConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(NOTIFY_TIMEOUT_SECOND))
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            smtp_config: Arc::new(smtp_config),
+        }
+    }
+
+    pub async fn notify(&self, channels: &[AlertChannel], event: &AlertHistoryDo) {
+        for channel in channels {
+            let r = match channel {
+                AlertChannel::Webhook { url, secret } => {
+                    self.notify_webhook(url, secret, event).await
+                }
+                AlertChannel::Email { to } => self.notify_email(to, event).await,
+                AlertChannel::Log => {
+                    log::warn!("{}|{}", event.get_title(), event.get_message());
+                    Ok(())
+                }
+            };
+            if let Err(err) = r {
+                log::warn!(
+                    "alert notify error, rule:{}, target:{}, error:{}",
+                    &event.rule_name,
+                    &event.target,
+                    err
+                );
+            }
+        }
+    }
+
+    async fn notify_webhook(
+        &self,
+        url: &str,
+        secret: &Option<Arc<String>>,
+        event: &AlertHistoryDo,
+    ) -> anyhow::Result<()> {
+        let payload = serde_json::to_vec(event)?;
+        let mut req = self
+            .client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .header(WEBHOOK_EVENT_HEADER, ALERT_EVENT_NAME);
+        if let Some(secret) = secret.as_ref().filter(|e| !e.is_empty()) {
+            req = req.header(
+                WEBHOOK_SIGNATURE_HEADER,
+                format!("sha256={}", sign_payload(secret, &payload)),
+            );
+        }
+        let res = req.body(payload).send().await?;
+        if !res.status().is_success() {
+            return Err(anyhow::anyhow!("response status {}", res.status()));
+        }
+        Ok(())
+    }
+
+    async fn notify_email(&self, to: &[Arc<String>], event: &AlertHistoryDo) -> anyhow::Result<()> {
+        if self.smtp_config.host.is_empty() {
+            return Err(anyhow::anyhow!("smtp host is not configured"));
+        }
+        let from: Mailbox = self.smtp_config.from.parse()?;
+        let mut builder = Message::builder().from(from).subject(event.get_title());
+        for item in to {
+            builder = builder.to(item.parse()?);
+        }
+        let message = builder.body(event.get_message())?;
+        self.smtp_config.build_transport()?.send(message).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alert::model::{AlertRuleDo, AlertStatus};
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    /// 本地模拟SMTP服务,返回收到的邮件内容
+    async fn mock_smtp_server(listener: TcpListener) -> String {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut reader = BufReader::new(reader);
+        writer.write_all(b"220 mock smtp\r\n").await.unwrap();
+        let mut data = String::new();
+        let mut in_data = false;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).await.unwrap() == 0 {
+                break;
+            }
+            if in_data {
+                if line == ".\r\n" {
+                    in_data = false;
+                    writer.write_all(b"250 ok\r\n").await.unwrap();
+                } else {
+                    data.push_str(&line);
+                }
+                continue;
+            }
+            let cmd = line.to_ascii_uppercase();
+            if cmd.starts_with("EHLO") {
+                writer.write_all(b"250 mock\r\n").await.unwrap();
+            } else if cmd.starts_with("DATA") {
+                in_data = true;
+                writer.write_all(b"354 go ahead\r\n").await.unwrap();
+            } else if cmd.starts_with("QUIT") {
+                writer.write_all(b"221 bye\r\n").await.unwrap();
+                break;
+            } else {
+                writer.write_all(b"250 ok\r\n").await.unwrap();
+            }
+        }
+        data
+    }
+
+    #[actix_rt::test]
+    async fn notify_email_by_mock_smtp() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(mock_smtp_server(listener));
+        let notifier = AlertNotifier::new(SmtpConfig {
+            host: Arc::new("127.0.0.1".to_owned()),
+            port,
+            from: Arc::new("rnacos@example.com".to_owned()),
+            ..Default::default()
+        });
+        let rule = AlertRuleDo {
+            id: Arc::new("r1".to_owned()),
+            name: Arc::new("grpc_conn".to_owned()),
+            threshold: 100f64,
+            ..Default::default()
+        };
+        let event = AlertHistoryDo::new(
+            &rule,
+            Arc::new("node_1".to_owned()),
+            AlertStatus::Firing,
+            120f64,
+            1,
+        );
+        notifier
+            .notify_email(&[Arc::new("ops@example.com".to_owned())], &event)
+            .await
+            .unwrap();
+        let data = server.await.unwrap();
+        assert!(data.contains("To: ops@example.com"));
+        assert!(data.contains("[rnacos alert][firing] grpc_conn - node_1"));
+    }
+}
@@ -1,8 +1,13 @@
+use crate::access_token::core::AccessTokenManager;
+use crate::alert::core::AlertManager;
 use crate::common::AppSysConfig;
+use crate::config::approval::core::ConfigChangeManager;
 use crate::config::core::ConfigActor;
+use crate::config::schema::core::ConfigSchemaManager;
 use crate::grpc::bistream_manage::BiStreamManage;
 use crate::health::core::HealthManager;
 use crate::ldap::core::LdapManager;
+use crate::limiter::core::RequestLimitManager;
 use crate::mcp::core::McpManager;
 use crate::mcp::sse_manage::SseStreamManager;
 use crate::metrics::core::MetricsManager;
@@ -24,6 +29,7 @@ use crate::sequence::SequenceManager;
 use crate::transfer::reader::TransferImportManager;
 use crate::transfer::writer::TransferWriterManager;
 use crate::user::UserManager;
+use crate::webhook::core::WebhookManager;
 use actix::Addr;
 use bean_factory::FactoryData;
 use chrono::FixedOffset;
@@ -60,5 +66,11 @@ pub struct AppShareData {
     pub sequence_manager: Addr<SequenceManager>,
     pub mcp_manager: Addr<McpManager>,
     pub sse_stream_manager: Addr<SseStreamManager>,
+    pub request_limit_manager: Addr<RequestLimitManager>,
+    pub access_token_manager: Addr<AccessTokenManager>,
+    pub config_schema_manager: Addr<ConfigSchemaManager>,
+    pub config_change_manager: Addr<ConfigChangeManager>,
+    pub webhook_manager: Addr<WebhookManager>,
+    pub alert_manager: Addr<AlertManager>,
     pub common_client: reqwest::Client,
 }
@@ -34,4 +34,12 @@ lazy_static::lazy_static! {
     pub static ref DEFAULT_NAMESPACE_ARC_STRING: Arc<String> = Arc::new("".to_string());
     pub static ref EMPTY_CLIENT_VERSION: Arc<ClientVersion> = Arc::new(ClientVersion::default());
     pub static ref NAMING_INSTANCE_TABLE: Arc<String> = Arc::new("T_NAMING_INSTANCE".to_string());
+    pub static ref SYS_CONFIG_TABLE_NAME: Arc<String> = Arc::new("T_SYS_CONFIG".to_string());
+    pub static ref ACCESS_TOKEN_TABLE_NAME: Arc<String> = Arc::new("T_ACCESS_TOKEN".to_string());
+    pub static ref CONFIG_SCHEMA_TABLE_NAME: Arc<String> = Arc::new("T_CONFIG_SCHEMA".to_string());
+    pub static ref CONFIG_CHANGE_TABLE_NAME: Arc<String> = Arc::new("T_CONFIG_CHANGE".to_string());
+    pub static ref WEBHOOK_TABLE_NAME: Arc<String> = Arc::new("T_WEBHOOK".to_string());
+    pub static ref CONFIG_LOCK_TABLE_NAME: Arc<String> = Arc::new("T_CONFIG_LOCK".to_string());
+    pub static ref ALERT_RULE_TABLE_NAME: Arc<String> = Arc::new("T_ALERT_RULE".to_string());
+    pub static ref ALERT_HISTORY_TABLE_NAME: Arc<String> = Arc::new("T_ALERT_HISTORY".to_string());
 }
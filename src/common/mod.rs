@@ -1,9 +1,12 @@
 use crate::common::string_utils::StringUtils;
+use crate::config::validate::ConfigValidateMode;
 use crate::ldap::model::LdapConfig;
+use crate::limiter::model::RequestLimitConfig;
+use crate::naming::health_history::FlapConfig;
 use crate::oauth2::model::OAuth2Config;
 use crate::user::permission;
 use crate::user::permission::UserRoleHelper;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -51,6 +54,7 @@ pub struct NamingSysConfig {
     pub instance_timeout_millis: i64,
     /// 嗅探检测间隔
     pub perpetual_instance_probe_interval: i32,
+    pub flap_config: FlapConfig,
 }
 
 impl NamingSysConfig {
@@ -62,6 +66,7 @@ impl NamingSysConfig {
             instance_health_timeout_millis: 18000,
             instance_timeout_millis: 33000,
             perpetual_instance_probe_interval: 60,
+            flap_config: FlapConfig::default(),
         }
     }
 }
@@ -95,12 +100,23 @@ pub struct AppSysConfig {
     pub metrics_collect_interval_second: u64,
     pub metrics_log_interval_second: u64,
     pub metrics_log_enable: bool,
+    /// 带标签指标每个标签名最多保留的取值数量
+    pub metrics_label_max_values: usize,
+    /// 是否把监控时间线持久化到本地磁盘,重启后可继续展示
+    pub metrics_timeline_persist_enable: bool,
+    /// 小时级监控时间线保留的小时数
+    pub metrics_timeline_retention_hour: usize,
     pub console_captcha_enable: bool,
     pub run_in_docker: bool,
     pub naming_health_timeout: u64,
     pub naming_instance_timeout: u64,
     /// 持久化服务检测间隔，小于或等于0表示不开启
     pub naming_perpetual_instance_probe_interval: i32,
+    /// 检测窗口内实例健康状态切换次数超过该值视为抖动,0表示不检测
+    pub naming_flap_threshold: usize,
+    pub naming_flap_window_second: u64,
+    /// 抖动实例的隔离时长,隔离期内保持不健康;0表示只标记不隔离
+    pub naming_flap_quarantine_second: u64,
     pub ldap_enable: bool,
     pub ldap_url: Arc<String>,
     pub ldap_user_base_dn: Arc<String>,
@@ -122,6 +138,51 @@ pub struct AppSysConfig {
     pub oauth2_nickname_claim_name: Arc<String>,
     pub oauth2_user_default_role: Arc<String>,
     pub oauth2_button: Arc<String>,
+    pub oauth2_oidc_issuer: Arc<String>,
+    pub oauth2_groups_claim_name: Arc<String>,
+    pub oauth2_developer_groups: Arc<HashSet<String>>,
+    pub oauth2_admin_groups: Arc<HashSet<String>>,
+    pub oauth2_group_namespaces: Arc<HashMap<String, Vec<Arc<String>>>>,
+    pub grpc_conn_per_ip_limit: u32,
+    pub request_ip_qps_limit: u32,
+    pub request_type_qps_limit: Arc<HashMap<String, u32>>,
+    pub config_validate_mode: ConfigValidateMode,
+    pub config_validate_namespace_modes: Arc<HashMap<String, ConfigValidateMode>>,
+    /// 需要审批才能发布配置的命名空间
+    pub config_approval_namespaces: Arc<HashSet<String>>,
+    /// webhook推送失败后的最大重试次数
+    pub webhook_max_retry: u32,
+    /// 是否开启OpenTelemetry链路导出
+    pub otel_enable: bool,
+    /// OTLP collector地址,为空时按协议使用本机默认端口
+    pub otel_endpoint: Arc<String>,
+    /// OTLP上报协议,支持http、grpc
+    pub otel_protocol: Arc<String>,
+    /// 链路采样比例,请求已带采样标记时沿用上游的采样结果
+    pub otel_sample_ratio: f64,
+    pub otel_service_name: Arc<String>,
+    /// 告警规则检查间隔,单位秒
+    pub alert_eval_interval_second: u64,
+    /// 告警邮件SMTP服务地址,为空时不发送邮件
+    pub alert_smtp_host: Arc<String>,
+    pub alert_smtp_port: u16,
+    pub alert_smtp_username: Arc<String>,
+    pub alert_smtp_password: Arc<String>,
+    pub alert_smtp_from: Arc<String>,
+    /// SMTP加密方式,支持none、starttls、tls
+    pub alert_smtp_tls: Arc<String>,
+    /// 日志格式,支持text、json
+    pub log_format: Arc<String>,
+    /// 日志文件目录,为空时输出到标准输出
+    pub log_dir: Arc<String>,
+    /// 单个日志文件大小上限,单位MB,0表示不按大小切割
+    pub log_rotate_size_mb: u64,
+    /// 日志按时间切割周期,支持none、hour、day
+    pub log_rotate_time: Arc<String>,
+    /// 每类日志保留的历史文件数
+    pub log_max_files: usize,
+    /// 是否开启http、grpc请求访问日志
+    pub access_log_enable: bool,
 }
 
 impl AppSysConfig {
@@ -241,6 +302,21 @@ impl AppSysConfig {
         if metrics_log_interval_second < metrics_collect_interval_second {
             metrics_collect_interval_second = metrics_log_interval_second;
         }
+        let metrics_label_max_values = std::env::var("RNACOS_METRICS_LABEL_MAX_VALUES")
+            .unwrap_or("200".to_owned())
+            .parse()
+            .unwrap_or(200);
+        let metrics_timeline_persist_enable =
+            std::env::var("RNACOS_METRICS_TIMELINE_PERSIST_ENABLE")
+                .unwrap_or("true".to_owned())
+                .parse()
+                .unwrap_or(true);
+        let metrics_timeline_retention_hour: usize =
+            std::env::var("RNACOS_METRICS_TIMELINE_RETENTION_HOUR")
+                .unwrap_or("360".to_owned())
+                .parse()
+                .unwrap_or(360);
+        let metrics_timeline_retention_hour = metrics_timeline_retention_hour.max(1);
         let naming_health_timeout = std::env::var("RNACOS_NAMING_HEALTH_TIMEOUT_SECOND")
             .unwrap_or("15".to_owned())
             .parse()
@@ -265,6 +341,19 @@ impl AppSysConfig {
         {
             naming_perpetual_instance_probe_interval = 5;
         }
+        let naming_flap_threshold = std::env::var("RNACOS_NAMING_FLAP_THRESHOLD")
+            .unwrap_or("5".to_owned())
+            .parse()
+            .unwrap_or(5usize);
+        let naming_flap_window_second = std::env::var("RNACOS_NAMING_FLAP_WINDOW_SECOND")
+            .unwrap_or("300".to_owned())
+            .parse()
+            .unwrap_or(300u64)
+            .max(1);
+        let naming_flap_quarantine_second = std::env::var("RNACOS_NAMING_FLAP_QUARANTINE_SECOND")
+            .unwrap_or("0".to_owned())
+            .parse()
+            .unwrap_or(0u64);
         let ldap_enable = std::env::var("RNACOS_LDAP_ENABLE")
             .unwrap_or("false".to_owned())
             .parse()
@@ -307,22 +396,35 @@ impl AppSysConfig {
         let oauth2_client_secret = std::env::var("RNACOS_OAUTH2_CLIENT_SECRET")
             .map(Arc::new)
             .unwrap_or(constant::EMPTY_ARC_STRING.clone());
+        let oauth2_oidc_issuer = std::env::var("RNACOS_OAUTH2_OIDC_ISSUER")
+            .map(Arc::new)
+            .unwrap_or(constant::EMPTY_ARC_STRING.clone());
         // OAuth2 endpoints should be full URLs
+        // 开启OIDC时,未配置的端点由discovery补全
         let oauth2_authorization_url = std::env::var("RNACOS_OAUTH2_AUTHORIZATION_URL")
             .map(Arc::new)
             .unwrap_or_else(|_| {
+                if !oauth2_oidc_issuer.is_empty() {
+                    return constant::EMPTY_ARC_STRING.clone();
+                }
                 let server_url = std::env::var("RNACOS_OAUTH2_SERVER_URL").unwrap_or_default();
                 Arc::new(format!("{}/oauth/authorize", server_url))
             });
         let oauth2_token_url = std::env::var("RNACOS_OAUTH2_TOKEN_URL")
             .map(Arc::new)
             .unwrap_or_else(|_| {
+                if !oauth2_oidc_issuer.is_empty() {
+                    return constant::EMPTY_ARC_STRING.clone();
+                }
                 let server_url = std::env::var("RNACOS_OAUTH2_SERVER_URL").unwrap_or_default();
                 Arc::new(format!("{}/oauth/token", server_url))
             });
         let oauth2_userinfo_url = std::env::var("RNACOS_OAUTH2_USERINFO_URL")
             .map(Arc::new)
             .unwrap_or_else(|_| {
+                if !oauth2_oidc_issuer.is_empty() {
+                    return constant::EMPTY_ARC_STRING.clone();
+                }
                 let server_url = std::env::var("RNACOS_OAUTH2_SERVER_URL").unwrap_or_default();
                 Arc::new(format!("{}/oauth/userinfo", server_url))
             });
@@ -347,6 +449,105 @@ impl AppSysConfig {
         let oauth2_button = std::env::var("RNACOS_OAUTH2_BUTTON")
             .map(Arc::new)
             .unwrap_or_else(|_| Arc::new("OAuth2.0 登录".to_string()));
+        let oauth2_groups_claim_name = std::env::var("RNACOS_OAUTH2_GROUPS_CLAIM_NAME")
+            .map(Arc::new)
+            .unwrap_or_else(|_| Arc::new("groups".to_string()));
+        let oauth2_developer_groups = Arc::new(
+            StringUtils::split_to_hashset(
+                &std::env::var("RNACOS_OAUTH2_DEVELOPER_GROUPS").unwrap_or_default(),
+            )
+            .into_iter()
+            .map(|s| s.trim_start_matches('/').to_owned())
+            .collect(),
+        );
+        let oauth2_admin_groups = Arc::new(
+            StringUtils::split_to_hashset(
+                &std::env::var("RNACOS_OAUTH2_ADMIN_GROUPS").unwrap_or_default(),
+            )
+            .into_iter()
+            .map(|s| s.trim_start_matches('/').to_owned())
+            .collect(),
+        );
+        let oauth2_group_namespaces = Arc::new(OAuth2Config::parse_group_namespaces(
+            &std::env::var("RNACOS_OAUTH2_GROUP_NAMESPACES").unwrap_or_default(),
+        ));
+        let grpc_conn_per_ip_limit = std::env::var("RNACOS_GRPC_CONN_PER_IP_LIMIT")
+            .unwrap_or("0".to_owned())
+            .parse()
+            .unwrap_or(0);
+        let request_ip_qps_limit = std::env::var("RNACOS_REQUEST_IP_QPS_LIMIT")
+            .unwrap_or("0".to_owned())
+            .parse()
+            .unwrap_or(0);
+        let request_type_qps_limit = Arc::new(RequestLimitConfig::parse_type_qps(
+            &std::env::var("RNACOS_REQUEST_TYPE_QPS_LIMIT").unwrap_or_default(),
+        ));
+        let config_validate_mode = ConfigValidateMode::new_by_value(
+            &std::env::var("RNACOS_CONFIG_VALIDATE_MODE").unwrap_or_default(),
+        )
+        .unwrap_or_default();
+        let config_validate_namespace_modes = Arc::new(ConfigValidateMode::parse_namespace_modes(
+            &std::env::var("RNACOS_CONFIG_VALIDATE_NAMESPACE_MODES").unwrap_or_default(),
+        ));
+        let config_approval_namespaces = Arc::new(
+            StringUtils::split_to_hashset(
+                &std::env::var("RNACOS_CONFIG_APPROVAL_NAMESPACES").unwrap_or_default(),
+            )
+            .into_iter()
+            .map(|s| if s == "public" { String::new() } else { s })
+            .collect(),
+        );
+        let webhook_max_retry = std::env::var("RNACOS_WEBHOOK_MAX_RETRY")
+            .unwrap_or("3".to_owned())
+            .parse()
+            .unwrap_or(3);
+        let otel_enable = std::env::var("RNACOS_OTEL_ENABLE")
+            .unwrap_or("false".to_owned())
+            .parse()
+            .unwrap_or(false);
+        let otel_endpoint = Arc::new(std::env::var("RNACOS_OTEL_ENDPOINT").unwrap_or_default());
+        let otel_protocol =
+            Arc::new(std::env::var("RNACOS_OTEL_PROTOCOL").unwrap_or("http".to_owned()));
+        let otel_sample_ratio = std::env::var("RNACOS_OTEL_SAMPLE_RATIO")
+            .unwrap_or("0.1".to_owned())
+            .parse()
+            .unwrap_or(0.1f64)
+            .clamp(0f64, 1f64);
+        let otel_service_name =
+            Arc::new(std::env::var("RNACOS_OTEL_SERVICE_NAME").unwrap_or("rnacos".to_owned()));
+        let alert_eval_interval_second = std::env::var("RNACOS_ALERT_EVAL_INTERVAL_SECOND")
+            .unwrap_or("15".to_owned())
+            .parse()
+            .unwrap_or(15u64)
+            .max(1);
+        let alert_smtp_host = Arc::new(std::env::var("RNACOS_ALERT_SMTP_HOST").unwrap_or_default());
+        let alert_smtp_port = std::env::var("RNACOS_ALERT_SMTP_PORT")
+            .unwrap_or("25".to_owned())
+            .parse()
+            .unwrap_or(25);
+        let alert_smtp_username =
+            Arc::new(std::env::var("RNACOS_ALERT_SMTP_USERNAME").unwrap_or_default());
+        let alert_smtp_password =
+            Arc::new(std::env::var("RNACOS_ALERT_SMTP_PASSWORD").unwrap_or_default());
+        let alert_smtp_from = Arc::new(std::env::var("RNACOS_ALERT_SMTP_FROM").unwrap_or_default());
+        let alert_smtp_tls =
+            Arc::new(std::env::var("RNACOS_ALERT_SMTP_TLS").unwrap_or("none".to_owned()));
+        let log_format = Arc::new(std::env::var("RNACOS_LOG_FORMAT").unwrap_or("text".to_owned()));
+        let log_dir = Arc::new(std::env::var("RNACOS_LOG_DIR").unwrap_or_default());
+        let log_rotate_size_mb = std::env::var("RNACOS_LOG_ROTATE_SIZE_MB")
+            .unwrap_or("100".to_owned())
+            .parse()
+            .unwrap_or(100u64);
+        let log_rotate_time =
+            Arc::new(std::env::var("RNACOS_LOG_ROTATE_TIME").unwrap_or("day".to_owned()));
+        let log_max_files = std::env::var("RNACOS_LOG_MAX_FILES")
+            .unwrap_or("10".to_owned())
+            .parse()
+            .unwrap_or(10usize);
+        let access_log_enable = std::env::var("RNACOS_ACCESS_LOG_ENABLE")
+            .unwrap_or("false".to_owned())
+            .parse()
+            .unwrap_or(false);
         Self {
             local_db_dir,
             config_db_file,
@@ -375,11 +576,17 @@ impl AppSysConfig {
             metrics_log_enable,
             metrics_collect_interval_second,
             metrics_log_interval_second,
+            metrics_label_max_values,
+            metrics_timeline_persist_enable,
+            metrics_timeline_retention_hour,
             console_captcha_enable,
             run_in_docker,
             naming_health_timeout,
             naming_instance_timeout,
             naming_perpetual_instance_probe_interval,
+            naming_flap_threshold,
+            naming_flap_window_second,
+            naming_flap_quarantine_second,
             ldap_enable,
             ldap_url,
             ldap_user_base_dn,
@@ -401,6 +608,36 @@ impl AppSysConfig {
             oauth2_nickname_claim_name,
             oauth2_user_default_role,
             oauth2_button,
+            oauth2_oidc_issuer,
+            oauth2_groups_claim_name,
+            oauth2_developer_groups,
+            oauth2_admin_groups,
+            oauth2_group_namespaces,
+            grpc_conn_per_ip_limit,
+            request_ip_qps_limit,
+            request_type_qps_limit,
+            config_validate_mode,
+            config_validate_namespace_modes,
+            config_approval_namespaces,
+            webhook_max_retry,
+            otel_enable,
+            otel_endpoint,
+            otel_protocol,
+            otel_sample_ratio,
+            otel_service_name,
+            alert_eval_interval_second,
+            alert_smtp_host,
+            alert_smtp_port,
+            alert_smtp_username,
+            alert_smtp_password,
+            alert_smtp_from,
+            alert_smtp_tls,
+            log_format,
+            log_dir,
+            log_rotate_size_mb,
+            log_rotate_time,
+            log_max_files,
+            access_log_enable,
         }
     }
 
@@ -462,8 +699,34 @@ impl AppSysConfig {
             oauth2_username_claim_name: self.oauth2_username_claim_name.clone(),
             oauth2_nickname_claim_name: self.oauth2_nickname_claim_name.clone(),
             oauth2_user_default_role: self.oauth2_user_default_role.clone(),
+            oauth2_oidc_issuer: self.oauth2_oidc_issuer.clone(),
+            oauth2_jwks_url: constant::EMPTY_ARC_STRING.clone(),
+            oauth2_groups_claim_name: self.oauth2_groups_claim_name.clone(),
+            oauth2_developer_groups: self.oauth2_developer_groups.clone(),
+            oauth2_admin_groups: self.oauth2_admin_groups.clone(),
+            oauth2_group_namespaces: self.oauth2_group_namespaces.clone(),
         })
     }
+
+    /// 获取命名空间的配置内容校验模式,未单独设置时使用默认模式
+    pub fn get_config_validate_mode(&self, tenant: &str) -> ConfigValidateMode {
+        self.config_validate_namespace_modes
+            .get(tenant)
+            .cloned()
+            .unwrap_or(self.config_validate_mode)
+    }
+
+    pub fn need_config_approval(&self, tenant: &str) -> bool {
+        self.config_approval_namespaces.contains(tenant)
+    }
+
+    pub fn get_request_limit_config(&self) -> RequestLimitConfig {
+        RequestLimitConfig {
+            grpc_conn_per_ip: self.grpc_conn_per_ip_limit,
+            ip_qps: self.request_ip_qps_limit,
+            request_type_qps: self.request_type_qps_limit.as_ref().clone(),
+        }
+    }
 }
 
 /**
@@ -3,6 +3,7 @@ pub mod privilege;
 
 use std::{collections::HashMap, sync::Arc};
 
+use crate::access_token::model::AccessTokenScope;
 pub use crate::common::model::client_version::ClientVersion;
 use crate::common::model::privilege::PrivilegeGroup;
 use serde::{Deserialize, Serialize};
@@ -104,4 +105,7 @@ pub struct TokenSession {
     pub username: Arc<String>,
     pub roles: Vec<Arc<String>>,
     pub extend_infos: HashMap<String, String>,
+    /// 个人访问令牌的权限范围,登录生成的token为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_scopes: Option<Arc<Vec<AccessTokenScope>>>,
 }
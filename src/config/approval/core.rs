@@ -0,0 +1,116 @@
+use crate::config::approval::model::{
+    ConfigChangeDo, ConfigChangeReq, ConfigChangeResult, ConfigChangeStatus,
+};
+use actix::prelude::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+///
+/// 配置变更审批管理器
+/// 变更记录通过raft同步到各节点,本地只做内存查询
+#[derive(Default)]
+pub struct ConfigChangeManager {
+    changes: HashMap<Arc<String>, Arc<ConfigChangeDo>>,
+}
+
+impl ConfigChangeManager {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    fn notify_change(&mut self, key: Vec<u8>, value: Option<Vec<u8>>) {
+        let id = Arc::new(String::from_utf8_lossy(&key).to_string());
+        match value {
+            Some(value) => match ConfigChangeDo::from_bytes(&value) {
+                Ok(item) => {
+                    self.changes.insert(id, Arc::new(item));
+                }
+                Err(err) => {
+                    log::error!("parse config change error:{}", err);
+                }
+            },
+            None => {
+                self.changes.remove(&id);
+            }
+        }
+    }
+
+    fn query_list(
+        &self,
+        tenant: &Option<Arc<String>>,
+        status: &Option<ConfigChangeStatus>,
+    ) -> Vec<Arc<ConfigChangeDo>> {
+        let mut list: Vec<Arc<ConfigChangeDo>> = self
+            .changes
+            .values()
+            .filter(|e| tenant.as_ref().map(|t| t == &e.tenant).unwrap_or(true))
+            .filter(|e| status.as_ref().map(|s| s == &e.status).unwrap_or(true))
+            .cloned()
+            .collect();
+        list.sort_by_key(|e| std::cmp::Reverse(e.submit_time));
+        list
+    }
+}
+
+impl Actor for ConfigChangeManager {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _ctx: &mut Self::Context) {
+        log::info!("ConfigChangeManager started");
+    }
+}
+
+impl Handler<ConfigChangeReq> for ConfigChangeManager {
+    type Result = anyhow::Result<ConfigChangeResult>;
+
+    fn handle(&mut self, msg: ConfigChangeReq, _ctx: &mut Self::Context) -> Self::Result {
+        match msg {
+            ConfigChangeReq::NotifyChange { key, value } => {
+                self.notify_change(key, value);
+                Ok(ConfigChangeResult::None)
+            }
+            ConfigChangeReq::QueryList { tenant, status } => {
+                Ok(ConfigChangeResult::List(self.query_list(&tenant, &status)))
+            }
+            ConfigChangeReq::Query(id) => {
+                Ok(ConfigChangeResult::Change(self.changes.get(&id).cloned()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_change(id: &str, tenant: &str, status: ConfigChangeStatus, time: i64) -> Vec<u8> {
+        ConfigChangeDo {
+            id: Arc::new(id.to_owned()),
+            tenant: Arc::new(tenant.to_owned()),
+            status,
+            submit_time: time,
+            ..Default::default()
+        }
+        .to_bytes()
+        .unwrap()
+    }
+
+    #[test]
+    fn query_change_list() {
+        let mut manager = ConfigChangeManager::new();
+        let pending = ConfigChangeStatus::Pending;
+        manager.notify_change(b"c1".to_vec(), Some(build_change("c1", "prod", pending, 1)));
+        manager.notify_change(b"c2".to_vec(), Some(build_change("c2", "prod", pending, 2)));
+        manager.notify_change(
+            b"c3".to_vec(),
+            Some(build_change("c3", "dev", ConfigChangeStatus::Approved, 3)),
+        );
+        let prod = Some(Arc::new("prod".to_owned()));
+        let list = manager.query_list(&prod, &Some(pending));
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0].id.as_str(), "c2");
+        assert_eq!(manager.query_list(&None, &None).len(), 3);
+        manager.notify_change(b"c2".to_vec(), None);
+        assert_eq!(manager.query_list(&prod, &None).len(), 1);
+    }
+}
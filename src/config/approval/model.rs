@@ -0,0 +1,122 @@
+use crate::config::core::ConfigKey;
+use crate::config::ConfigUtils;
+use actix::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfigChangeStatus {
+    #[default]
+    Pending,
+    Approved,
+    Rejected,
+}
+
+///
+/// 待审批的配置变更,审批通过后才真正发布配置
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigChangeDo {
+    pub id: Arc<String>,
+    pub tenant: Arc<String>,
+    pub group: Arc<String>,
+    pub data_id: Arc<String>,
+    pub content: Arc<String>,
+    pub config_type: Option<Arc<String>>,
+    pub desc: Option<Arc<String>>,
+    /// 提交时配置内容的md5,用于审批时判断配置是否已被其它变更修改
+    pub base_md5: Arc<String>,
+    pub status: ConfigChangeStatus,
+    pub submit_user: Arc<String>,
+    pub submit_time: i64,
+    pub review_user: Option<Arc<String>>,
+    pub review_time: i64,
+    pub review_comment: Option<Arc<String>>,
+}
+
+impl ConfigChangeDo {
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn from_bytes(v: &[u8]) -> anyhow::Result<Self> {
+        Ok(serde_json::from_slice(v)?)
+    }
+
+    pub fn get_key(&self) -> ConfigKey {
+        ConfigKey::new_by_arc(
+            self.data_id.clone(),
+            self.group.clone(),
+            self.tenant.clone(),
+        )
+    }
+}
+
+///
+/// 配置变更详情,包含当前配置内容与差异
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigChangeInfo {
+    pub change: Arc<ConfigChangeDo>,
+    pub current_content: Arc<String>,
+    pub current_md5: Arc<String>,
+    /// 当前配置内容到变更内容的unified diff
+    pub diff: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigChangeParam {
+    pub id: Option<Arc<String>>,
+    pub data_id: Option<Arc<String>>,
+    pub group: Option<Arc<String>>,
+    pub tenant: Option<String>,
+    pub content: Option<Arc<String>>,
+    pub config_type: Option<Arc<String>>,
+    pub desc: Option<Arc<String>>,
+    pub status: Option<ConfigChangeStatus>,
+    pub comment: Option<Arc<String>>,
+}
+
+impl ConfigChangeParam {
+    pub fn get_tenant(&self) -> Option<Arc<String>> {
+        self.tenant
+            .as_ref()
+            .map(|e| Arc::new(ConfigUtils::default_tenant(e.to_owned())))
+    }
+
+    pub fn to_key(&self) -> ConfigKey {
+        let group = self
+            .group
+            .clone()
+            .unwrap_or(Arc::new("DEFAULT_GROUP".to_owned()));
+        ConfigKey::new_by_arc(
+            self.data_id.clone().unwrap_or_default(),
+            group,
+            self.get_tenant().unwrap_or_default(),
+        )
+    }
+}
+
+#[derive(Message, Clone, Debug)]
+#[rtype(result = "anyhow::Result<ConfigChangeResult>")]
+pub enum ConfigChangeReq {
+    /// raft数据变更通知
+    NotifyChange {
+        key: Vec<u8>,
+        value: Option<Vec<u8>>,
+    },
+    QueryList {
+        tenant: Option<Arc<String>>,
+        status: Option<ConfigChangeStatus>,
+    },
+    Query(Arc<String>),
+}
+
+#[derive(Clone, Debug)]
+pub enum ConfigChangeResult {
+    None,
+    List(Vec<Arc<ConfigChangeDo>>),
+    Change(Option<Arc<ConfigChangeDo>>),
+}
@@ -0,0 +1,341 @@
+use crate::config::config_type::ConfigType;
+use crate::config::core::ConfigKey;
+use crate::utils::get_md5;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// 配置引用占位符前缀,格式:`${ref:group/dataId#key}`,分组为空时为DEFAULT_GROUP,key为空时引用整个配置内容
+pub const CONFIG_REF_PREFIX: &str = "${ref:";
+const CONFIG_REF_SUFFIX: char = '}';
+const DEFAULT_GROUP: &str = "DEFAULT_GROUP";
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConfigRef {
+    pub group: Arc<String>,
+    pub data_id: Arc<String>,
+    /// properties/yaml/json/toml配置中的key,多级key用`.`分割
+    pub key: Option<String>,
+}
+
+impl ConfigRef {
+    fn parse(v: &str) -> Option<Self> {
+        let (path, key) = match v.split_once('#') {
+            Some((path, key)) => (path, Some(key.trim().to_owned()).filter(|e| !e.is_empty())),
+            None => (v, None),
+        };
+        let (group, data_id) = path.split_once('/').unwrap_or((DEFAULT_GROUP, path));
+        let group = if group.trim().is_empty() {
+            DEFAULT_GROUP
+        } else {
+            group.trim()
+        };
+        let data_id = data_id.trim();
+        if data_id.is_empty() {
+            return None;
+        }
+        Some(Self {
+            group: Arc::new(group.to_owned()),
+            data_id: Arc::new(data_id.to_owned()),
+            key,
+        })
+    }
+
+    pub fn to_config_key(&self, tenant: &Arc<String>) -> ConfigKey {
+        ConfigKey::new_by_arc(self.data_id.clone(), self.group.clone(), tenant.clone())
+    }
+}
+
+///
+/// 解析内容中的引用,返回占位符在内容中的区间及引用信息
+pub fn parse_refs(content: &str) -> Vec<(usize, usize, ConfigRef)> {
+    let mut list = vec![];
+    let mut offset = 0;
+    while let Some(start) = content[offset..].find(CONFIG_REF_PREFIX) {
+        let start = offset + start;
+        let value_start = start + CONFIG_REF_PREFIX.len();
+        let end = match content[value_start..].find(CONFIG_REF_SUFFIX) {
+            Some(v) => value_start + v,
+            None => break,
+        };
+        if let Some(config_ref) = ConfigRef::parse(&content[value_start..end]) {
+            list.push((start, end + 1, config_ref));
+        }
+        offset = end + 1;
+    }
+    list
+}
+
+pub fn parse_ref_keys(tenant: &Arc<String>, content: &str) -> HashSet<ConfigKey> {
+    if !content.contains(CONFIG_REF_PREFIX) {
+        return HashSet::new();
+    }
+    parse_refs(content)
+        .into_iter()
+        .map(|(_, _, e)| e.to_config_key(tenant))
+        .collect()
+}
+
+fn value_to_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(v) => v.to_owned(),
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::Bool(_) | serde_json::Value::Number(_) => value.to_string(),
+        _ => serde_json::to_string(value).unwrap_or_default(),
+    }
+}
+
+fn get_json_value(root: &serde_json::Value, key: &str) -> Option<String> {
+    if let Some(v) = root.as_object().and_then(|e| e.get(key)) {
+        return Some(value_to_string(v));
+    }
+    let mut value = root;
+    for item in key.split('.') {
+        value = match value {
+            serde_json::Value::Object(map) => map.get(item)?,
+            serde_json::Value::Array(list) => list.get(item.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(value_to_string(value))
+}
+
+fn get_properties_value(content: &str, key: &str) -> Option<String> {
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+            continue;
+        }
+        if let Some(pos) = line.find(['=', ':']) {
+            if line[..pos].trim() == key {
+                return Some(line[pos + 1..].trim().to_owned());
+            }
+        }
+    }
+    None
+}
+
+///
+/// 按配置类型从内容中取出key对应的值,不支持的类型返回空
+pub fn get_content_value(config_type: &ConfigType, content: &str, key: &str) -> Option<String> {
+    let root = match config_type {
+        ConfigType::Properties => return get_properties_value(content, key),
+        ConfigType::Json => serde_json::from_str::<serde_json::Value>(content).ok()?,
+        ConfigType::Yaml => {
+            serde_json::to_value(serde_yml::from_str::<serde_yml::Value>(content).ok()?).ok()?
+        }
+        ConfigType::Toml => {
+            serde_json::to_value(toml::from_str::<toml::Table>(content).ok()?).ok()?
+        }
+        _ => return None,
+    };
+    get_json_value(&root, key)
+}
+
+#[derive(Debug, Clone)]
+pub struct ResolvedConfig {
+    pub content: Arc<String>,
+    pub md5: Arc<String>,
+}
+
+impl ResolvedConfig {
+    pub fn new(content: String) -> Self {
+        let md5 = Arc::new(get_md5(&content));
+        Self {
+            content: Arc::new(content),
+            md5,
+        }
+    }
+}
+
+///
+/// 配置引用关系索引,记录引用与被引用关系,以及包含引用的配置解析后的内容
+#[derive(Debug, Default)]
+pub struct ConfigRefIndex {
+    depends: HashMap<ConfigKey, HashSet<ConfigKey>>,
+    dependents: HashMap<ConfigKey, HashSet<ConfigKey>>,
+    resolved: HashMap<ConfigKey, ResolvedConfig>,
+}
+
+impl ConfigRefIndex {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    ///
+    /// 更新配置引用的配置,内容为空表示配置已删除
+    pub fn update_refs(&mut self, key: &ConfigKey, content: Option<&str>) {
+        let refs = content
+            .map(|e| parse_ref_keys(&key.tenant, e))
+            .unwrap_or_default();
+        if let Some(old_refs) = self.depends.remove(key) {
+            for item in old_refs.difference(&refs) {
+                if let Some(set) = self.dependents.get_mut(item) {
+                    set.remove(key);
+                    if set.is_empty() {
+                        self.dependents.remove(item);
+                    }
+                }
+            }
+        }
+        if refs.is_empty() {
+            return;
+        }
+        for item in &refs {
+            self.dependents
+                .entry(item.clone())
+                .or_default()
+                .insert(key.clone());
+        }
+        self.depends.insert(key.clone(), refs);
+    }
+
+    pub fn has_refs(&self, key: &ConfigKey) -> bool {
+        self.depends.contains_key(key)
+    }
+
+    ///
+    /// 直接或间接引用指定配置的配置列表
+    pub fn get_dependents(&self, key: &ConfigKey) -> Vec<ConfigKey> {
+        let mut list = vec![];
+        let mut visited = HashSet::new();
+        visited.insert(key.clone());
+        let mut stack = vec![key.clone()];
+        while let Some(item) = stack.pop() {
+            if let Some(set) = self.dependents.get(&item) {
+                for dependent in set {
+                    if visited.insert(dependent.clone()) {
+                        list.push(dependent.clone());
+                        stack.push(dependent.clone());
+                    }
+                }
+            }
+        }
+        list
+    }
+
+    ///
+    /// 检查配置使用新内容后是否存在循环引用,存在时返回引用链
+    pub fn find_cycle(&self, key: &ConfigKey, content: &str) -> Option<Vec<ConfigKey>> {
+        let refs = parse_ref_keys(&key.tenant, content);
+        let mut visited = HashSet::new();
+        for item in refs {
+            let mut path = vec![key.clone()];
+            if self.find_path(&item, key, &mut visited, &mut path) {
+                return Some(path);
+            }
+        }
+        None
+    }
+
+    fn find_path(
+        &self,
+        current: &ConfigKey,
+        target: &ConfigKey,
+        visited: &mut HashSet<ConfigKey>,
+        path: &mut Vec<ConfigKey>,
+    ) -> bool {
+        path.push(current.clone());
+        if current == target {
+            return true;
+        }
+        if visited.insert(current.clone()) {
+            if let Some(refs) = self.depends.get(current) {
+                for item in refs {
+                    if self.find_path(item, target, visited, path) {
+                        return true;
+                    }
+                }
+            }
+        }
+        path.pop();
+        false
+    }
+
+    pub fn get_resolved(&self, key: &ConfigKey) -> Option<&ResolvedConfig> {
+        self.resolved.get(key)
+    }
+
+    ///
+    /// 设置解析后的内容,返回解析后的md5是否变化
+    pub fn set_resolved(&mut self, key: ConfigKey, value: Option<ResolvedConfig>) -> bool {
+        match value {
+            Some(value) => {
+                let changed = self
+                    .resolved
+                    .get(&key)
+                    .map(|e| e.md5 != value.md5)
+                    .unwrap_or(true);
+                self.resolved.insert(key, value);
+                changed
+            }
+            None => self.resolved.remove(&key).is_some(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_config_refs() {
+        let content = "url=${ref:db/common.properties#db.host}:${ref:port.txt}\nx=${ref:}";
+        let refs = parse_refs(content);
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[0].2.group.as_str(), "db");
+        assert_eq!(refs[0].2.data_id.as_str(), "common.properties");
+        assert_eq!(refs[0].2.key.as_deref(), Some("db.host"));
+        assert_eq!(
+            &content[refs[0].0..refs[0].1],
+            "${ref:db/common.properties#db.host}"
+        );
+        assert_eq!(refs[1].2.group.as_str(), DEFAULT_GROUP);
+        assert_eq!(refs[1].2.key, None);
+    }
+
+    #[test]
+    fn get_value_by_type() {
+        assert_eq!(
+            get_content_value(
+                &ConfigType::Properties,
+                "# c\ndb.host = 10.0.0.1\n",
+                "db.host"
+            ),
+            Some("10.0.0.1".to_owned())
+        );
+        assert_eq!(
+            get_content_value(
+                &ConfigType::Yaml,
+                "db:\n  host: 10.0.0.1\n  port: 3306\n",
+                "db.port"
+            ),
+            Some("3306".to_owned())
+        );
+        assert_eq!(
+            get_content_value(
+                &ConfigType::Json,
+                "{\"db\":{\"hosts\":[\"a\",\"b\"]}}",
+                "db.hosts.1"
+            ),
+            Some("b".to_owned())
+        );
+        assert_eq!(get_content_value(&ConfigType::Text, "a", "a"), None);
+    }
+
+    #[test]
+    fn find_ref_cycle() {
+        let tenant = Arc::new("dev".to_owned());
+        let a = ConfigKey::new("a", DEFAULT_GROUP, "dev");
+        let b = ConfigKey::new("b", DEFAULT_GROUP, "dev");
+        let mut index = ConfigRefIndex::new();
+        index.update_refs(&a, Some("${ref:b#x}"));
+        assert_eq!(index.get_dependents(&b), vec![a.clone()]);
+        assert!(index.find_cycle(&b, "x=1").is_none());
+        let path = index.find_cycle(&b, "x=${ref:a}").unwrap();
+        assert_eq!(path, vec![b.clone(), a.clone(), b.clone()]);
+        assert!(index.find_cycle(&a, "${ref:a}").is_some());
+        index.update_refs(&a, None);
+        assert!(index.get_dependents(&b).is_empty());
+        assert!(parse_ref_keys(&tenant, "no ref").is_empty());
+    }
+}
@@ -3,15 +3,103 @@ use std::{
     sync::Arc,
 };
 
-use super::core::{ConfigKey, ListenerItem};
+use super::core::{ConfigKey, ConfigValue, ListenerItem};
+use crate::config::schema::model::match_pattern;
 use crate::grpc::bistream_manage::{BiStreamManage, BiStreamManageCmd};
+use crate::mcp::model::sse_model::SseStreamManageAsyncCmd;
+use crate::mcp::sse_manage::{SseConnUtils, SseStreamManager};
+use crate::webhook::core::WebhookManager;
+use crate::webhook::model::{WebhookEvent, WebhookEventType, WebhookReq};
 use actix::prelude::*;
+use serde::Serialize;
+
+/// SSE配置监听事件名
+pub const CONFIG_WATCH_SSE_EVENT: &str = "config";
+/// 建立监听时推送的配置当前值
+pub const CONFIG_WATCH_TYPE_CURRENT: &str = "current";
+
+///
+/// SSE配置监听规则,按配置key列表或分组、dataId通配符匹配
+#[derive(Clone, Debug, Default)]
+pub struct ConfigSseWatcher {
+    pub tenant: Arc<String>,
+    pub keys: HashSet<ConfigKey>,
+    /// 分组匹配规则,支持`*`通配符
+    pub group_pattern: Option<Arc<String>>,
+    /// dataId匹配规则,支持`*`通配符
+    pub data_id_pattern: Option<Arc<String>>,
+    /// 事件是否包含配置内容
+    pub with_content: bool,
+}
+
+impl ConfigSseWatcher {
+    pub fn has_pattern(&self) -> bool {
+        self.group_pattern.is_some() || self.data_id_pattern.is_some()
+    }
+
+    pub fn match_key(&self, key: &ConfigKey) -> bool {
+        if self.tenant != key.tenant {
+            return false;
+        }
+        if self.keys.contains(key) {
+            return true;
+        }
+        self.has_pattern()
+            && self
+                .group_pattern
+                .as_ref()
+                .map(|p| match_pattern(p, &key.group))
+                .unwrap_or(true)
+            && self
+                .data_id_pattern
+                .as_ref()
+                .map(|p| match_pattern(p, &key.data_id))
+                .unwrap_or(true)
+    }
+
+    pub fn build_message(
+        &self,
+        event_type: &'static str,
+        key: &ConfigKey,
+        value: Option<&ConfigValue>,
+    ) -> String {
+        let event = ConfigWatchEvent {
+            r#type: event_type,
+            data_id: key.data_id.clone(),
+            group: key.group.clone(),
+            tenant: key.tenant.clone(),
+            md5: value.map(|v| v.md5.clone()),
+            content: value
+                .filter(|_| self.with_content)
+                .map(|v| v.content.clone()),
+        };
+        SseConnUtils::create_sse_event_message(CONFIG_WATCH_SSE_EVENT, &event)
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigWatchEvent {
+    pub r#type: &'static str,
+    pub data_id: Arc<String>,
+    pub group: Arc<String>,
+    pub tenant: Arc<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub md5: Option<Arc<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<Arc<String>>,
+}
 
 #[derive(Default)]
 pub struct Subscriber {
     listener: HashMap<ConfigKey, HashSet<Arc<String>>>,
-    client_keys: HashMap<Arc<String>, HashSet<ConfigKey>>,
+    /// client_id -> (配置key -> 客户端当前md5)
+    client_keys: HashMap<Arc<String>, HashMap<ConfigKey, Arc<String>>>,
     conn_manage: Option<Addr<BiStreamManage>>,
+    webhook_manager: Option<Addr<WebhookManager>>,
+    sse_manage: Option<Addr<SseStreamManager>>,
+    /// sse session_id -> 监听规则
+    sse_watchers: HashMap<Arc<String>, Arc<ConfigSseWatcher>>,
 }
 
 impl Subscriber {
@@ -20,6 +108,9 @@ impl Subscriber {
             listener: Default::default(),
             client_keys: Default::default(),
             conn_manage: Default::default(),
+            webhook_manager: Default::default(),
+            sse_manage: Default::default(),
+            sse_watchers: Default::default(),
         }
     }
 
@@ -27,6 +118,28 @@ impl Subscriber {
         self.conn_manage = Some(conn_manage);
     }
 
+    pub fn set_webhook_manager(&mut self, webhook_manager: Addr<WebhookManager>) {
+        self.webhook_manager = Some(webhook_manager);
+    }
+
+    pub fn set_sse_manage(&mut self, sse_manage: Addr<SseStreamManager>) {
+        self.sse_manage = Some(sse_manage);
+    }
+
+    pub fn add_sse_watcher(&mut self, session_id: Arc<String>, watcher: Arc<ConfigSseWatcher>) {
+        self.sse_watchers.insert(session_id, watcher);
+    }
+
+    pub fn remove_sse_watcher(&mut self, session_id: &Arc<String>) {
+        self.sse_watchers.remove(session_id);
+    }
+
+    pub fn send_sse_message(&self, session_id: Arc<String>, message: String) {
+        if let Some(sse_manage) = &self.sse_manage {
+            sse_manage.do_send(SseStreamManageAsyncCmd::SendMessage(session_id, message));
+        }
+    }
+
     pub fn add_subscribe(&mut self, client_id: Arc<String>, items: Vec<ListenerItem>) {
         for item in &items {
             match self.listener.get_mut(&item.key) {
@@ -40,19 +153,9 @@ impl Subscriber {
                 }
             };
         }
-        match self.client_keys.get_mut(&client_id) {
-            Some(set) => {
-                for item in items {
-                    set.insert(item.key);
-                }
-            }
-            None => {
-                let mut set = HashSet::new();
-                for item in items {
-                    set.insert(item.key);
-                }
-                self.client_keys.insert(client_id, set);
-            }
+        let map = self.client_keys.entry(client_id).or_default();
+        for item in items {
+            map.insert(item.key, item.md5);
         }
     }
 
@@ -87,7 +190,7 @@ impl Subscriber {
     pub fn remove_client_subscribe(&mut self, client_id: Arc<String>) {
         if let Some(set) = self.client_keys.remove(&client_id) {
             let mut remove_keys = vec![];
-            for key in set {
+            for key in set.into_keys() {
                 if let Some(set) = self.listener.get_mut(&key) {
                     set.remove(&client_id);
                     if set.is_empty() {
@@ -118,7 +221,23 @@ impl Subscriber {
         }
     }
 
-    pub fn notify(&self, key: ConfigKey) {
+    pub fn notify(
+        &self,
+        key: ConfigKey,
+        event_type: WebhookEventType,
+        value: Option<&ConfigValue>,
+    ) {
+        for (session_id, watcher) in &self.sse_watchers {
+            if watcher.match_key(&key) {
+                let message = watcher.build_message(event_type.get_value(), &key, value);
+                self.send_sse_message(session_id.clone(), message);
+            }
+        }
+        if let Some(webhook_manager) = &self.webhook_manager {
+            webhook_manager.do_send(WebhookReq::Fire(WebhookEvent::new_config_event(
+                event_type, &key,
+            )));
+        }
         if let Some(conn_manage) = &self.conn_manage {
             if let Some(set) = self.listener.get(&key) {
                 conn_manage.do_send(BiStreamManageCmd::NotifyConfig(key, set.clone()));
@@ -126,6 +245,44 @@ impl Subscriber {
         }
     }
 
+    pub fn get_client_keys(&self, client_id: &Arc<String>) -> Vec<ConfigKey> {
+        if let Some(map) = self.client_keys.get(client_id) {
+            map.keys().cloned().collect()
+        } else {
+            vec![]
+        }
+    }
+
+    /// 查询监听指定配置的客户端id与客户端md5
+    pub fn query_by_key(&self, key: &ConfigKey) -> Vec<(Arc<String>, Arc<String>)> {
+        let mut list = vec![];
+        if let Some(set) = self.listener.get(key) {
+            for client_id in set {
+                if let Some(md5) = self.client_keys.get(client_id).and_then(|m| m.get(key)) {
+                    list.push((client_id.clone(), md5.clone()));
+                }
+            }
+        }
+        list
+    }
+
+    /// 查询指定客户端ip下各连接监听的配置与客户端md5
+    pub fn query_by_client_ip(
+        &self,
+        client_ip: &str,
+    ) -> Vec<(Arc<String>, ConfigKey, Arc<String>)> {
+        let mut list = vec![];
+        for (client_id, map) in &self.client_keys {
+            if get_client_ip_from_client_id(client_id) != client_ip {
+                continue;
+            }
+            for (key, md5) in map {
+                list.push((client_id.clone(), key.clone(), md5.clone()));
+            }
+        }
+        list
+    }
+
     pub fn get_listener_key_size(&self) -> usize {
         self.listener.len()
     }
@@ -150,3 +307,47 @@ impl Subscriber {
         sum
     }
 }
+
+///
+/// 从grpc连接id中解析客户端ip,连接id格式: {node_id}_{ip}:{port}
+pub fn get_client_ip_from_client_id(client_id: &str) -> &str {
+    let addr = client_id
+        .split_once('_')
+        .map(|(_, addr)| addr)
+        .unwrap_or(client_id);
+    let ip = addr.rsplit_once(':').map(|(ip, _)| ip).unwrap_or(addr);
+    ip.trim_start_matches('[').trim_end_matches(']')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_sse_watcher() {
+        let mut watcher = ConfigSseWatcher {
+            tenant: Arc::new("dev".to_owned()),
+            ..Default::default()
+        };
+        watcher
+            .keys
+            .insert(ConfigKey::new("app.yaml", "DEFAULT_GROUP", "dev"));
+        assert!(watcher.match_key(&ConfigKey::new("app.yaml", "DEFAULT_GROUP", "dev")));
+        assert!(!watcher.match_key(&ConfigKey::new("db.yaml", "DEFAULT_GROUP", "dev")));
+        watcher.group_pattern = Some(Arc::new("order-*".to_owned()));
+        assert!(watcher.match_key(&ConfigKey::new("db.yaml", "order-service", "dev")));
+        assert!(!watcher.match_key(&ConfigKey::new("db.yaml", "order-service", "prod")));
+        watcher.data_id_pattern = Some(Arc::new("*.json".to_owned()));
+        assert!(!watcher.match_key(&ConfigKey::new("db.yaml", "order-service", "dev")));
+        assert!(watcher.match_key(&ConfigKey::new("db.json", "order-service", "dev")));
+    }
+
+    #[test]
+    fn parse_client_ip() {
+        assert_eq!(
+            get_client_ip_from_client_id("1_127.0.0.1:52345"),
+            "127.0.0.1"
+        );
+        assert_eq!(get_client_ip_from_client_id("1_[::1]:52345"), "::1");
+    }
+}
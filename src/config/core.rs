@@ -1,4 +1,3 @@
-use async_raft_ext::raft::ClientWriteRequest;
 use bean_factory::bean;
 use bean_factory::Inject;
 use chrono::Local;
@@ -9,24 +8,32 @@ use std::sync::Arc;
 use std::sync::Weak;
 use std::time::Duration;
 
+use crate::raft::cluster::model::SetConfigReq;
 use crate::raft::store::ClientRequest;
 use crate::raft::NacosRaft;
 use crate::utils::get_md5;
 use serde::{Deserialize, Serialize};
 
 use crate::common::byte_utils::id_to_bin;
-use crate::common::constant::{CONFIG_TREE_NAME, SEQUENCE_TREE_NAME, SEQ_KEY_CONFIG};
+use crate::common::constant::{
+    CONFIG_TREE_NAME, EMPTY_ARC_STRING, SEQUENCE_TREE_NAME, SEQ_KEY_CONFIG,
+};
 use crate::common::sequence_utils::SimpleSequence;
 use actix::prelude::*;
 
-use super::config_subscribe::Subscriber;
+use super::config_subscribe::{ConfigSseWatcher, Subscriber, CONFIG_WATCH_TYPE_CURRENT};
 use super::dal::ConfigHistoryParam;
 use crate::config::config_index::{ConfigQueryParam, TenantIndex};
+use crate::config::config_ref::{get_content_value, parse_refs, ConfigRefIndex, ResolvedConfig};
+use crate::config::config_subscribe::get_client_ip_from_client_id;
 use crate::config::config_type::ConfigType;
+use crate::config::lock::{ConfigLockDo, ConfigLockIndex};
 use crate::config::model::{
-    ConfigRaftCmd, ConfigRaftResult, ConfigValueDO, HistoryItem, SetConfigParam,
+    ConfigListenerInfo, ConfigRaftCmd, ConfigRaftResult, ConfigRollbackInfo, ConfigValueDO,
+    HistoryItem, ListenerType, SetConfigParam,
 };
 use crate::config::utils::param_utils;
+use crate::namespace::model::{NamespaceQuota, NamespaceUsage};
 use crate::namespace::NamespaceActor;
 use crate::now_millis_i64;
 use crate::raft::filestore::model::SnapshotRecordDto;
@@ -35,6 +42,7 @@ use crate::transfer::model::{
     TransferDataRequest, TransferDataResponse, TransferRecordDto, TransferWriterRequest,
 };
 use crate::transfer::writer::TransferWriterActor;
+use crate::webhook::model::WebhookEventType;
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub struct ConfigKey {
@@ -154,6 +162,8 @@ impl ConfigValue {
                 content,
                 modified_time: op_time,
                 op_user,
+                rollback_from: None,
+                clone_from: None,
             }],
             config_type: None,
             desc: None,
@@ -182,6 +192,8 @@ impl ConfigValue {
             content,
             modified_time: op_time,
             op_user,
+            rollback_from: None,
+            clone_from: None,
         };
         if self.histories.len() >= 100 {
             self.histories.remove(0);
@@ -189,6 +201,32 @@ impl ConfigValue {
         self.last_modified = op_time;
         self.histories.push(item);
     }
+
+    pub fn set_last_rollback_from(&mut self, rollback_from: Option<u64>) {
+        if let Some(item) = self.histories.last_mut() {
+            item.rollback_from = rollback_from;
+        }
+    }
+
+    pub fn set_last_clone_from(&mut self, clone_from: Option<Arc<String>>) {
+        if let Some(item) = self.histories.last_mut() {
+            item.clone_from = clone_from;
+        }
+    }
+
+    ///
+    /// 按配额裁剪历史记录,至少保留当前版本
+    pub fn trim_histories(&mut self, max_history_count: usize) {
+        let max_history_count = max_history_count.max(1);
+        if self.histories.len() > max_history_count {
+            let n = self.histories.len() - max_history_count;
+            self.histories.drain(..n);
+        }
+    }
+
+    pub fn get_history(&self, history_id: u64) -> Option<&HistoryItem> {
+        self.histories.iter().find(|e| e.id == history_id)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -200,6 +238,8 @@ pub struct ConfigInfoDto {
     pub content: Option<Arc<String>>,
     pub md5: Option<Arc<String>>,
     pub desc: Option<Arc<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lock: Option<Arc<ConfigLockDo>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
@@ -212,9 +252,13 @@ pub struct ConfigHistoryInfoDto {
     pub content: Option<String>,
     pub modified_time: Option<i64>, //给历史记录使用
     pub op_user: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rollback_from: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clone_from: Option<Arc<String>>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ListenerItem {
     pub key: ConfigKey,
     pub md5: Arc<String>,
@@ -307,11 +351,18 @@ pub enum ListenerResult {
 type ListenerSenderType = tokio::sync::oneshot::Sender<ListenerResult>;
 //type ListenerReceiverType = tokio::sync::oneshot::Receiver<ListenerResult>;
 
+/// http长轮询监听的客户端信息
+struct ListenerClient {
+    client_ip: Arc<String>,
+    items: Vec<ListenerItem>,
+}
+
 pub(crate) struct ConfigListener {
     version: u64,
     listener: HashMap<ConfigKey, Vec<u64>>,
     time_listener: BTreeMap<i64, Vec<OnceListener>>,
     sender_map: HashMap<u64, ListenerSenderType>,
+    client_map: HashMap<u64, ListenerClient>,
 }
 
 impl ConfigListener {
@@ -321,10 +372,17 @@ impl ConfigListener {
             listener: Default::default(),
             time_listener: Default::default(),
             sender_map: Default::default(),
+            client_map: Default::default(),
         }
     }
 
-    fn add(&mut self, items: Vec<ListenerItem>, sender: ListenerSenderType, time: i64) {
+    fn add(
+        &mut self,
+        items: Vec<ListenerItem>,
+        sender: ListenerSenderType,
+        time: i64,
+        client_ip: Arc<String>,
+    ) {
         self.version += 1;
         for item in &items {
             let key = item.key.clone();
@@ -338,6 +396,8 @@ impl ConfigListener {
             };
         }
         self.sender_map.insert(self.version, sender);
+        self.client_map
+            .insert(self.version, ListenerClient { client_ip, items });
         let once_listener = OnceListener {
             version: self.version,
             //time,
@@ -356,6 +416,7 @@ impl ConfigListener {
     fn notify(&mut self, key: ConfigKey) {
         if let Some(list) = self.listener.remove(&key) {
             for v in list {
+                self.client_map.remove(&v);
                 if let Some(sender) = self.sender_map.remove(&v) {
                     sender.send(ListenerResult::DATA(vec![key.clone()])).ok();
                 }
@@ -371,6 +432,7 @@ impl ConfigListener {
                 keys.push(*key);
                 for item in list {
                     let v = item.version;
+                    self.client_map.remove(&v);
                     if let Some(sender) = self.sender_map.remove(&v) {
                         sender.send(ListenerResult::NULL).ok();
                     }
@@ -384,6 +446,34 @@ impl ConfigListener {
         }
     }
 
+    /// 查询监听指定配置的客户端ip与md5
+    fn query_by_key(&self, key: &ConfigKey) -> Vec<(Arc<String>, Arc<String>)> {
+        let mut list = vec![];
+        if let Some(versions) = self.listener.get(key) {
+            for v in versions {
+                if let Some(client) = self.client_map.get(v) {
+                    if let Some(item) = client.items.iter().find(|e| &e.key == key) {
+                        list.push((client.client_ip.clone(), item.md5.clone()));
+                    }
+                }
+            }
+        }
+        list
+    }
+
+    /// 查询指定客户端ip监听的配置与md5
+    fn query_by_ip(&self, client_ip: &str) -> Vec<(ConfigKey, Arc<String>)> {
+        let mut list = vec![];
+        for client in self.client_map.values() {
+            if client.client_ip.as_str() == client_ip {
+                for item in &client.items {
+                    list.push((item.key.clone(), item.md5.clone()));
+                }
+            }
+        }
+        list
+    }
+
     pub(crate) fn get_listener_client_size(&self) -> usize {
         self.sender_map.len()
     }
@@ -402,6 +492,9 @@ pub struct ConfigActor {
     raft: Option<Weak<NacosRaft>>,
     namespace_actor: Option<Addr<NamespaceActor>>,
     sequence: SimpleSequence,
+    namespace_quota: HashMap<Arc<String>, Arc<NamespaceQuota>>,
+    ref_index: ConfigRefIndex,
+    lock_index: ConfigLockIndex,
 }
 
 impl Inject for ConfigActor {
@@ -420,6 +513,12 @@ impl Inject for ConfigActor {
         if let Some(conn_manage) = factory_data.get_actor() {
             self.subscriber.set_conn_manage(conn_manage);
         }
+        if let Some(webhook_manager) = factory_data.get_actor() {
+            self.subscriber.set_webhook_manager(webhook_manager);
+        }
+        if let Some(sse_manage) = factory_data.get_actor() {
+            self.subscriber.set_sse_manage(sse_manage);
+        }
         log::info!("ConfigActor inject complete");
     }
 }
@@ -440,6 +539,9 @@ impl ConfigActor {
             raft: None,
             namespace_actor: None,
             sequence: SimpleSequence::new(0, 100),
+            namespace_quota: Default::default(),
+            ref_index: ConfigRefIndex::new(),
+            lock_index: ConfigLockIndex::new(),
         }
     }
 
@@ -457,13 +559,182 @@ impl ConfigActor {
 
     fn inner_set_config(&mut self, key: ConfigKey, value: ConfigValue) {
         self.tenant_index.insert_config(key.clone());
-        self.cache.insert(key, value);
+        self.cache.insert(key.clone(), value);
+        self.refresh_config_ref(&key);
+    }
+
+    ///
+    /// 解析配置内容中的引用,引用不存在或循环引用时保留原占位符
+    fn resolve_content(
+        &self,
+        key: &ConfigKey,
+        content: &str,
+        stack: &mut Vec<ConfigKey>,
+    ) -> String {
+        let refs = parse_refs(content);
+        if refs.is_empty() {
+            return content.to_owned();
+        }
+        stack.push(key.clone());
+        let mut result = String::with_capacity(content.len());
+        let mut offset = 0;
+        for (start, end, config_ref) in refs {
+            result.push_str(&content[offset..start]);
+            offset = end;
+            let ref_key = config_ref.to_config_key(&key.tenant);
+            let value = match self.cache.get(&ref_key) {
+                Some(v) if !stack.contains(&ref_key) => v,
+                _ => {
+                    result.push_str(&content[start..end]);
+                    continue;
+                }
+            };
+            let ref_content = self.resolve_content(&ref_key, &value.content, stack);
+            match &config_ref.key {
+                Some(k) => {
+                    let config_type = match &value.config_type {
+                        Some(v) => ConfigType::new_by_value(v),
+                        None => SetConfigReq::detect_config_type(ref_key.data_id.clone())
+                            .map(|v| ConfigType::new_by_value(&v))
+                            .unwrap_or_default(),
+                    };
+                    match get_content_value(&config_type, &ref_content, k) {
+                        Some(v) => result.push_str(&v),
+                        None => result.push_str(&content[start..end]),
+                    }
+                }
+                None => result.push_str(&ref_content),
+            }
+        }
+        result.push_str(&content[offset..]);
+        stack.pop();
+        result
+    }
+
+    ///
+    /// 获取客户端实际读取到的内容md5,包含引用的配置为解析后内容的md5
+    fn get_effective_md5(&self, key: &ConfigKey) -> Option<Arc<String>> {
+        if let Some(v) = self.ref_index.get_resolved(key) {
+            return Some(v.md5.clone());
+        }
+        self.cache.get(key).map(|v| v.md5.clone())
+    }
+
+    fn update_resolved(&mut self, key: &ConfigKey) {
+        let resolved = match self.cache.get(key) {
+            Some(v) if self.ref_index.has_refs(key) => Some(ResolvedConfig::new(
+                self.resolve_content(key, &v.content, &mut vec![]),
+            )),
+            _ => None,
+        };
+        self.ref_index.set_resolved(key.clone(), resolved);
+    }
+
+    ///
+    /// 配置变更后更新引用关系,并通知解析后内容有变化的引用方
+    fn refresh_config_ref(&mut self, key: &ConfigKey) {
+        let content = self.cache.get(key).map(|v| v.content.clone());
+        self.ref_index
+            .update_refs(key, content.as_ref().map(|v| v.as_str()));
+        self.update_resolved(key);
+        for dependent in self.ref_index.get_dependents(key) {
+            let old_md5 = self.get_effective_md5(&dependent);
+            self.update_resolved(&dependent);
+            if old_md5 != self.get_effective_md5(&dependent) {
+                self.listener.notify(dependent.clone());
+                self.subscriber.notify(
+                    dependent.clone(),
+                    WebhookEventType::ConfigPublished,
+                    self.cache.get(&dependent),
+                );
+            }
+        }
+    }
+
+    ///
+    /// 校验发布内容是否引用自身或形成循环引用
+    fn check_config_ref(&self, key: &ConfigKey, content: &str) -> anyhow::Result<()> {
+        if let Some(path) = self.ref_index.find_cycle(key, content) {
+            let path: Vec<String> = path
+                .iter()
+                .map(|e| format!("{}/{}", &e.group, &e.data_id))
+                .collect();
+            return Err(anyhow::anyhow!(
+                "config reference cycle: {}",
+                path.join(" -> ")
+            ));
+        }
+        Ok(())
+    }
+
+    fn get_tenant_usage(&self, tenant: &Arc<String>) -> (u64, u64) {
+        let mut count = 0;
+        let mut bytes = 0;
+        if let Some(index) = self.tenant_index.tenant_group.get(tenant) {
+            for (group, set) in &index.group_data {
+                for data_id in set {
+                    let key = ConfigKey::new_by_arc(data_id.clone(), group.clone(), tenant.clone());
+                    if let Some(v) = self.cache.get(&key) {
+                        count += 1;
+                        bytes += v.content.len() as u64;
+                    }
+                }
+            }
+        }
+        (count, bytes)
+    }
+
+    pub(crate) fn query_namespace_usage(&self) -> HashMap<Arc<String>, NamespaceUsage> {
+        let mut map: HashMap<Arc<String>, NamespaceUsage> = HashMap::new();
+        for tenant in self.tenant_index.tenant_group.keys() {
+            let (config_count, config_bytes) = self.get_tenant_usage(tenant);
+            let usage = map.entry(tenant.clone()).or_default();
+            usage.config_count = config_count;
+            usage.config_bytes = config_bytes;
+        }
+        map
+    }
+
+    ///
+    /// 校验发布配置后命名空间的配置数量及内容总大小是否超过配额
+    fn check_config_quota(&self, key: &ConfigKey, content_len: usize) -> anyhow::Result<()> {
+        let quota = if let Some(v) = self.namespace_quota.get(&key.tenant) {
+            v
+        } else {
+            return Ok(());
+        };
+        let old_len = self
+            .cache
+            .get(key)
+            .filter(|v| !v.histories.is_empty())
+            .map(|v| v.content.len() as u64);
+        if old_len.is_none() && quota.max_config_count.is_some() {
+            let count = self
+                .tenant_index
+                .tenant_group
+                .get(&key.tenant)
+                .map(|e| e.get_config_count())
+                .unwrap_or_default() as u64;
+            NamespaceQuota::check("configCount", quota.max_config_count, count + 1)?;
+        }
+        if quota.max_config_bytes.is_some() {
+            let (_, bytes) = self.get_tenant_usage(&key.tenant);
+            let new_bytes = bytes - old_len.unwrap_or_default() + content_len as u64;
+            NamespaceQuota::check("configBytes", quota.max_config_bytes, new_bytes)?;
+        }
+        Ok(())
     }
 
     fn set_config(&mut self, param: SetConfigParam) -> anyhow::Result<ConfigResult> {
         if let Some(history_table_id) = param.history_table_id {
             self.sequence.set_valid_last_id(history_table_id);
         }
+        self.lock_index.check_writable(&param.key)?;
+        self.check_config_quota(&param.key, param.value.len())?;
+        let max_history_count = self
+            .namespace_quota
+            .get(&param.key.tenant)
+            .and_then(|e| e.max_history_count);
         if let Some(v) = self.cache.get_mut(&param.key) {
             let md5 = get_md5(param.value.as_str());
             if let Some(s) = param.config_type {
@@ -485,6 +756,11 @@ impl ConfigActor {
                 Some(Arc::new(md5)),
                 param.op_user,
             );
+            v.set_last_rollback_from(param.rollback_from);
+            v.set_last_clone_from(param.clone_from);
+            if let Some(max_history_count) = max_history_count {
+                v.trim_histories(max_history_count as usize);
+            }
         } else {
             let mut v = ConfigValue::init(
                 param.value,
@@ -495,20 +771,100 @@ impl ConfigActor {
             );
             v.config_type = param.config_type;
             v.desc = param.desc;
+            v.set_last_rollback_from(param.rollback_from);
+            v.set_last_clone_from(param.clone_from);
             self.cache.insert(param.key.clone(), v);
             self.tenant_index.insert_config(param.key.clone());
         }
+        self.refresh_config_ref(&param.key);
         self.listener.notify(param.key.clone());
-        self.subscriber.notify(param.key);
+        self.subscriber.notify(
+            param.key.clone(),
+            WebhookEventType::ConfigPublished,
+            self.cache.get(&param.key),
+        );
         Ok(ConfigResult::NULL)
     }
 
+    ///
+    /// 查询回滚到指定历史版本的预览信息
+    fn query_rollback_info(&self, key: &ConfigKey, history_id: u64) -> Option<ConfigRollbackInfo> {
+        let v = self.cache.get(key)?;
+        let history = v.get_history(history_id)?;
+        let mut info =
+            ConfigRollbackInfo::new(history_id, v.content.clone(), history.content.clone());
+        info.config_type = v.config_type.clone();
+        info.desc = v.desc.clone();
+        Some(info)
+    }
+
+    fn get_md5_or_empty(&self, key: &ConfigKey) -> Arc<String> {
+        self.get_effective_md5(key)
+            .unwrap_or_else(|| EMPTY_ARC_STRING.clone())
+    }
+
+    fn query_listener_by_key(&self, key: &ConfigKey) -> Vec<ConfigListenerInfo> {
+        let server_md5 = self.get_md5_or_empty(key);
+        let mut list = vec![];
+        for (client_ip, md5) in self.listener.query_by_key(key) {
+            list.push(ConfigListenerInfo::new(
+                key,
+                ListenerType::Http,
+                client_ip,
+                EMPTY_ARC_STRING.clone(),
+                md5,
+                server_md5.clone(),
+            ));
+        }
+        for (client_id, md5) in self.subscriber.query_by_key(key) {
+            list.push(ConfigListenerInfo::new(
+                key,
+                ListenerType::Grpc,
+                Arc::new(get_client_ip_from_client_id(&client_id).to_owned()),
+                client_id,
+                md5,
+                server_md5.clone(),
+            ));
+        }
+        list
+    }
+
+    fn query_listener_by_ip(&self, client_ip: &Arc<String>) -> Vec<ConfigListenerInfo> {
+        let mut list = vec![];
+        for (key, md5) in self.listener.query_by_ip(client_ip) {
+            let server_md5 = self.get_md5_or_empty(&key);
+            list.push(ConfigListenerInfo::new(
+                &key,
+                ListenerType::Http,
+                client_ip.clone(),
+                EMPTY_ARC_STRING.clone(),
+                md5,
+                server_md5,
+            ));
+        }
+        for (client_id, key, md5) in self.subscriber.query_by_client_ip(client_ip) {
+            let server_md5 = self.get_md5_or_empty(&key);
+            list.push(ConfigListenerInfo::new(
+                &key,
+                ListenerType::Grpc,
+                client_ip.clone(),
+                client_id,
+                md5,
+                server_md5,
+            ));
+        }
+        list
+    }
+
     fn del_config(&mut self, key: ConfigKey) -> anyhow::Result<()> {
+        self.lock_index.check_writable(&key)?;
         self.cache.remove(&key);
         //self.config_db.del_config(&key).ok();
         self.tenant_index.remove_config(&key);
+        self.refresh_config_ref(&key);
         self.listener.notify(key.clone());
-        self.subscriber.notify(key.clone());
+        self.subscriber
+            .notify(key.clone(), WebhookEventType::ConfigRemoved, None);
         self.subscriber.remove_config_key(key);
         Ok(())
     }
@@ -536,7 +892,7 @@ impl ConfigActor {
         if let Some(weak_raft) = raft {
             if let Some(raft) = weak_raft.upgrade() {
                 //TODO换成feature,非wait的方式
-                raft.client_write(ClientWriteRequest::new(req)).await?;
+                crate::raft::client_write(&raft, req).await?;
             }
         }
         Ok(())
@@ -557,6 +913,7 @@ impl ConfigActor {
                     group: item.group.clone(),
                     data_id: item.data_id.clone(),
                     desc: value.desc.clone(),
+                    lock: self.lock_index.get_lock(item).cloned(),
                     //md5:Some(value.md5.clone()),
                     //content:Some(value.content.clone()),
                     ..Default::default()
@@ -589,6 +946,7 @@ impl ConfigActor {
                     desc: value.desc.clone(),
                     content: Some(value.content.clone()),
                     md5: Some(value.md5.clone()),
+                    lock: self.lock_index.get_lock(&key).cloned(),
                 };
                 info_list.push(info);
             }
@@ -714,13 +1072,33 @@ pub enum ConfigCmd {
     SetFullValue(ConfigKey, ConfigValue),
     InnerSetLastId(u64),
     GET(ConfigKey),
+    /// 查询客户端读取的配置,内容中的引用替换为被引用配置的值
+    GetResolved(ConfigKey),
+    /// 校验发布内容的引用是否形成循环
+    CheckRef(ConfigKey, Arc<String>),
     QueryPageInfo(Box<ConfigQueryParam>),
     QueryInfoByKeys(Box<Vec<ConfigKey>>),
     QueryHistoryPageInfo(Box<ConfigHistoryParam>),
-    LISTENER(Vec<ListenerItem>, ListenerSenderType, i64),
+    LISTENER(Vec<ListenerItem>, ListenerSenderType, i64, Arc<String>),
     Subscribe(Vec<ListenerItem>, Arc<String>),
     RemoveSubscribe(Vec<ListenerItem>, Arc<String>),
     RemoveSubscribeClient(Arc<String>),
+    QueryClientSubscribeKeys(Arc<String>),
+    QueryListenerByKey(ConfigKey),
+    QueryListenerByIp(Arc<String>),
+    QueryRollbackInfo(ConfigKey, u64),
+    /// 添加SSE配置监听,并推送监听key的当前值
+    AddSseWatcher(Arc<String>, Arc<ConfigSseWatcher>),
+    RemoveSseWatcher(Arc<String>),
+    /// 设置命名空间配额,为空表示清除
+    SetNamespaceQuota(Arc<String>, Option<Arc<NamespaceQuota>>),
+    QueryNamespaceUsage,
+    /// 配置锁表变更通知,value为空表示解锁
+    NotifyLock {
+        key: Vec<u8>,
+        value: Option<Vec<u8>>,
+    },
+    QueryLockList(Option<Arc<String>>),
     BuildSnapshot(Addr<SnapshotWriterActor>),
     GetSequenceSection(u64),
 }
@@ -734,6 +1112,8 @@ pub enum ConfigAsyncCmd {
         op_user: Option<Arc<String>>,
         config_type: Option<Arc<String>>,
         desc: Option<Arc<String>>,
+        rollback_from: Option<u64>,
+        clone_from: Option<Arc<String>>,
     },
     Delete(ConfigKey),
 }
@@ -748,8 +1128,13 @@ pub enum ConfigResult {
     },
     NULL,
     ChangeKey(Vec<ConfigKey>),
+    ClientSubscribeKeys(Vec<ConfigKey>),
+    ListenerInfoList(Vec<ConfigListenerInfo>),
     ConfigInfoPage(usize, Vec<ConfigInfoDto>),
     ConfigHistoryInfoPage(usize, Vec<ConfigHistoryInfoDto>),
+    RollbackInfo(Option<ConfigRollbackInfo>),
+    NamespaceUsage(HashMap<Arc<String>, NamespaceUsage>),
+    LockList(Vec<Arc<ConfigLockDo>>),
     SequenceSection {
         //id包含start值
         start: u64,
@@ -798,11 +1183,29 @@ impl Handler<ConfigCmd> for ConfigActor {
                     });
                 }
             }
-            ConfigCmd::LISTENER(items, sender, time) => {
+            ConfigCmd::GetResolved(key) => {
+                if let Some(v) = self.cache.get(&key) {
+                    let (value, md5) = match self.ref_index.get_resolved(&key) {
+                        Some(resolved) => (resolved.content.clone(), resolved.md5.clone()),
+                        None => (v.content.clone(), v.md5.clone()),
+                    };
+                    return Ok(ConfigResult::Data {
+                        value,
+                        md5,
+                        config_type: v.config_type.clone(),
+                        desc: v.desc.clone(),
+                        last_modified: v.last_modified,
+                    });
+                }
+            }
+            ConfigCmd::CheckRef(key, content) => {
+                self.check_config_ref(&key, &content)?;
+            }
+            ConfigCmd::LISTENER(items, sender, time, client_ip) => {
                 let mut changes = vec![];
                 for item in &items {
-                    if let Some(v) = self.cache.get(&item.key) {
-                        if v.md5 != item.md5 {
+                    if let Some(md5) = self.get_effective_md5(&item.key) {
+                        if md5 != item.md5 {
                             changes.push(item.key.clone());
                         }
                     } else if !item.md5.is_empty() {
@@ -813,15 +1216,15 @@ impl Handler<ConfigCmd> for ConfigActor {
                     sender.send(ListenerResult::DATA(changes)).ok();
                     return Ok(ConfigResult::NULL);
                 } else {
-                    self.listener.add(items, sender, time);
+                    self.listener.add(items, sender, time, client_ip);
                     return Ok(ConfigResult::NULL);
                 }
             }
             ConfigCmd::Subscribe(items, client_id) => {
                 let mut changes = vec![];
                 for item in &items {
-                    if let Some(v) = self.cache.get(&item.key) {
-                        if v.md5 != item.md5 {
+                    if let Some(md5) = self.get_effective_md5(&item.key) {
+                        if md5 != item.md5 {
                             changes.push(item.key.clone());
                         }
                     } else if !item.md5.is_empty() {
@@ -839,6 +1242,50 @@ impl Handler<ConfigCmd> for ConfigActor {
             ConfigCmd::RemoveSubscribeClient(client_id) => {
                 self.subscriber.remove_client_subscribe(client_id);
             }
+            ConfigCmd::AddSseWatcher(session_id, watcher) => {
+                for key in &watcher.keys {
+                    if let Some(value) = self.cache.get(key) {
+                        let message =
+                            watcher.build_message(CONFIG_WATCH_TYPE_CURRENT, key, Some(value));
+                        self.subscriber
+                            .send_sse_message(session_id.clone(), message);
+                    }
+                }
+                self.subscriber.add_sse_watcher(session_id, watcher);
+            }
+            ConfigCmd::RemoveSseWatcher(session_id) => {
+                self.subscriber.remove_sse_watcher(&session_id);
+            }
+            ConfigCmd::SetNamespaceQuota(tenant, quota) => {
+                if let Some(quota) = quota {
+                    self.namespace_quota.insert(tenant, quota);
+                } else {
+                    self.namespace_quota.remove(&tenant);
+                }
+            }
+            ConfigCmd::NotifyLock { key, value } => {
+                self.lock_index.notify_change(&key, value.as_deref());
+            }
+            ConfigCmd::QueryLockList(tenant) => {
+                return Ok(ConfigResult::LockList(self.lock_index.query_list(&tenant)));
+            }
+            ConfigCmd::QueryNamespaceUsage => {
+                return Ok(ConfigResult::NamespaceUsage(self.query_namespace_usage()));
+            }
+            ConfigCmd::QueryClientSubscribeKeys(client_id) => {
+                let keys = self.subscriber.get_client_keys(&client_id);
+                return Ok(ConfigResult::ClientSubscribeKeys(keys));
+            }
+            ConfigCmd::QueryListenerByKey(key) => {
+                return Ok(ConfigResult::ListenerInfoList(
+                    self.query_listener_by_key(&key),
+                ));
+            }
+            ConfigCmd::QueryListenerByIp(client_ip) => {
+                return Ok(ConfigResult::ListenerInfoList(
+                    self.query_listener_by_ip(&client_ip),
+                ));
+            }
             ConfigCmd::QueryPageInfo(config_query_param) => {
                 let (size, list) = self.get_config_info_page(config_query_param.as_ref());
                 return Ok(ConfigResult::ConfigInfoPage(size, list));
@@ -847,6 +1294,11 @@ impl Handler<ConfigCmd> for ConfigActor {
                 let (size, list) = self.get_config_info_by_keys(config_keys.as_ref());
                 return Ok(ConfigResult::ConfigInfoPage(size, list));
             }
+            ConfigCmd::QueryRollbackInfo(key, history_id) => {
+                return Ok(ConfigResult::RollbackInfo(
+                    self.query_rollback_info(&key, history_id),
+                ));
+            }
             ConfigCmd::QueryHistoryPageInfo(query_param) => {
                 let (size, list) = self.get_history_info_page(query_param.as_ref());
                 return Ok(ConfigResult::ConfigHistoryInfoPage(size, list));
@@ -868,6 +1320,17 @@ impl Handler<ConfigAsyncCmd> for ConfigActor {
 
     fn handle(&mut self, msg: ConfigAsyncCmd, _ctx: &mut Context<Self>) -> Self::Result {
         let raft = self.raft.clone();
+        // 写入raft前校验锁与配额,避免被拒绝的请求被提交
+        let check_result = match &msg {
+            ConfigAsyncCmd::Add { key, value, .. } => self
+                .lock_index
+                .check_writable(key)
+                .and_then(|_| self.check_config_quota(key, value.len())),
+            ConfigAsyncCmd::Delete(key) => self.lock_index.check_writable(key),
+        };
+        if let Err(err) = check_result {
+            return Box::pin(actix::fut::ready(Err(err)));
+        }
         let history_info = if let ConfigAsyncCmd::Add { .. } = &msg {
             self.sequence.next_state().ok()
         } else {
@@ -881,6 +1344,8 @@ impl Handler<ConfigAsyncCmd> for ConfigActor {
                     op_user,
                     config_type,
                     desc,
+                    rollback_from,
+                    clone_from,
                 } => {
                     if let Some((history_id, history_table_id)) = history_info {
                         let req = ClientRequest::ConfigSet {
@@ -892,6 +1357,8 @@ impl Handler<ConfigAsyncCmd> for ConfigActor {
                             history_table_id,
                             op_time: now_millis_i64(),
                             op_user,
+                            rollback_from,
+                            clone_from,
                         };
                         Self::send_raft_request(&raft, req).await.ok();
                     }
@@ -925,6 +1392,8 @@ impl Handler<ConfigRaftCmd> for ConfigActor {
                 history_table_id,
                 op_time,
                 op_user,
+                rollback_from,
+                clone_from,
             } => {
                 let key: ConfigKey = (&key as &str).into();
                 let param = SetConfigParam {
@@ -937,6 +1406,8 @@ impl Handler<ConfigRaftCmd> for ConfigActor {
                     history_table_id,
                     op_time,
                     op_user,
+                    rollback_from,
+                    clone_from,
                 };
                 self.set_config(param).ok();
             }
@@ -0,0 +1,195 @@
+use crate::common::constant::EMPTY_ARC_STRING;
+use crate::config::core::ConfigKey;
+use crate::config::ConfigUtils;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+///
+/// 配置只读锁,data_id为空时锁定整个分组
+/// 锁定后所有写入途径(控制台、openapi、sdk)的发布与删除都会被拒绝
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigLockDo {
+    pub tenant: Arc<String>,
+    pub group: Arc<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_id: Option<Arc<String>>,
+    pub reason: Option<Arc<String>>,
+    pub lock_user: Option<Arc<String>>,
+    pub lock_time: i64,
+}
+
+impl ConfigLockDo {
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn from_bytes(v: &[u8]) -> anyhow::Result<Self> {
+        Ok(serde_json::from_slice(v)?)
+    }
+
+    pub fn get_lock_key(&self) -> ConfigKey {
+        ConfigKey::new_by_arc(
+            self.data_id.clone().unwrap_or(EMPTY_ARC_STRING.clone()),
+            self.group.clone(),
+            self.tenant.clone(),
+        )
+    }
+
+    pub fn is_group_lock(&self) -> bool {
+        self.data_id.is_none()
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigLockParam {
+    pub tenant: Option<String>,
+    pub group: Option<Arc<String>>,
+    pub data_id: Option<Arc<String>>,
+    pub reason: Option<Arc<String>>,
+}
+
+impl ConfigLockParam {
+    pub fn get_tenant(&self) -> Arc<String> {
+        Arc::new(ConfigUtils::default_tenant(
+            self.tenant.clone().unwrap_or_default(),
+        ))
+    }
+
+    pub fn get_data_id(&self) -> Option<Arc<String>> {
+        self.data_id.clone().filter(|e| !e.is_empty())
+    }
+
+    pub fn check(&self) -> anyhow::Result<()> {
+        if self.group.as_ref().map(|e| e.is_empty()).unwrap_or(true) {
+            return Err(anyhow::anyhow!("group is empty"));
+        }
+        Ok(())
+    }
+
+    pub fn to_lock_key(&self) -> ConfigKey {
+        ConfigKey::new_by_arc(
+            self.get_data_id().unwrap_or(EMPTY_ARC_STRING.clone()),
+            self.group.clone().unwrap_or_default(),
+            self.get_tenant(),
+        )
+    }
+}
+
+///
+/// 配置锁索引,锁记录通过raft表同步,本地只做内存判断
+#[derive(Debug, Default)]
+pub struct ConfigLockIndex {
+    locks: HashMap<ConfigKey, Arc<ConfigLockDo>>,
+}
+
+impl ConfigLockIndex {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn notify_change(&mut self, key: &[u8], value: Option<&[u8]>) {
+        let lock_key: ConfigKey = String::from_utf8_lossy(key).as_ref().into();
+        match value {
+            Some(value) => match ConfigLockDo::from_bytes(value) {
+                Ok(item) => {
+                    self.locks.insert(lock_key, Arc::new(item));
+                }
+                Err(err) => {
+                    log::error!("parse config lock error:{}", err);
+                }
+            },
+            None => {
+                self.locks.remove(&lock_key);
+            }
+        }
+    }
+
+    ///
+    /// 获取配置生效的锁,优先返回配置本身的锁,其次是所在分组的锁
+    pub fn get_lock(&self, key: &ConfigKey) -> Option<&Arc<ConfigLockDo>> {
+        if self.locks.is_empty() {
+            return None;
+        }
+        self.locks.get(key).or_else(|| {
+            let group_key = ConfigKey::new_by_arc(
+                EMPTY_ARC_STRING.clone(),
+                key.group.clone(),
+                key.tenant.clone(),
+            );
+            self.locks.get(&group_key)
+        })
+    }
+
+    pub fn check_writable(&self, key: &ConfigKey) -> anyhow::Result<()> {
+        if let Some(lock) = self.get_lock(key) {
+            let target = if lock.is_group_lock() {
+                "group"
+            } else {
+                "config"
+            };
+            return Err(anyhow::anyhow!(
+                "the {} is locked, dataId:{}, group:{}, reason:{}",
+                target,
+                &key.data_id,
+                &key.group,
+                lock.reason.as_ref().map(|e| e.as_str()).unwrap_or_default()
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn query_list(&self, tenant: &Option<Arc<String>>) -> Vec<Arc<ConfigLockDo>> {
+        let mut list: Vec<Arc<ConfigLockDo>> = self
+            .locks
+            .values()
+            .filter(|e| tenant.as_ref().map(|t| t == &e.tenant).unwrap_or(true))
+            .cloned()
+            .collect();
+        list.sort_by_key(|e| std::cmp::Reverse(e.lock_time));
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lock_bytes(tenant: &str, group: &str, data_id: Option<&str>) -> (Vec<u8>, Vec<u8>) {
+        let lock = ConfigLockDo {
+            tenant: Arc::new(tenant.to_owned()),
+            group: Arc::new(group.to_owned()),
+            data_id: data_id.map(|e| Arc::new(e.to_owned())),
+            ..Default::default()
+        };
+        (
+            lock.get_lock_key().build_key().into_bytes(),
+            lock.to_bytes().unwrap(),
+        )
+    }
+
+    #[test]
+    fn check_config_lock() {
+        let mut index = ConfigLockIndex::new();
+        let a = ConfigKey::new("a", "g1", "prod");
+        let b = ConfigKey::new("b", "g1", "prod");
+        let c = ConfigKey::new("a", "g1", "");
+        let (key, value) = lock_bytes("prod", "g1", Some("a"));
+        index.notify_change(&key, Some(&value));
+        assert!(index.check_writable(&a).is_err());
+        assert!(index.check_writable(&b).is_ok());
+        assert!(index.check_writable(&c).is_ok());
+        let (group_key, value) = lock_bytes("prod", "g1", None);
+        index.notify_change(&group_key, Some(&value));
+        assert!(index.check_writable(&b).is_err());
+        assert_eq!(
+            index.query_list(&Some(Arc::new("prod".to_owned()))).len(),
+            2
+        );
+        index.notify_change(&key, None);
+        index.notify_change(&group_key, None);
+        assert!(index.check_writable(&a).is_ok());
+    }
+}
@@ -1,7 +1,34 @@
-use crate::config::core::ConfigActor;
+use crate::config::core::{ConfigActor, ConfigKey, ConfigValue};
+use crate::config::model::HistoryItem;
+use crate::metrics::dump::{
+    entry_bytes, str_bytes, top_n_items, ActorDump, DebugDumpQuery, StructureSize,
+};
+use crate::metrics::labeled::MetricsLabels;
 use crate::metrics::metrics_key::MetricsKey;
-use crate::metrics::model::{MetricsItem, MetricsQuery, MetricsRecord};
+use crate::metrics::model::{
+    LabeledMetricsItem, LabeledMetricsQuery, MetricsItem, MetricsQuery, MetricsRecord,
+};
+use crate::namespace::DEFAULT_NAMESPACE;
 use actix::prelude::*;
+use std::collections::HashSet;
+use std::mem::size_of;
+use std::sync::Arc;
+
+fn config_key_bytes(key: &ConfigKey) -> usize {
+    str_bytes(&key.data_id) + str_bytes(&key.group) + str_bytes(&key.tenant)
+}
+
+fn history_bytes(value: &ConfigValue) -> usize {
+    value
+        .histories
+        .iter()
+        .map(|e| {
+            size_of::<HistoryItem>()
+                + str_bytes(&e.content)
+                + e.op_user.as_ref().map(|v| str_bytes(v)).unwrap_or_default()
+        })
+        .sum()
+}
 
 impl Handler<MetricsQuery> for ConfigActor {
     type Result = anyhow::Result<Vec<MetricsItem>>;
@@ -48,3 +75,95 @@ impl Handler<MetricsQuery> for ConfigActor {
         Ok(list)
     }
 }
+
+impl Handler<LabeledMetricsQuery> for ConfigActor {
+    type Result = anyhow::Result<Vec<LabeledMetricsItem>>;
+
+    fn handle(&mut self, _msg: LabeledMetricsQuery, _ctx: &mut Self::Context) -> Self::Result {
+        let list = self
+            .query_namespace_usage()
+            .into_iter()
+            .map(|(tenant, usage)| {
+                let namespace = if tenant.is_empty() {
+                    DEFAULT_NAMESPACE.to_owned()
+                } else {
+                    tenant.as_ref().to_owned()
+                };
+                LabeledMetricsItem::new(
+                    MetricsKey::ConfigNamespaceDataSize,
+                    MetricsLabels::default().with("namespace", namespace),
+                    MetricsRecord::Gauge(usage.config_count as f32),
+                )
+            })
+            .collect();
+        Ok(list)
+    }
+}
+
+impl Handler<DebugDumpQuery> for ConfigActor {
+    type Result = anyhow::Result<ActorDump>;
+
+    fn handle(&mut self, msg: DebugDumpQuery, _ctx: &mut Self::Context) -> Self::Result {
+        let mut config_bytes = 0;
+        let mut history_count = 0;
+        let mut history_sum = 0;
+        let mut config_sizes = Vec::with_capacity(self.cache.len());
+        for (key, value) in &self.cache {
+            let content_bytes = str_bytes(&value.content)
+                + str_bytes(&value.md5)
+                + value
+                    .desc
+                    .as_ref()
+                    .map(|e| str_bytes(e))
+                    .unwrap_or_default();
+            let histories = history_bytes(value);
+            config_bytes +=
+                entry_bytes::<ConfigKey, ConfigValue>() + config_key_bytes(key) + content_bytes;
+            history_count += value.histories.len();
+            history_sum += histories;
+            let namespace = if key.tenant.is_empty() {
+                DEFAULT_NAMESPACE
+            } else {
+                key.tenant.as_str()
+            };
+            config_sizes.push((
+                content_bytes + histories,
+                format!("{}#{}#{}", namespace, &key.group, &key.data_id),
+            ));
+        }
+        let (group_size, config_size) = self.tenant_index.get_config_count();
+        let tenant_index_bytes = self
+            .tenant_index
+            .tenant_group
+            .keys()
+            .map(|e| str_bytes(e))
+            .sum::<usize>()
+            + group_size * entry_bytes::<Arc<String>, HashSet<Arc<String>>>()
+            + config_size * size_of::<Arc<String>>();
+        let listener_key_size = self.listener.get_listener_key_size();
+        let listener_client_size = self.listener.get_listener_client_size();
+        let subscriber_key_size = self.subscriber.get_listener_key_size();
+        let subscriber_value_size = self.subscriber.get_listener_value_size();
+        let subscriber_client_value_size = self.subscriber.get_client_value_size();
+        let structures = vec![
+            StructureSize::new("config_map", self.cache.len(), config_bytes),
+            StructureSize::new("config_histories", history_count, history_sum),
+            StructureSize::new("tenant_index", config_size, tenant_index_bytes),
+            StructureSize::new(
+                "config_listener",
+                listener_key_size,
+                listener_key_size * entry_bytes::<ConfigKey, Vec<u64>>()
+                    + listener_client_size * entry_bytes::<u64, u64>(),
+            ),
+            StructureSize::new(
+                "config_subscriber",
+                subscriber_key_size,
+                subscriber_key_size * entry_bytes::<ConfigKey, HashSet<Arc<String>>>()
+                    + (subscriber_value_size + subscriber_client_value_size)
+                        * entry_bytes::<ConfigKey, Arc<String>>(),
+            ),
+        ];
+        Ok(ActorDump::new("ConfigActor", &msg, structures)
+            .with_top_items(top_n_items(config_sizes.into_iter(), msg.top_n)))
+    }
+}
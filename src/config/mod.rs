@@ -1,16 +1,21 @@
 use crate::common::constant::EMPTY_ARC_STRING;
 use std::sync::Arc;
 
+pub mod approval;
 pub mod config_db;
 pub mod config_index;
+pub mod config_ref;
 pub mod config_sled;
 pub mod config_subscribe;
 pub mod config_type;
 pub mod core;
 pub mod dal;
+pub mod lock;
 pub mod metrics;
 pub mod model;
+pub mod schema;
 pub mod utils;
+pub mod validate;
 
 pub struct ConfigUtils;
 
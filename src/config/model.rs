@@ -17,6 +17,8 @@ pub enum ConfigRaftCmd {
         history_table_id: Option<u64>,
         op_time: i64,
         op_user: Option<Arc<String>>,
+        rollback_from: Option<u64>,
+        clone_from: Option<Arc<String>>,
     },
     ConfigRemove {
         key: String,
@@ -38,6 +40,8 @@ pub struct SetConfigParam {
     pub history_table_id: Option<u64>,
     pub op_time: i64,
     pub op_user: Option<Arc<String>>,
+    pub rollback_from: Option<u64>,
+    pub clone_from: Option<Arc<String>>,
 }
 
 pub enum ConfigRaftResult {
@@ -54,6 +58,10 @@ pub struct HistoryItem {
     pub content: Arc<String>,
     pub modified_time: i64,
     pub op_user: Option<Arc<String>>,
+    /// 回滚操作产生的历史记录,记录来源的历史版本id
+    pub rollback_from: Option<u64>,
+    /// 从其它命名空间克隆产生的历史记录,记录来源配置
+    pub clone_from: Option<Arc<String>>,
 }
 
 impl HistoryItem {
@@ -66,6 +74,8 @@ impl HistoryItem {
             content: Some(self.content.to_string()),
             modified_time: Some(self.modified_time),
             op_user: self.op_user.as_ref().map(|e| e.to_string()),
+            rollback_from: self.rollback_from.map(|e| e as i64),
+            clone_from: self.clone_from.clone(),
         }
     }
 }
@@ -80,6 +90,10 @@ pub struct ConfigHistoryItemDO {
     pub last_time: Option<i64>,
     #[prost(string, optional, tag = "4")]
     pub op_user: Option<String>,
+    #[prost(uint64, optional, tag = "5")]
+    pub rollback_from: Option<u64>,
+    #[prost(string, optional, tag = "6")]
+    pub clone_from: Option<String>,
 }
 
 impl From<HistoryItem> for ConfigHistoryItemDO {
@@ -89,6 +103,8 @@ impl From<HistoryItem> for ConfigHistoryItemDO {
             content: Some(value.content.as_ref().to_string()),
             last_time: Some(value.modified_time),
             op_user: value.op_user.map(|e| e.as_ref().to_string()),
+            rollback_from: value.rollback_from,
+            clone_from: value.clone_from.map(|e| e.as_ref().to_string()),
         }
     }
 }
@@ -100,6 +116,8 @@ impl From<ConfigHistoryItemDO> for HistoryItem {
             content: Arc::new(value.content.unwrap_or_default()),
             modified_time: value.last_time.unwrap_or_default(),
             op_user: value.op_user.map(Arc::new),
+            rollback_from: value.rollback_from,
+            clone_from: value.clone_from.map(Arc::new),
         }
     }
 }
@@ -164,3 +182,200 @@ impl From<ConfigValueDO> for ConfigValue {
         }
     }
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ListenerType {
+    Http,
+    Grpc,
+}
+
+///
+/// 配置监听者信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigListenerInfo {
+    pub client_ip: Arc<String>,
+    /// grpc连接id,http长轮询时为空
+    pub client_id: Arc<String>,
+    pub listener_type: ListenerType,
+    pub data_id: Arc<String>,
+    pub group: Arc<String>,
+    pub tenant: Arc<String>,
+    /// 客户端监听时上报的md5
+    pub md5: Arc<String>,
+    /// 服务端当前配置md5
+    pub server_md5: Arc<String>,
+    /// 客户端md5是否落后于服务端
+    pub md5_stale: bool,
+}
+
+impl ConfigListenerInfo {
+    pub fn new(
+        key: &ConfigKey,
+        listener_type: ListenerType,
+        client_ip: Arc<String>,
+        client_id: Arc<String>,
+        md5: Arc<String>,
+        server_md5: Arc<String>,
+    ) -> Self {
+        let md5_stale = md5 != server_md5;
+        Self {
+            client_ip,
+            client_id,
+            listener_type,
+            data_id: key.data_id.clone(),
+            group: key.group.clone(),
+            tenant: key.tenant.clone(),
+            md5,
+            server_md5,
+            md5_stale,
+        }
+    }
+}
+
+///
+/// 配置回滚预览信息
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigRollbackInfo {
+    pub history_id: u64,
+    pub current_content: Arc<String>,
+    pub history_content: Arc<String>,
+    pub config_type: Option<Arc<String>>,
+    pub desc: Option<Arc<String>>,
+    /// 当前内容到历史版本内容的unified diff
+    pub diff: String,
+    /// 历史版本内容与当前内容一致
+    pub same_content: bool,
+}
+
+impl ConfigRollbackInfo {
+    pub fn new(
+        history_id: u64,
+        current_content: Arc<String>,
+        history_content: Arc<String>,
+    ) -> Self {
+        let diff = build_unified_diff(&current_content, &history_content, history_id);
+        let same_content = current_content == history_content;
+        Self {
+            history_id,
+            current_content,
+            history_content,
+            diff,
+            same_content,
+            ..Default::default()
+        }
+    }
+}
+
+///
+/// 克隆配置时目标命名空间已存在不同内容配置的处理策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfigClonePolicy {
+    /// 存在冲突时整体放弃
+    #[default]
+    Abort,
+    Skip,
+    Overwrite,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfigCloneStatus {
+    /// 目标命名空间不存在
+    New,
+    /// 目标配置内容与来源一致
+    Same,
+    /// 目标配置内容与来源不一致
+    Conflict,
+}
+
+///
+/// 配置克隆预览项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigCloneItem {
+    pub group: Arc<String>,
+    pub data_id: Arc<String>,
+    pub status: ConfigCloneStatus,
+    /// 目标内容到来源内容的unified diff,只在冲突时返回
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diff: Option<String>,
+}
+
+impl ConfigCloneItem {
+    pub fn new(
+        key: &ConfigKey,
+        source_content: &str,
+        target_content: Option<&Arc<String>>,
+    ) -> Self {
+        let (status, diff) = match target_content {
+            None => (ConfigCloneStatus::New, None),
+            Some(v) if v.as_str() == source_content => (ConfigCloneStatus::Same, None),
+            Some(v) => {
+                let diff = similar::TextDiff::from_lines(v.as_str(), source_content)
+                    .unified_diff()
+                    .context_radius(3)
+                    .header("target", "source")
+                    .to_string();
+                (ConfigCloneStatus::Conflict, Some(diff))
+            }
+        };
+        Self {
+            group: key.group.clone(),
+            data_id: key.data_id.clone(),
+            status,
+            diff,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigCloneResult {
+    pub created: usize,
+    pub overwritten: usize,
+    pub skipped: usize,
+    pub unchanged: usize,
+}
+
+pub fn build_unified_diff(current_content: &str, history_content: &str, history_id: u64) -> String {
+    similar::TextDiff::from_lines(current_content, history_content)
+        .unified_diff()
+        .context_radius(3)
+        .header("current", &format!("history-{}", history_id))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rollback_diff() {
+        let info = ConfigRollbackInfo::new(
+            3,
+            Arc::new("a=1\nb=2\n".to_owned()),
+            Arc::new("a=1\nb=1\n".to_owned()),
+        );
+        assert!(!info.same_content);
+        assert!(info.diff.starts_with("--- current\n+++ history-3\n"));
+        assert!(info.diff.contains("-b=2\n+b=1\n"));
+        let info = ConfigRollbackInfo::new(3, Arc::new("a".to_owned()), Arc::new("a".to_owned()));
+        assert!(info.same_content);
+        assert!(info.diff.is_empty());
+    }
+    #[test]
+    fn clone_item_status() {
+        let key = ConfigKey::new("app.yaml", "DEFAULT_GROUP", "prod");
+        let item = ConfigCloneItem::new(&key, "a=1\n", None);
+        assert_eq!(item.status, ConfigCloneStatus::New);
+        let item = ConfigCloneItem::new(&key, "a=1\n", Some(&Arc::new("a=1\n".to_owned())));
+        assert_eq!(item.status, ConfigCloneStatus::Same);
+        let item = ConfigCloneItem::new(&key, "a=1\n", Some(&Arc::new("a=2\n".to_owned())));
+        assert_eq!(item.status, ConfigCloneStatus::Conflict);
+        assert!(item.diff.unwrap().contains("-a=2\n+a=1\n"));
+    }
+}
@@ -0,0 +1,199 @@
+use crate::config::config_type::ConfigType;
+use crate::config::core::ConfigKey;
+use crate::config::schema::model::{
+    content_to_json_value, ConfigSchemaDo, ConfigSchemaReq, ConfigSchemaResult,
+};
+use actix::prelude::*;
+use jsonschema::JSONSchema;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 单次校验最多返回的错误数
+const MAX_ERROR_COUNT: usize = 20;
+
+struct SchemaItem {
+    info: Arc<ConfigSchemaDo>,
+    compiled: Option<Arc<JSONSchema>>,
+}
+
+///
+/// 配置JSON Schema约束管理器
+/// 规则通过raft同步到各节点,本地缓存编译后的schema
+#[derive(Default)]
+pub struct ConfigSchemaManager {
+    schemas: HashMap<Arc<String>, SchemaItem>,
+}
+
+impl ConfigSchemaManager {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    fn notify_change(&mut self, key: Vec<u8>, value: Option<Vec<u8>>) {
+        let id = Arc::new(String::from_utf8_lossy(&key).to_string());
+        let value = match value {
+            Some(v) => v,
+            None => {
+                self.schemas.remove(&id);
+                return;
+            }
+        };
+        let info = match ConfigSchemaDo::from_bytes(&value) {
+            Ok(v) => Arc::new(v),
+            Err(err) => {
+                log::error!("parse config schema error:{}", err);
+                return;
+            }
+        };
+        let compiled = match serde_json::from_str(&info.schema)
+            .map_err(anyhow::Error::from)
+            .and_then(|v| JSONSchema::compile(&v).map_err(|err| anyhow::anyhow!(err.to_string())))
+        {
+            Ok(v) => Some(Arc::new(v)),
+            Err(err) => {
+                log::error!("compile config schema error, id:{}, {}", &id, err);
+                None
+            }
+        };
+        self.schemas.insert(id, SchemaItem { info, compiled });
+    }
+
+    fn validate(
+        &self,
+        key: &ConfigKey,
+        config_type: &ConfigType,
+        content: &str,
+    ) -> anyhow::Result<()> {
+        let mut matched = self
+            .schemas
+            .values()
+            .filter(|e| e.info.match_key(key))
+            .peekable();
+        if matched.peek().is_none() {
+            return Ok(());
+        }
+        let value = match content_to_json_value(config_type, content) {
+            Ok(Some(v)) => v,
+            Ok(None) => return Ok(()),
+            Err(err) => {
+                return Err(anyhow::anyhow!(
+                    "config content can't convert to json for schema validate: {}",
+                    err
+                ))
+            }
+        };
+        for item in matched {
+            let compiled = match &item.compiled {
+                Some(v) => v,
+                None => continue,
+            };
+            if let Err(errors) = compiled.validate(&value) {
+                let errors: Vec<String> = errors
+                    .take(MAX_ERROR_COUNT)
+                    .map(|e| {
+                        let path = e.instance_path.to_string();
+                        format!("{}: {}", if path.is_empty() { "/" } else { &path }, e)
+                    })
+                    .collect();
+                return Err(anyhow::anyhow!(
+                    "config content violates schema {}: {}",
+                    &item.info.id,
+                    errors.join("; ")
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn query_list(&self, tenant: &Option<Arc<String>>) -> Vec<Arc<ConfigSchemaDo>> {
+        let mut list: Vec<Arc<ConfigSchemaDo>> = self
+            .schemas
+            .values()
+            .filter(|e| tenant.as_ref().map(|t| t == &e.info.tenant).unwrap_or(true))
+            .map(|e| e.info.clone())
+            .collect();
+        list.sort_by_key(|e| std::cmp::Reverse(e.update_time));
+        list
+    }
+}
+
+impl Actor for ConfigSchemaManager {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _ctx: &mut Self::Context) {
+        log::info!("ConfigSchemaManager started");
+    }
+}
+
+impl Handler<ConfigSchemaReq> for ConfigSchemaManager {
+    type Result = anyhow::Result<ConfigSchemaResult>;
+
+    fn handle(&mut self, msg: ConfigSchemaReq, _ctx: &mut Self::Context) -> Self::Result {
+        match msg {
+            ConfigSchemaReq::Validate {
+                key,
+                config_type,
+                content,
+            } => {
+                self.validate(&key, &config_type, &content)?;
+                Ok(ConfigSchemaResult::None)
+            }
+            ConfigSchemaReq::NotifyChange { key, value } => {
+                self.notify_change(key, value);
+                Ok(ConfigSchemaResult::None)
+            }
+            ConfigSchemaReq::QueryList { tenant } => {
+                Ok(ConfigSchemaResult::List(self.query_list(&tenant)))
+            }
+            ConfigSchemaReq::Query(id) => Ok(ConfigSchemaResult::Schema(
+                self.schemas.get(&id).map(|e| e.info.clone()),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::model::match_pattern;
+
+    #[test]
+    fn validate_schema() {
+        let mut manager = ConfigSchemaManager::new();
+        let schema = r#"{"type":"object","required":["port"],"properties":{"port":{"type":"integer","maximum":65535}}}"#;
+        let item = ConfigSchemaDo {
+            id: Arc::new("s1".to_owned()),
+            tenant: Arc::new("".to_owned()),
+            group: Arc::new("*".to_owned()),
+            data_id_pattern: Arc::new("app-*.yaml".to_owned()),
+            schema: Arc::new(schema.to_owned()),
+            ..Default::default()
+        };
+        manager.notify_change(b"s1".to_vec(), Some(item.to_bytes().unwrap()));
+        let key = ConfigKey::new("app-a.yaml", "DEFAULT_GROUP", "");
+        assert!(manager
+            .validate(&key, &ConfigType::Yaml, "port: 8080\n")
+            .is_ok());
+        let err = manager
+            .validate(&key, &ConfigType::Yaml, "port: 70000\n")
+            .unwrap_err();
+        assert!(err.to_string().contains("/port"));
+        assert!(manager
+            .validate(&key, &ConfigType::Toml, "name = \"a\"\n")
+            .is_err());
+        let other = ConfigKey::new("other.yaml", "DEFAULT_GROUP", "");
+        assert!(manager
+            .validate(&other, &ConfigType::Yaml, "port: 70000\n")
+            .is_ok());
+        manager.notify_change(b"s1".to_vec(), None);
+        assert!(manager
+            .validate(&key, &ConfigType::Yaml, "port: 70000\n")
+            .is_ok());
+
+        assert!(match_pattern("*", "a"));
+        assert!(match_pattern("a*b*c", "a1b2c"));
+        assert!(!match_pattern("a*b", "a1c"));
+        assert!(match_pattern("app.yaml", "app.yaml"));
+        assert!(!match_pattern("app", "app.yaml"));
+    }
+}
@@ -0,0 +1,217 @@
+use crate::common::model::privilege::NamespacePrivilegeGroup;
+use crate::config::config_type::ConfigType;
+use crate::config::core::ConfigKey;
+use actix::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::sync::Arc;
+
+/// 匹配全部分组或dataId
+pub const MATCH_ALL: &str = "*";
+
+///
+/// 配置JSON Schema约束规则;
+/// 按命名空间、分组及dataId通配符匹配配置,发布时校验内容
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigSchemaDo {
+    pub id: Arc<String>,
+    pub tenant: Arc<String>,
+    /// 分组,`*`表示全部分组
+    pub group: Arc<String>,
+    /// dataId匹配规则,支持`*`通配符
+    pub data_id_pattern: Arc<String>,
+    /// JSON Schema内容
+    pub schema: Arc<String>,
+    pub desc: Option<Arc<String>>,
+    pub op_user: Option<Arc<String>>,
+    pub create_time: i64,
+    pub update_time: i64,
+}
+
+impl ConfigSchemaDo {
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn from_bytes(v: &[u8]) -> anyhow::Result<Self> {
+        Ok(serde_json::from_slice(v)?)
+    }
+
+    pub fn match_key(&self, key: &ConfigKey) -> bool {
+        self.tenant.as_str() == key.tenant.as_str()
+            && (self.group.as_str() == MATCH_ALL || self.group.as_str() == key.group.as_str())
+            && match_pattern(&self.data_id_pattern, &key.data_id)
+    }
+}
+
+///
+/// 通配符匹配,`*`匹配任意长度字符
+pub fn match_pattern(pattern: &str, value: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let mut rest = match value.strip_prefix(first) {
+        Some(v) => v,
+        None => return false,
+    };
+    let parts: Vec<&str> = parts.collect();
+    if parts.is_empty() {
+        return rest.is_empty();
+    }
+    let (last, middle) = parts.split_last().unwrap();
+    for part in middle {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+///
+/// 把配置内容转为JSON值;不支持的类型返回None
+/// properties转为扁平对象,值均为字符串
+pub fn content_to_json_value(
+    config_type: &ConfigType,
+    content: &str,
+) -> anyhow::Result<Option<Value>> {
+    let value = match config_type {
+        ConfigType::Json => serde_json::from_str(content)?,
+        ConfigType::Yaml => {
+            serde_json::to_value(serde_yml::from_str::<serde_yml::Value>(content)?)?
+        }
+        ConfigType::Toml => serde_json::to_value(toml::from_str::<toml::Table>(content)?)?,
+        ConfigType::Properties => Value::Object(parse_properties(content)),
+        ConfigType::Text | ConfigType::Xml | ConfigType::Html => return Ok(None),
+    };
+    Ok(Some(value))
+}
+
+fn parse_properties(content: &str) -> Map<String, Value> {
+    let mut map = Map::new();
+    let mut logical_line = String::new();
+    for line in content.lines() {
+        let line = line.trim_start();
+        if logical_line.is_empty()
+            && (line.is_empty() || line.starts_with('#') || line.starts_with('!'))
+        {
+            continue;
+        }
+        // 以奇数个`\`结尾表示续行
+        let tail_slash = line.len() - line.trim_end_matches('\\').len();
+        if tail_slash % 2 == 1 {
+            logical_line.push_str(&line[..line.len() - 1]);
+            continue;
+        }
+        logical_line.push_str(line);
+        let (key, value) = split_property(&logical_line);
+        map.insert(key, Value::String(value));
+        logical_line.clear();
+    }
+    if !logical_line.is_empty() {
+        let (key, value) = split_property(&logical_line);
+        map.insert(key, Value::String(value));
+    }
+    map
+}
+
+fn split_property(line: &str) -> (String, String) {
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' => escaped = true,
+            '=' | ':' | ' ' | '\t' => {
+                let key = line[..i].to_owned();
+                let value = line[i + 1..]
+                    .trim_start_matches([' ', '\t'])
+                    .trim_start_matches(['=', ':'])
+                    .trim_start();
+                return (key, value.to_owned());
+            }
+            _ => {}
+        }
+    }
+    (line.to_owned(), String::new())
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigSchemaParam {
+    pub id: Option<Arc<String>>,
+    pub tenant: Option<Arc<String>>,
+    pub group: Option<Arc<String>>,
+    pub data_id_pattern: Option<Arc<String>>,
+    pub schema: Option<Arc<String>>,
+    pub desc: Option<Arc<String>>,
+}
+
+impl ConfigSchemaParam {
+    pub fn get_tenant(&self) -> Arc<String> {
+        match &self.tenant {
+            Some(v) if v.as_str() != "public" => v.clone(),
+            _ => Arc::new(String::new()),
+        }
+    }
+
+    ///
+    /// 校验规则参数,并确认schema可以编译
+    pub fn check(&self, namespace_privilege: &NamespacePrivilegeGroup) -> anyhow::Result<()> {
+        let tenant = self.get_tenant();
+        if !namespace_privilege.check_permission(&tenant) {
+            return Err(anyhow::anyhow!(
+                "user no such namespace permission: {}",
+                &tenant
+            ));
+        }
+        if self.group.as_ref().map(|e| e.is_empty()).unwrap_or(true) {
+            return Err(anyhow::anyhow!("group is empty"));
+        }
+        if self
+            .data_id_pattern
+            .as_ref()
+            .map(|e| e.is_empty())
+            .unwrap_or(true)
+        {
+            return Err(anyhow::anyhow!("dataIdPattern is empty"));
+        }
+        let schema = match &self.schema {
+            Some(v) if !v.is_empty() => v,
+            _ => return Err(anyhow::anyhow!("schema is empty")),
+        };
+        let schema: Value = serde_json::from_str(schema)?;
+        jsonschema::JSONSchema::compile(&schema)
+            .map_err(|err| anyhow::anyhow!("invalid json schema: {}", err))?;
+        Ok(())
+    }
+}
+
+#[derive(Message, Clone, Debug)]
+#[rtype(result = "anyhow::Result<ConfigSchemaResult>")]
+pub enum ConfigSchemaReq {
+    /// 校验配置内容是否满足匹配的schema
+    Validate {
+        key: ConfigKey,
+        config_type: ConfigType,
+        content: Arc<String>,
+    },
+    /// raft数据变更通知
+    NotifyChange {
+        key: Vec<u8>,
+        value: Option<Vec<u8>>,
+    },
+    QueryList {
+        tenant: Option<Arc<String>>,
+    },
+    Query(Arc<String>),
+}
+
+#[derive(Clone, Debug)]
+pub enum ConfigSchemaResult {
+    None,
+    List(Vec<Arc<ConfigSchemaDo>>),
+    Schema(Option<Arc<ConfigSchemaDo>>),
+}
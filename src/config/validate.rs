@@ -0,0 +1,361 @@
+use crate::common::appdata::AppShareData;
+use crate::common::AppSysConfig;
+use crate::config::config_ref::CONFIG_REF_PREFIX;
+use crate::config::config_type::ConfigType;
+use crate::config::core::{ConfigCmd, ConfigKey};
+use crate::config::schema::model::ConfigSchemaReq;
+use crate::raft::cluster::model::SetConfigReq;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+///
+/// 配置内容发布前的语法校验模式
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum ConfigValidateMode {
+    /// 不校验
+    #[default]
+    Off,
+    /// 校验失败只打印告警日志,仍然发布
+    Warn,
+    /// 校验失败拒绝发布
+    Strict,
+}
+
+impl ConfigValidateMode {
+    pub fn new_by_value(v: &str) -> Option<Self> {
+        match v.trim().to_lowercase().as_str() {
+            "off" => Some(Self::Off),
+            "warn" => Some(Self::Warn),
+            "strict" => Some(Self::Strict),
+            _ => None,
+        }
+    }
+
+    ///
+    /// 解析命名空间的校验模式,格式: namespace:mode,namespace2:mode
+    /// 如: public:warn,prod:strict
+    pub fn parse_namespace_modes(v: &str) -> HashMap<String, Self> {
+        let mut map = HashMap::new();
+        for item in v.split(',') {
+            if let Some((namespace, mode)) = item.split_once(':') {
+                if let Some(mode) = Self::new_by_value(mode) {
+                    map.insert(normalize_namespace(namespace.trim()).to_owned(), mode);
+                }
+            }
+        }
+        map
+    }
+}
+
+fn normalize_namespace(namespace: &str) -> &str {
+    if namespace == "public" {
+        ""
+    } else {
+        namespace
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ConfigSyntaxError {
+    pub config_type: ConfigType,
+    /// 行号,从1开始;0表示无法定位
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ConfigSyntaxError {
+    fn new(config_type: ConfigType, line: usize, column: usize, message: String) -> Self {
+        Self {
+            config_type,
+            line,
+            column,
+            message,
+        }
+    }
+
+    fn new_by_offset(
+        config_type: ConfigType,
+        content: &str,
+        offset: usize,
+        message: String,
+    ) -> Self {
+        let (line, column) = get_line_column(content, offset);
+        Self::new(config_type, line, column, message)
+    }
+}
+
+impl Display for ConfigSyntaxError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid {} content at line {}, column {}: {}",
+            self.config_type.get_value(),
+            self.line,
+            self.column,
+            self.message
+        )
+    }
+}
+
+impl std::error::Error for ConfigSyntaxError {}
+
+fn get_line_column(content: &str, offset: usize) -> (usize, usize) {
+    let mut line = 1;
+    let mut column = 1;
+    for (i, c) in content.char_indices() {
+        if i >= offset {
+            break;
+        }
+        if c == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+    }
+    (line, column)
+}
+
+///
+/// 按配置类型校验内容语法;text与html不校验
+pub fn validate_content(config_type: &ConfigType, content: &str) -> Result<(), ConfigSyntaxError> {
+    match config_type {
+        ConfigType::Json => validate_json(content),
+        ConfigType::Yaml => validate_yaml(content),
+        ConfigType::Toml => validate_toml(content),
+        ConfigType::Xml => validate_xml(content),
+        ConfigType::Properties => validate_properties(content),
+        ConfigType::Text | ConfigType::Html => Ok(()),
+    }
+}
+
+fn validate_json(content: &str) -> Result<(), ConfigSyntaxError> {
+    serde_json::from_str::<serde_json::Value>(content)
+        .map(|_| ())
+        .map_err(|err| {
+            ConfigSyntaxError::new(ConfigType::Json, err.line(), err.column(), err.to_string())
+        })
+}
+
+fn validate_yaml(content: &str) -> Result<(), ConfigSyntaxError> {
+    serde_yml::from_str::<serde_yml::Value>(content)
+        .map(|_| ())
+        .map_err(|err| {
+            let (line, column) = err
+                .location()
+                .map(|e| (e.line(), e.column()))
+                .unwrap_or_default();
+            ConfigSyntaxError::new(ConfigType::Yaml, line, column, err.to_string())
+        })
+}
+
+fn validate_toml(content: &str) -> Result<(), ConfigSyntaxError> {
+    toml::from_str::<toml::Table>(content)
+        .map(|_| ())
+        .map_err(|err| {
+            let offset = err.span().map(|e| e.start).unwrap_or_default();
+            ConfigSyntaxError::new_by_offset(
+                ConfigType::Toml,
+                content,
+                offset,
+                err.message().to_owned(),
+            )
+        })
+}
+
+fn validate_xml(content: &str) -> Result<(), ConfigSyntaxError> {
+    use xmlparser::{ElementEnd, Token, Tokenizer};
+    let error = |offset: usize, message: String| {
+        ConfigSyntaxError::new_by_offset(ConfigType::Xml, content, offset, message)
+    };
+    let mut stack: Vec<&str> = vec![];
+    let mut has_root = false;
+    for token in Tokenizer::from(content) {
+        let token = token.map_err(|err| {
+            let pos = err.pos();
+            ConfigSyntaxError::new(
+                ConfigType::Xml,
+                pos.row as usize,
+                pos.col as usize,
+                err.to_string(),
+            )
+        })?;
+        match token {
+            Token::ElementStart { span, .. } => {
+                if stack.is_empty() && has_root {
+                    return Err(error(span.start(), "multiple root elements".to_owned()));
+                }
+                has_root = true;
+                // span为`<name`
+                stack.push(&span.as_str()[1..]);
+            }
+            Token::ElementEnd { end, span } => match end {
+                ElementEnd::Open => {}
+                ElementEnd::Empty => {
+                    stack.pop();
+                }
+                ElementEnd::Close(prefix, local) => {
+                    let name = if prefix.is_empty() {
+                        local.as_str().to_owned()
+                    } else {
+                        format!("{}:{}", prefix.as_str(), local.as_str())
+                    };
+                    match stack.pop() {
+                        Some(start) if start == name => {}
+                        Some(start) => {
+                            return Err(error(
+                                span.start(),
+                                format!("end tag </{}> does not match start tag <{}>", name, start),
+                            ));
+                        }
+                        None => {
+                            return Err(error(
+                                span.start(),
+                                format!("unexpected end tag </{}>", name),
+                            ));
+                        }
+                    }
+                }
+            },
+            _ => {}
+        }
+    }
+    if let Some(start) = stack.last() {
+        return Err(error(content.len(), format!("unclosed tag <{}>", start)));
+    }
+    if !has_root {
+        return Err(error(content.len(), "no root element".to_owned()));
+    }
+    Ok(())
+}
+
+///
+/// properties格式很宽松,只校验unicode转义是否合法
+fn validate_properties(content: &str) -> Result<(), ConfigSyntaxError> {
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with('#') || trimmed.starts_with('!') {
+            continue;
+        }
+        let mut iter = line.char_indices();
+        while let Some((i, c)) = iter.next() {
+            if c != '\\' {
+                continue;
+            }
+            if let Some((_, 'u')) = iter.next() {
+                let hex: String = iter.by_ref().take(4).map(|(_, c)| c).collect();
+                if hex.len() != 4 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(ConfigSyntaxError::new(
+                        ConfigType::Properties,
+                        index + 1,
+                        line[..i].chars().count() + 1,
+                        format!("malformed \\uxxxx encoding: \\u{}", hex),
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn get_publish_config_type(key: &ConfigKey, config_type: &Option<Arc<String>>) -> ConfigType {
+    match config_type {
+        Some(v) => ConfigType::new_by_value(v),
+        None => SetConfigReq::detect_config_type(key.data_id.clone())
+            .map(|v| ConfigType::new_by_value(&v))
+            .unwrap_or_default(),
+    }
+}
+
+///
+/// 按命名空间的校验模式校验内容语法
+fn check_content_syntax(
+    sys_config: &AppSysConfig,
+    key: &ConfigKey,
+    config_type: &ConfigType,
+    content: &str,
+) -> anyhow::Result<()> {
+    let mode = sys_config.get_config_validate_mode(&key.tenant);
+    if mode == ConfigValidateMode::Off {
+        return Ok(());
+    }
+    if let Err(err) = validate_content(config_type, content) {
+        if mode == ConfigValidateMode::Strict {
+            return Err(err.into());
+        }
+        log::warn!(
+            "config content validate failed, dataId:{}, group:{}, tenant:{}, {}",
+            &key.data_id,
+            &key.group,
+            &key.tenant,
+            err
+        );
+    }
+    Ok(())
+}
+
+///
+/// 发布配置前校验内容:先按命名空间的校验模式校验语法,再校验匹配的JSON Schema约束;
+/// 未指定类型时按dataId后缀推断类型
+pub async fn check_publish_content(
+    app: &AppShareData,
+    key: &ConfigKey,
+    config_type: &Option<Arc<String>>,
+    content: &Arc<String>,
+) -> anyhow::Result<()> {
+    let config_type = get_publish_config_type(key, config_type);
+    check_content_syntax(&app.sys_config, key, &config_type, content)?;
+    app.config_schema_manager
+        .send(ConfigSchemaReq::Validate {
+            key: key.clone(),
+            config_type,
+            content: content.clone(),
+        })
+        .await??;
+    if content.contains(CONFIG_REF_PREFIX) {
+        app.config_addr
+            .send(ConfigCmd::CheckRef(key.clone(), content.clone()))
+            .await??;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(config_type: ConfigType, content: &str) -> Option<(usize, usize)> {
+        validate_content(&config_type, content)
+            .err()
+            .map(|e| (e.line, e.column))
+    }
+
+    #[test]
+    fn validate_by_type() {
+        assert_eq!(check(ConfigType::Json, "{\"a\":1}"), None);
+        assert_eq!(check(ConfigType::Json, "{\n\"a\":1,\n}"), Some((3, 1)));
+        assert_eq!(check(ConfigType::Yaml, "a:\n  b: 1\n  c: 2\n"), None);
+        assert_eq!(
+            check(ConfigType::Yaml, "a:\n  b: 1\n c: 2\n").map(|e| e.0),
+            Some(3)
+        );
+        assert_eq!(check(ConfigType::Toml, "[a]\nb = 1\n"), None);
+        assert_eq!(check(ConfigType::Toml, "[a]\nb = \n").map(|e| e.0), Some(2));
+        assert_eq!(check(ConfigType::Xml, "<a><b/>\n</a>"), None);
+        assert_eq!(check(ConfigType::Xml, "<a>\n<b></c>\n</a>"), Some((2, 4)));
+        assert_eq!(check(ConfigType::Xml, "<a>\n<b>"), Some((2, 4)));
+        assert_eq!(check(ConfigType::Properties, "a=\\u4e2d"), None);
+        assert_eq!(check(ConfigType::Properties, "a=1\nb=\\u4z"), Some((2, 3)));
+        assert_eq!(check(ConfigType::Text, "{"), None);
+    }
+
+    #[test]
+    fn parse_namespace_modes() {
+        let map = ConfigValidateMode::parse_namespace_modes("public:warn, prod:strict,dev:x");
+        assert_eq!(map.get(""), Some(&ConfigValidateMode::Warn));
+        assert_eq!(map.get("prod"), Some(&ConfigValidateMode::Strict));
+        assert_eq!(map.get("dev"), None);
+    }
+}
@@ -9,7 +9,7 @@ use super::{
     naming_api::{query_grpc_client_instance_count, query_ops_instances_list},
     transfer_api, NamespaceUtils,
 };
-use super::{login_api, user_api};
+use super::{connection_api, login_api, user_api};
 use crate::common::appdata::AppShareData;
 use crate::common::error_code::NO_PERMISSION;
 use crate::common::string_utils::StringUtils;
@@ -246,6 +246,18 @@ pub fn console_api_config_v2(config: &mut web::ServiceConfig) {
                 web::resource("/user/reset_password")
                     .route(web::post().to(v2::user_api::reset_password)),
             )
+            .service(
+                web::resource("/user/access_token/list")
+                    .route(web::get().to(v2::access_token_api::query_access_token_list)),
+            )
+            .service(
+                web::resource("/user/access_token/create")
+                    .route(web::post().to(v2::access_token_api::create_access_token)),
+            )
+            .service(
+                web::resource("/user/access_token/remove")
+                    .route(web::post().to(v2::access_token_api::remove_access_token)),
+            )
             .service(
                 web::resource("/namespaces/list")
                     .route(web::get().to(v2::namespace_api::query_namespace_list)),
@@ -291,6 +303,119 @@ pub fn console_api_config_v2(config: &mut web::ServiceConfig) {
                 web::resource("/config/history")
                     .route(web::get().to(v2::config_api::query_history_config_page)),
             )
+            .service(
+                web::resource("/config/history/rollback_preview")
+                    .route(web::get().to(v2::config_api::query_config_rollback_preview)),
+            )
+            .service(
+                web::resource("/config/history/rollback")
+                    .route(web::post().to(v2::config_api::rollback_config)),
+            )
+            .service(
+                web::resource("/config/clone/preview")
+                    .route(web::post().to(v2::config_clone_api::query_config_clone_preview)),
+            )
+            .service(
+                web::resource("/config/clone")
+                    .route(web::post().to(v2::config_clone_api::clone_config)),
+            )
+            .service(
+                web::resource("/config/lock/list")
+                    .route(web::get().to(v2::config_lock_api::query_config_lock_list)),
+            )
+            .service(
+                web::resource("/config/lock/add")
+                    .route(web::post().to(v2::config_lock_api::lock_config)),
+            )
+            .service(
+                web::resource("/config/lock/remove")
+                    .route(web::post().to(v2::config_lock_api::unlock_config)),
+            )
+            .service(
+                web::resource("/config/change/list")
+                    .route(web::get().to(v2::config_change_api::query_config_change_list)),
+            )
+            .service(
+                web::resource("/config/change/info")
+                    .route(web::get().to(v2::config_change_api::query_config_change_info)),
+            )
+            .service(
+                web::resource("/config/change/submit")
+                    .route(web::post().to(v2::config_change_api::submit_config_change)),
+            )
+            .service(
+                web::resource("/config/change/approve")
+                    .route(web::post().to(v2::config_change_api::approve_config_change)),
+            )
+            .service(
+                web::resource("/config/change/reject")
+                    .route(web::post().to(v2::config_change_api::reject_config_change)),
+            )
+            .service(
+                web::resource("/config/schema/list")
+                    .route(web::get().to(v2::config_schema_api::query_config_schema_list)),
+            )
+            .service(
+                web::resource("/config/schema/add")
+                    .route(web::post().to(v2::config_schema_api::set_config_schema)),
+            )
+            .service(
+                web::resource("/config/schema/update")
+                    .route(web::post().to(v2::config_schema_api::set_config_schema)),
+            )
+            .service(
+                web::resource("/config/schema/remove")
+                    .route(web::post().to(v2::config_schema_api::remove_config_schema)),
+            )
+            .service(
+                web::resource("/webhook/list")
+                    .route(web::get().to(v2::webhook_api::query_webhook_list)),
+            )
+            .service(
+                web::resource("/webhook/add").route(web::post().to(v2::webhook_api::set_webhook)),
+            )
+            .service(
+                web::resource("/webhook/update")
+                    .route(web::post().to(v2::webhook_api::set_webhook)),
+            )
+            .service(
+                web::resource("/webhook/remove")
+                    .route(web::post().to(v2::webhook_api::remove_webhook)),
+            )
+            .service(
+                web::resource("/alert/rule/list")
+                    .route(web::get().to(v2::alert_api::query_alert_rule_list)),
+            )
+            .service(
+                web::resource("/alert/rule/add")
+                    .route(web::post().to(v2::alert_api::set_alert_rule)),
+            )
+            .service(
+                web::resource("/alert/rule/update")
+                    .route(web::post().to(v2::alert_api::set_alert_rule)),
+            )
+            .service(
+                web::resource("/alert/rule/remove")
+                    .route(web::post().to(v2::alert_api::remove_alert_rule)),
+            )
+            .service(
+                web::resource("/alert/history/list")
+                    .route(web::get().to(v2::alert_api::query_alert_history_list)),
+            )
+            .service(web::resource("/log/level").route(web::get().to(v2::log_api::query_log_level)))
+            .service(
+                web::resource("/log/level/update")
+                    .route(web::post().to(v2::log_api::update_log_level)),
+            )
+            .service(web::resource("/debug/dump").route(web::get().to(v2::debug_api::debug_dump)))
+            .service(
+                web::resource("/config/listener/list")
+                    .route(web::get().to(v2::config_api::query_config_listener_list)),
+            )
+            .service(
+                web::resource("/config/listener/list_by_ip")
+                    .route(web::get().to(v2::config_api::query_config_listener_list_by_ip)),
+            )
             .service(
                 web::resource("/service/list")
                     .route(web::get().to(v2::naming_api::query_service_list)),
@@ -316,6 +441,10 @@ pub fn console_api_config_v2(config: &mut web::ServiceConfig) {
             .service(
                 web::resource("/instance/info").route(web::get().to(v2::naming_api::get_instance)),
             )
+            .service(
+                web::resource("/instance/history")
+                    .route(web::get().to(v2::naming_api::query_instance_history)),
+            )
             .service(
                 web::resource("/instance/add").route(web::post().to(v2::naming_api::add_instance)),
             )
@@ -340,6 +469,30 @@ pub fn console_api_config_v2(config: &mut web::ServiceConfig) {
                     .route(web::get().to(v2::metrics_api::query_metrics_timeline))
                     .route(web::post().to(v2::metrics_api::query_metrics_timeline_json)),
             )
+            .service(
+                web::resource("/connection/list")
+                    .route(web::get().to(connection_api::query_grpc_connection_list)),
+            )
+            .service(
+                web::resource("/connection/info")
+                    .route(web::get().to(connection_api::query_grpc_connection_detail)),
+            )
+            .service(
+                web::resource("/connection/reset")
+                    .route(web::post().to(connection_api::reset_grpc_connection)),
+            )
+            .service(
+                web::resource("/sys/request_limit/info")
+                    .route(web::get().to(v2::sys_config_api::query_request_limit)),
+            )
+            .service(
+                web::resource("/sys/request_limit/update")
+                    .route(web::post().to(v2::sys_config_api::update_request_limit)),
+            )
+            .service(
+                web::resource("/sys/request_limit/reset")
+                    .route(web::post().to(v2::sys_config_api::reset_request_limit)),
+            )
             .service(
                 web::resource("/mcp/toolspec/list")
                     .route(web::get().to(v2::mcp_tool_spec_api::query_tool_spec_list)),
@@ -1,8 +1,15 @@
 use actix_web::{http::header, web, HttpResponse, Responder};
+use std::sync::Arc;
 
 use actix::prelude::Addr;
 
+use crate::common::appdata::AppShareData;
+use crate::common::model::ApiResult;
+use crate::config::core::{ConfigCmd, ConfigResult};
+use crate::console::model::connection_model::{ConnectionDetailDto, ConnectionParam};
+use crate::console::v2::{handle_not_found_error, handle_param_error, handle_system_error};
 use crate::grpc::bistream_manage::{BiStreamManage, BiStreamManageCmd, BiStreamManageResult};
+use crate::naming::core::{NamingCmd, NamingResult};
 
 use super::model::PageResult;
 
@@ -32,3 +39,104 @@ pub async fn query_grpc_connection(
         Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
     }
 }
+
+pub async fn query_grpc_connection_list(app: web::Data<Arc<AppShareData>>) -> impl Responder {
+    match app
+        .bi_stream_manage
+        .send(BiStreamManageCmd::QueryConnInfoList)
+        .await
+    {
+        Ok(Ok(BiStreamManageResult::ConnInfoList(list))) => {
+            let resp = PageResult {
+                count: list.len() as u64,
+                list,
+            };
+            HttpResponse::Ok().json(ApiResult::success(Some(resp)))
+        }
+        Ok(Err(err)) => handle_system_error(err, "query_grpc_connection_list"),
+        Err(err) => handle_system_error(err, "query_grpc_connection_list"),
+        _ => handle_system_error("unexpected result", "query_grpc_connection_list"),
+    }
+}
+
+pub async fn query_grpc_connection_detail(
+    app: web::Data<Arc<AppShareData>>,
+    web::Query(param): web::Query<ConnectionParam>,
+) -> impl Responder {
+    let client_id = if let Some(v) = param.client_id {
+        v
+    } else {
+        return handle_param_error("clientId is empty", "query_grpc_connection_detail");
+    };
+    match query_connection_detail(&app, client_id.clone()).await {
+        Ok(Some(detail)) => HttpResponse::Ok().json(ApiResult::success(Some(detail))),
+        Ok(None) => handle_not_found_error("connection", &client_id),
+        Err(err) => handle_system_error(err, "query_grpc_connection_detail"),
+    }
+}
+
+async fn query_connection_detail(
+    app: &Arc<AppShareData>,
+    client_id: Arc<String>,
+) -> anyhow::Result<Option<ConnectionDetailDto>> {
+    let info = match app
+        .bi_stream_manage
+        .send(BiStreamManageCmd::QueryConnInfo(client_id.clone()))
+        .await??
+    {
+        BiStreamManageResult::ConnInfo(Some(info)) => info,
+        _ => return Ok(None),
+    };
+    let listen_configs = match app
+        .config_addr
+        .send(ConfigCmd::QueryClientSubscribeKeys(client_id.clone()))
+        .await??
+    {
+        ConfigResult::ClientSubscribeKeys(keys) => keys.into_iter().map(|e| e.into()).collect(),
+        _ => vec![],
+    };
+    let (subscribe_services, publish_instances) = match app
+        .naming_addr
+        .send(NamingCmd::QueryClientDetail(client_id))
+        .await??
+    {
+        NamingResult::ClientDetail {
+            subscribe_services,
+            instances,
+        } => (
+            subscribe_services.into_iter().map(|e| e.into()).collect(),
+            instances,
+        ),
+        _ => (vec![], vec![]),
+    };
+    Ok(Some(ConnectionDetailDto {
+        info,
+        listen_configs,
+        subscribe_services,
+        publish_instances,
+    }))
+}
+
+/// 管理端强制断开客户端链接
+pub async fn reset_grpc_connection(
+    app: web::Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConnectionParam>,
+) -> impl Responder {
+    let client_id = if let Some(v) = param.client_id {
+        v
+    } else {
+        return handle_param_error("clientId is empty", "reset_grpc_connection");
+    };
+    match app
+        .bi_stream_manage
+        .send(BiStreamManageCmd::ResetConn(client_id.clone()))
+        .await
+    {
+        Ok(Ok(BiStreamManageResult::ResetResult(true))) => {
+            HttpResponse::Ok().json(ApiResult::success(Some(true)))
+        }
+        Ok(Ok(_)) => handle_not_found_error("connection", &client_id),
+        Ok(Err(err)) => handle_system_error(err, "reset_grpc_connection"),
+        Err(err) => handle_system_error(err, "reset_grpc_connection"),
+    }
+}
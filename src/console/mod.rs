@@ -17,6 +17,7 @@ use self::model::NamespaceInfo;
 use crate::namespace::model::{
     NamespaceFromFlags, NamespaceQueryReq, NamespaceQueryResult, NamespaceRaftReq,
 };
+use crate::naming::core::{NamingCmd, NamingResult};
 use crate::{
     common::appdata::AppShareData,
     config::core::{ConfigActor, ConfigCmd, ConfigKey, ConfigResult},
@@ -36,6 +37,7 @@ lazy_static::lazy_static! {
             namespace_id: Some(Arc::new("".to_owned())),
             namespace_name: Some(DEFAULT_NAMESPACE.to_owned()),
             r#type: Some("0".to_owned()),
+            ..Default::default()
     });
 }
 
@@ -123,6 +125,7 @@ impl NamespaceUtilsOld {
                 namespace_id: Some(namespace_id),
                 namespace_name: Some(namespace_name),
                 r#type: Some("2".to_owned()),
+                ..Default::default()
             };
             infos.push(new_info);
             Self::save_namespace(app_data, &infos).await
@@ -205,6 +208,40 @@ impl NamespaceUtils {
         }
     }
 
+    ///
+    /// 填充命名空间的配置与服务使用量
+    pub async fn fill_namespace_usage(
+        app_share_data: &Arc<AppShareData>,
+        list: &mut [NamespaceInfo],
+    ) -> anyhow::Result<()> {
+        let mut config_usage = match app_share_data
+            .config_addr
+            .send(ConfigCmd::QueryNamespaceUsage)
+            .await??
+        {
+            ConfigResult::NamespaceUsage(v) => v,
+            _ => return Err(anyhow::anyhow!("ConfigResult is error")),
+        };
+        let mut naming_usage = match app_share_data
+            .naming_addr
+            .send(NamingCmd::QueryNamespaceUsage)
+            .await??
+        {
+            NamingResult::NamespaceUsage(v) => v,
+            _ => return Err(anyhow::anyhow!("NamingResult is error")),
+        };
+        for item in list.iter_mut() {
+            let namespace_id = item.namespace_id.clone().unwrap_or_default();
+            let mut usage = config_usage.remove(&namespace_id).unwrap_or_default();
+            if let Some(v) = naming_usage.remove(&namespace_id) {
+                usage.service_count = v.service_count;
+                usage.instance_count = v.instance_count;
+            }
+            item.usage = Some(usage);
+        }
+        Ok(())
+    }
+
     pub async fn get_namespace(
         app_share_data: &Arc<AppShareData>,
         namespace_id: Option<Arc<String>>,
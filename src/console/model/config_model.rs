@@ -1,6 +1,7 @@
 use crate::config::config_index::ConfigQueryParam;
 use crate::config::core::{ConfigInfoDto, ConfigKey};
 use crate::config::dal::ConfigHistoryParam;
+use crate::config::model::ConfigClonePolicy;
 use crate::config::ConfigUtils;
 use crate::user_namespace_privilege;
 use actix_http::HttpMessage;
@@ -104,6 +105,12 @@ impl ConfigParams {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigListenerIpParams {
+    pub ip: Option<Arc<String>>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct ConfigInfo {
@@ -112,3 +119,92 @@ pub struct ConfigInfo {
     pub config_type: Option<Arc<String>>,
     pub desc: Option<Arc<String>>,
 }
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigRollbackParams {
+    pub data_id: Arc<String>,
+    pub group: Option<Arc<String>>,
+    pub tenant: Option<String>,
+    pub history_id: u64,
+}
+
+impl ConfigRollbackParams {
+    pub fn to_key(&self) -> ConfigKey {
+        let group = self
+            .group
+            .clone()
+            .unwrap_or(Arc::new("DEFAULT_GROUP".to_owned()));
+        let tenant = ConfigUtils::default_tenant(self.tenant.clone().unwrap_or_default());
+        ConfigKey::new_by_arc(self.data_id.clone(), group, Arc::new(tenant))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigCloneKeyParam {
+    pub data_id: Arc<String>,
+    pub group: Option<Arc<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigCloneParams {
+    pub source_tenant: Option<String>,
+    pub target_tenant: Option<String>,
+    /// 克隆整个分组
+    pub group: Option<Arc<String>>,
+    /// 克隆指定配置
+    pub keys: Option<Vec<ConfigCloneKeyParam>>,
+    pub policy: Option<ConfigClonePolicy>,
+}
+
+impl ConfigCloneParams {
+    pub fn get_source_tenant(&self) -> Arc<String> {
+        Arc::new(ConfigUtils::default_tenant(
+            self.source_tenant.clone().unwrap_or_default(),
+        ))
+    }
+
+    pub fn get_target_tenant(&self) -> Arc<String> {
+        Arc::new(ConfigUtils::default_tenant(
+            self.target_tenant.clone().unwrap_or_default(),
+        ))
+    }
+
+    pub fn get_group(&self) -> Option<Arc<String>> {
+        self.group.clone().filter(|e| !e.is_empty())
+    }
+
+    /// 指定配置的来源key
+    pub fn get_source_keys(&self) -> Vec<ConfigKey> {
+        let tenant = self.get_source_tenant();
+        self.keys
+            .as_ref()
+            .map(|keys| {
+                keys.iter()
+                    .map(|e| {
+                        let group = e
+                            .group
+                            .clone()
+                            .filter(|e| !e.is_empty())
+                            .unwrap_or(Arc::new("DEFAULT_GROUP".to_owned()));
+                        ConfigKey::new_by_arc(e.data_id.clone(), group, tenant.clone())
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn check(&self) -> anyhow::Result<()> {
+        if self.get_source_tenant() == self.get_target_tenant() {
+            return Err(anyhow::anyhow!(
+                "sourceTenant and targetTenant can't be the same"
+            ));
+        }
+        if self.get_group().is_none() && self.keys.as_ref().map(|e| e.is_empty()).unwrap_or(true) {
+            return Err(anyhow::anyhow!("group or keys is required"));
+        }
+        Ok(())
+    }
+}
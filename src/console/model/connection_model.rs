@@ -0,0 +1,58 @@
+use crate::config::core::ConfigKey;
+use crate::grpc::bistream_manage::ConnectionInfo;
+use crate::naming::model::{InstanceKey, ServiceKey};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionParam {
+    pub client_id: Option<Arc<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnConfigKeyDto {
+    pub data_id: Arc<String>,
+    pub group: Arc<String>,
+    pub tenant: Arc<String>,
+}
+
+impl From<ConfigKey> for ConnConfigKeyDto {
+    fn from(value: ConfigKey) -> Self {
+        Self {
+            data_id: value.data_id,
+            group: value.group,
+            tenant: value.tenant,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnServiceKeyDto {
+    pub namespace_id: Arc<String>,
+    pub group_name: Arc<String>,
+    pub service_name: Arc<String>,
+}
+
+impl From<ServiceKey> for ConnServiceKeyDto {
+    fn from(value: ServiceKey) -> Self {
+        Self {
+            namespace_id: value.namespace_id,
+            group_name: value.group_name,
+            service_name: value.service_name,
+        }
+    }
+}
+
+///
+/// grpc长链接详情，包含链接监听的配置、订阅与注册的服务
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionDetailDto {
+    pub info: ConnectionInfo,
+    pub listen_configs: Vec<ConnConfigKeyDto>,
+    pub subscribe_services: Vec<ConnServiceKeyDto>,
+    pub publish_instances: Vec<InstanceKey>,
+}
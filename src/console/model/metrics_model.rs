@@ -13,6 +13,7 @@ pub struct TimelineQueryRequest {
     // string_key与keys只取其中一个
     pub keys: Option<Vec<String>>,
     pub node_id: Option<u64>,
+    pub all_node: Option<bool>,
 }
 
 impl From<TimelineQueryRequest> for TimelineQueryParam {
@@ -32,6 +33,7 @@ impl From<TimelineQueryRequest> for TimelineQueryParam {
             timeline_group_name: value.timeline_group_name.unwrap_or_default(),
             keys,
             node_id: value.node_id.unwrap_or_default(),
+            all_node: value.all_node.unwrap_or_default(),
         }
     }
 }
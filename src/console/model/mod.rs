@@ -1,5 +1,6 @@
 pub mod cluster_model;
 pub mod config_model;
+pub mod connection_model;
 pub mod login_model;
 pub mod mcp_server_model;
 pub mod mcp_tool_spec_model;
@@ -8,7 +9,9 @@ pub mod naming_model;
 pub mod raft_model;
 pub mod user_model;
 
-use crate::namespace::model::{Namespace, NamespaceFromFlags, NamespaceParam};
+use crate::namespace::model::{
+    Namespace, NamespaceFromFlags, NamespaceParam, NamespaceQuota, NamespaceUsage,
+};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
@@ -18,6 +21,12 @@ pub struct NamespaceInfo {
     pub namespace_id: Option<Arc<String>>,
     pub namespace_name: Option<String>,
     pub r#type: Option<String>,
+    /// 配额,更新时为空表示不修改
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quota: Option<NamespaceQuota>,
+    /// 使用量,只在查询列表时返回
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<NamespaceUsage>,
 }
 
 impl From<Namespace> for NamespaceInfo {
@@ -26,6 +35,8 @@ impl From<Namespace> for NamespaceInfo {
             namespace_id: Some(value.namespace_id),
             namespace_name: Some(value.namespace_name),
             r#type: Some(NamespaceFromFlags::get_api_type(value.flag)),
+            quota: value.quota.map(|e| e.as_ref().to_owned()),
+            usage: None,
         }
     }
 }
@@ -36,6 +47,7 @@ impl From<NamespaceInfo> for NamespaceParam {
             namespace_id: value.namespace_id.unwrap_or_default(),
             namespace_name: value.namespace_name,
             r#type: value.r#type,
+            quota: value.quota,
         }
     }
 }
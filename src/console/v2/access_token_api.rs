@@ -0,0 +1,128 @@
+use crate::access_token::model::{
+    build_last_used_key, build_token, hash_token, AccessTokenDo, AccessTokenDto, AccessTokenParam,
+    AccessTokenReq, AccessTokenResult,
+};
+use crate::common::appdata::AppShareData;
+use crate::common::constant::ACCESS_TOKEN_TABLE_NAME;
+use crate::common::model::{ApiResult, UserSession};
+use crate::console::v2::{handle_not_found_error, handle_param_error, handle_system_error};
+use crate::raft::db::table::TableManagerReq;
+use crate::{now_second_i32, user_namespace_privilege};
+use actix_http::HttpMessage;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use std::sync::Arc;
+
+fn get_session_username(req: &HttpRequest) -> Option<Arc<String>> {
+    req.extensions()
+        .get::<Arc<UserSession>>()
+        .map(|e| e.username.clone())
+}
+
+/// 查询当前用户的访问令牌列表
+pub async fn query_access_token_list(
+    req: HttpRequest,
+    app: web::Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let username = match get_session_username(&req) {
+        Some(v) => v,
+        None => return handle_not_found_error("user session", ""),
+    };
+    match app
+        .access_token_manager
+        .send(AccessTokenReq::QueryList {
+            username: Some(username),
+        })
+        .await
+    {
+        Ok(Ok(AccessTokenResult::List(list))) => {
+            HttpResponse::Ok().json(ApiResult::success(Some(list)))
+        }
+        Ok(Err(err)) => handle_system_error(err, "query_access_token_list"),
+        Err(err) => handle_system_error(err, "query_access_token_list"),
+        _ => handle_system_error("unexpected result", "query_access_token_list"),
+    }
+}
+
+/// 创建访问令牌,令牌原文只在创建时返回一次
+pub async fn create_access_token(
+    req: HttpRequest,
+    app: web::Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<AccessTokenParam>,
+) -> impl Responder {
+    let username = match get_session_username(&req) {
+        Some(v) => v,
+        None => return handle_not_found_error("user session", ""),
+    };
+    if let Err(err) = param.check(&user_namespace_privilege!(req)) {
+        return handle_param_error(err, "create_access_token");
+    }
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    let secret = uuid::Uuid::new_v4().simple().to_string();
+    let token = build_token(&id, &secret);
+    let item = AccessTokenDo {
+        id: Arc::new(id),
+        name: param.name.unwrap_or_default(),
+        username,
+        token_hash: hash_token(&token),
+        scopes: Arc::new(param.scopes.unwrap_or_default()),
+        expire_time: param.expire_time.unwrap_or_default(),
+        create_time: now_second_i32() as i64,
+    };
+    let value = match item.to_bytes() {
+        Ok(v) => v,
+        Err(err) => return handle_system_error(err, "create_access_token"),
+    };
+    let req = TableManagerReq::Set {
+        table_name: ACCESS_TOKEN_TABLE_NAME.clone(),
+        key: item.id.as_bytes().to_vec(),
+        value,
+        last_seq_id: None,
+    };
+    match app.raft_table_route.request(req).await {
+        Ok(_) => {
+            let mut dto = AccessTokenDto::new(&item, 0);
+            dto.token = Some(token);
+            HttpResponse::Ok().json(ApiResult::success(Some(dto)))
+        }
+        Err(err) => handle_system_error(err, "create_access_token"),
+    }
+}
+
+/// 吊销访问令牌,只能吊销自己创建的令牌
+pub async fn remove_access_token(
+    req: HttpRequest,
+    app: web::Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<AccessTokenParam>,
+) -> impl Responder {
+    let username = match get_session_username(&req) {
+        Some(v) => v,
+        None => return handle_not_found_error("user session", ""),
+    };
+    let id = match param.id {
+        Some(id) if !id.is_empty() => id,
+        _ => return handle_param_error("id is empty", "remove_access_token"),
+    };
+    match app
+        .access_token_manager
+        .send(AccessTokenReq::Query(id.clone()))
+        .await
+    {
+        Ok(Ok(AccessTokenResult::Token(Some(item)))) if item.username == username => {}
+        Ok(Ok(_)) => return handle_not_found_error("access token", &id),
+        Ok(Err(err)) => return handle_system_error(err, "remove_access_token"),
+        Err(err) => return handle_system_error(err, "remove_access_token"),
+    }
+    for key in [
+        id.as_bytes().to_vec(),
+        build_last_used_key(&id).into_bytes(),
+    ] {
+        let req = TableManagerReq::Remove {
+            table_name: ACCESS_TOKEN_TABLE_NAME.clone(),
+            key,
+        };
+        if let Err(err) = app.raft_table_route.request(req).await {
+            return handle_system_error(err, "remove_access_token");
+        }
+    }
+    HttpResponse::Ok().json(ApiResult::success(Some(true)))
+}
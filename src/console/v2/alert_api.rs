@@ -0,0 +1,165 @@
+use crate::alert::model::{
+    AlertChannel, AlertHistoryParam, AlertReq, AlertResult, AlertRuleDo, AlertRuleParam,
+};
+use crate::common::appdata::AppShareData;
+use crate::common::constant::ALERT_RULE_TABLE_NAME;
+use crate::common::model::{ApiResult, PageResult, UserSession};
+use crate::console::v2::{handle_not_found_error, handle_param_error, handle_system_error};
+use crate::now_millis_i64;
+use crate::raft::db::table::TableManagerReq;
+use actix_http::HttpMessage;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use std::sync::Arc;
+
+async fn query_rule(
+    app: &Arc<AppShareData>,
+    id: Arc<String>,
+) -> anyhow::Result<Option<Arc<AlertRuleDo>>> {
+    match app.alert_manager.send(AlertReq::QueryRule(id)).await?? {
+        AlertResult::Rule(v) => Ok(v),
+        _ => Err(anyhow::anyhow!("unexpected result")),
+    }
+}
+
+/// 查询结果不返回webhook签名密钥
+fn hide_secret(rule: &AlertRuleDo) -> AlertRuleDo {
+    let mut rule = rule.clone();
+    for channel in rule.channels.iter_mut() {
+        if let AlertChannel::Webhook { secret, .. } = channel {
+            *secret = None;
+        }
+    }
+    rule
+}
+
+/// 更新时webhook密钥为空表示保留相同地址的原密钥
+fn keep_secret(channels: &mut [AlertChannel], old_channels: &[AlertChannel]) {
+    for channel in channels.iter_mut() {
+        if let AlertChannel::Webhook { url, secret } = channel {
+            if secret.as_ref().map(|e| !e.is_empty()).unwrap_or(false) {
+                continue;
+            }
+            *secret = old_channels.iter().find_map(|e| match e {
+                AlertChannel::Webhook {
+                    url: old_url,
+                    secret: old_secret,
+                } if old_url == url => old_secret.clone(),
+                _ => None,
+            });
+        }
+    }
+}
+
+/// 查询告警规则列表
+pub async fn query_alert_rule_list(app: web::Data<Arc<AppShareData>>) -> impl Responder {
+    match app.alert_manager.send(AlertReq::QueryRuleList).await {
+        Ok(Ok(AlertResult::RuleList(list))) => {
+            let list: Vec<AlertRuleDo> = list.iter().map(|e| hide_secret(e)).collect();
+            HttpResponse::Ok().json(ApiResult::success(Some(list)))
+        }
+        Ok(Err(err)) => handle_system_error(err, "query_alert_rule_list"),
+        Err(err) => handle_system_error(err, "query_alert_rule_list"),
+        _ => handle_system_error("unexpected result", "query_alert_rule_list"),
+    }
+}
+
+/// 新增或更新告警规则,通过raft同步到集群各节点
+pub async fn set_alert_rule(
+    req: HttpRequest,
+    app: web::Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<AlertRuleParam>,
+) -> impl Responder {
+    if let Err(err) = param.check() {
+        return handle_param_error(err, "set_alert_rule");
+    }
+    let now = now_millis_i64();
+    let mut create_time = now;
+    let mut channels = param.channels.clone().unwrap_or_default();
+    let id = match &param.id {
+        Some(id) if !id.is_empty() => {
+            match query_rule(&app, id.clone()).await {
+                Ok(Some(v)) => {
+                    create_time = v.create_time;
+                    keep_secret(&mut channels, &v.channels);
+                }
+                Ok(None) => return handle_not_found_error("alert rule", id),
+                Err(err) => return handle_system_error(err, "set_alert_rule"),
+            }
+            id.clone()
+        }
+        _ => Arc::new(uuid::Uuid::new_v4().simple().to_string()),
+    };
+    let op_user = req
+        .extensions()
+        .get::<Arc<UserSession>>()
+        .map(|session| session.username.clone());
+    let item = AlertRuleDo {
+        id,
+        name: param.name.clone().unwrap_or_default(),
+        rule_type: param.rule_type.unwrap_or_default(),
+        namespace: param.get_namespace(),
+        group_name: param.group_name.clone().filter(|e| !e.is_empty()),
+        service_name: param.service_name.clone(),
+        metrics_key: param.metrics_key.clone(),
+        compare_op: param.compare_op.unwrap_or_default(),
+        threshold: param.threshold.unwrap_or_default(),
+        for_count: param.for_count.unwrap_or(1).max(1),
+        channels,
+        enabled: param.enabled.unwrap_or(true),
+        op_user,
+        create_time,
+        update_time: now,
+    };
+    let value = match item.to_bytes() {
+        Ok(v) => v,
+        Err(err) => return handle_system_error(err, "set_alert_rule"),
+    };
+    let req = TableManagerReq::Set {
+        table_name: ALERT_RULE_TABLE_NAME.clone(),
+        key: item.id.as_bytes().to_vec(),
+        value,
+        last_seq_id: None,
+    };
+    match app.raft_table_route.request(req).await {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(hide_secret(&item)))),
+        Err(err) => handle_system_error(err, "set_alert_rule"),
+    }
+}
+
+/// 删除告警规则
+pub async fn remove_alert_rule(
+    app: web::Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<AlertRuleParam>,
+) -> impl Responder {
+    let id = match param.id {
+        Some(id) if !id.is_empty() => id,
+        _ => return handle_param_error("id is empty", "remove_alert_rule"),
+    };
+    let req = TableManagerReq::Remove {
+        table_name: ALERT_RULE_TABLE_NAME.clone(),
+        key: id.as_bytes().to_vec(),
+    };
+    match app.raft_table_route.request(req).await {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(err) => handle_system_error(err, "remove_alert_rule"),
+    }
+}
+
+/// 分页查询告警触发与恢复历史,按时间倒序
+pub async fn query_alert_history_list(
+    app: web::Data<Arc<AppShareData>>,
+    web::Query(param): web::Query<AlertHistoryParam>,
+) -> impl Responder {
+    match app
+        .alert_manager
+        .send(AlertReq::QueryHistoryPage(param))
+        .await
+    {
+        Ok(Ok(AlertResult::HistoryPage(total_count, list))) => {
+            HttpResponse::Ok().json(ApiResult::success(Some(PageResult { total_count, list })))
+        }
+        Ok(Err(err)) => handle_system_error(err, "query_alert_history_list"),
+        Err(err) => handle_system_error(err, "query_alert_history_list"),
+        _ => handle_system_error("unexpected result", "query_alert_history_list"),
+    }
+}
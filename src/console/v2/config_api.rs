@@ -1,9 +1,17 @@
 use crate::common::appdata::AppShareData;
 use crate::common::model::{ApiResult, PageResult, UserSession};
-use crate::config::core::{ConfigActor, ConfigCmd, ConfigResult};
+use crate::config::core::{ConfigActor, ConfigCmd, ConfigKey, ConfigResult};
+use crate::config::model::ConfigRollbackInfo;
+use crate::config::validate::check_publish_content;
 pub use crate::console::config_api::{download_config, import_config};
-use crate::console::model::config_model::{ConfigInfo, ConfigParams, OpsConfigQueryListRequest};
-use crate::console::v2::ERROR_CODE_SYSTEM_ERROR;
+use crate::console::model::config_model::{
+    ConfigInfo, ConfigListenerIpParams, ConfigParams, ConfigRollbackParams,
+    OpsConfigQueryListRequest,
+};
+use crate::console::v2::{
+    handle_config_need_approval_error, handle_not_found_error, handle_param_error,
+    handle_system_error, ERROR_CODE_SYSTEM_ERROR,
+};
 use crate::raft::cluster::model::{DelConfigReq, SetConfigReq};
 use crate::{user_namespace_privilege, user_no_namespace_permission};
 use actix::Addr;
@@ -133,6 +141,9 @@ pub async fn add_config(
     if !namespace_privilege.check_permission(&config_key.tenant) {
         user_no_namespace_permission!(&config_key.tenant);
     }
+    if appdata.sys_config.need_config_approval(&config_key.tenant) {
+        return handle_config_need_approval_error(&config_key.tenant, "add_config");
+    }
     if let Err(e) = config_key.is_valid() {
         return HttpResponse::Ok().json(ApiResult::<()>::error(
             ERROR_CODE_SYSTEM_ERROR.to_string(),
@@ -143,6 +154,11 @@ pub async fn add_config(
     req.config_type = param.config_type;
     req.desc = param.desc;
     req.op_user = op_user;
+    if let Err(err) =
+        check_publish_content(&appdata, &req.config_key, &req.config_type, &req.value).await
+    {
+        return handle_param_error(err, "add_config");
+    }
     if appdata.config_route.set_config(req).await.is_ok() {
         HttpResponse::Ok().json(ApiResult::success(Some(true)))
     } else {
@@ -173,3 +189,130 @@ pub async fn remove_config(
         ))
     }
 }
+
+/// 查询监听指定配置的客户端列表
+pub async fn query_config_listener_list(
+    req: HttpRequest,
+    web::Query(param): web::Query<ConfigParams>,
+    appdata: Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let config_key = param.to_key();
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&config_key.tenant) {
+        user_no_namespace_permission!(&config_key.tenant);
+    }
+    match appdata
+        .config_addr
+        .send(ConfigCmd::QueryListenerByKey(config_key))
+        .await
+    {
+        Ok(Ok(ConfigResult::ListenerInfoList(list))) => {
+            HttpResponse::Ok().json(ApiResult::success(Some(list)))
+        }
+        Ok(Err(err)) => handle_system_error(err, "query_config_listener_list"),
+        Err(err) => handle_system_error(err, "query_config_listener_list"),
+        _ => handle_system_error("unexpected result", "query_config_listener_list"),
+    }
+}
+
+/// 查询指定客户端ip监听的配置列表,只返回有命名空间权限的配置
+pub async fn query_config_listener_list_by_ip(
+    req: HttpRequest,
+    web::Query(param): web::Query<ConfigListenerIpParams>,
+    appdata: Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let ip = match param.ip {
+        Some(ip) if !ip.is_empty() => ip,
+        _ => return handle_param_error("ip is empty", "query_config_listener_list_by_ip"),
+    };
+    let namespace_privilege = user_namespace_privilege!(req);
+    match appdata
+        .config_addr
+        .send(ConfigCmd::QueryListenerByIp(ip))
+        .await
+    {
+        Ok(Ok(ConfigResult::ListenerInfoList(list))) => {
+            let list: Vec<_> = list
+                .into_iter()
+                .filter(|e| namespace_privilege.check_permission(&e.tenant))
+                .collect();
+            HttpResponse::Ok().json(ApiResult::success(Some(list)))
+        }
+        Ok(Err(err)) => handle_system_error(err, "query_config_listener_list_by_ip"),
+        Err(err) => handle_system_error(err, "query_config_listener_list_by_ip"),
+        _ => handle_system_error("unexpected result", "query_config_listener_list_by_ip"),
+    }
+}
+
+async fn query_rollback_info(
+    appdata: &Arc<AppShareData>,
+    config_key: ConfigKey,
+    history_id: u64,
+) -> anyhow::Result<Option<ConfigRollbackInfo>> {
+    match appdata
+        .config_addr
+        .send(ConfigCmd::QueryRollbackInfo(config_key, history_id))
+        .await??
+    {
+        ConfigResult::RollbackInfo(info) => Ok(info),
+        _ => Err(anyhow::anyhow!("unexpected result")),
+    }
+}
+
+/// 预览回滚到指定历史版本的内容差异
+pub async fn query_config_rollback_preview(
+    req: HttpRequest,
+    web::Query(param): web::Query<ConfigRollbackParams>,
+    appdata: Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let config_key = param.to_key();
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&config_key.tenant) {
+        user_no_namespace_permission!(&config_key.tenant);
+    }
+    match query_rollback_info(&appdata, config_key, param.history_id).await {
+        Ok(Some(info)) => HttpResponse::Ok().json(ApiResult::success(Some(info))),
+        Ok(None) => handle_not_found_error("config history", &param.history_id.to_string()),
+        Err(err) => handle_system_error(err, "query_config_rollback_preview"),
+    }
+}
+
+/// 回滚配置到指定历史版本,新的历史记录会标记回滚来源版本
+pub async fn rollback_config(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigRollbackParams>,
+) -> impl Responder {
+    let op_user = req
+        .extensions()
+        .get::<Arc<UserSession>>()
+        .map(|session| session.username.clone());
+    let config_key = param.to_key();
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&config_key.tenant) {
+        user_no_namespace_permission!(&config_key.tenant);
+    }
+    if appdata.sys_config.need_config_approval(&config_key.tenant) {
+        return handle_config_need_approval_error(&config_key.tenant, "rollback_config");
+    }
+    let info = match query_rollback_info(&appdata, config_key.clone(), param.history_id).await {
+        Ok(Some(info)) => info,
+        Ok(None) => return handle_not_found_error("config history", &param.history_id.to_string()),
+        Err(err) => return handle_system_error(err, "rollback_config"),
+    };
+    if info.same_content {
+        return handle_param_error(
+            "the history content is the same as the current content",
+            "rollback_config",
+        );
+    }
+    let mut req = SetConfigReq::new(config_key, info.history_content);
+    req.config_type = info.config_type;
+    req.desc = info.desc;
+    req.op_user = op_user;
+    req.rollback_from = Some(param.history_id);
+    match appdata.config_route.set_config(req).await {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(err) => handle_system_error(err, "rollback_config"),
+    }
+}
@@ -0,0 +1,235 @@
+use crate::common::appdata::AppShareData;
+use crate::common::constant::{CONFIG_CHANGE_TABLE_NAME, EMPTY_ARC_STRING};
+use crate::common::model::{ApiResult, UserSession};
+use crate::config::approval::model::{
+    ConfigChangeDo, ConfigChangeInfo, ConfigChangeParam, ConfigChangeReq, ConfigChangeResult,
+    ConfigChangeStatus,
+};
+use crate::config::core::{ConfigCmd, ConfigKey, ConfigResult};
+use crate::config::validate::check_publish_content;
+use crate::console::v2::{handle_not_found_error, handle_param_error, handle_system_error};
+use crate::raft::cluster::model::SetConfigReq;
+use crate::raft::db::table::TableManagerReq;
+use crate::{now_millis_i64, user_namespace_privilege, user_no_namespace_permission};
+use actix_http::HttpMessage;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use std::sync::Arc;
+
+fn get_session_username(req: &HttpRequest) -> Option<Arc<String>> {
+    req.extensions()
+        .get::<Arc<UserSession>>()
+        .map(|e| e.username.clone())
+}
+
+async fn query_change(
+    app: &Arc<AppShareData>,
+    id: Arc<String>,
+) -> anyhow::Result<Option<Arc<ConfigChangeDo>>> {
+    match app
+        .config_change_manager
+        .send(ConfigChangeReq::Query(id))
+        .await??
+    {
+        ConfigChangeResult::Change(v) => Ok(v),
+        _ => Err(anyhow::anyhow!("unexpected result")),
+    }
+}
+
+/// 查询当前配置内容与md5,配置不存在时返回空
+async fn query_current_content(
+    app: &Arc<AppShareData>,
+    key: ConfigKey,
+) -> anyhow::Result<(Arc<String>, Arc<String>)> {
+    match app.config_addr.send(ConfigCmd::GET(key)).await?? {
+        ConfigResult::Data { value, md5, .. } => Ok((value, md5)),
+        _ => Ok((EMPTY_ARC_STRING.clone(), EMPTY_ARC_STRING.clone())),
+    }
+}
+
+async fn save_change(app: &Arc<AppShareData>, item: &ConfigChangeDo) -> anyhow::Result<()> {
+    let req = TableManagerReq::Set {
+        table_name: CONFIG_CHANGE_TABLE_NAME.clone(),
+        key: item.id.as_bytes().to_vec(),
+        value: item.to_bytes()?,
+        last_seq_id: None,
+    };
+    app.raft_table_route.request(req).await?;
+    Ok(())
+}
+
+/// 提交配置变更,审批通过后才会发布
+pub async fn submit_config_change(
+    req: HttpRequest,
+    app: web::Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigChangeParam>,
+) -> impl Responder {
+    let username = match get_session_username(&req) {
+        Some(v) => v,
+        None => return handle_not_found_error("user session", ""),
+    };
+    let key = param.to_key();
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&key.tenant) {
+        user_no_namespace_permission!(&key.tenant);
+    }
+    if let Err(err) = key.is_valid() {
+        return handle_param_error(err, "submit_config_change");
+    }
+    let content = param.content.unwrap_or_default();
+    if let Err(err) = check_publish_content(&app, &key, &param.config_type, &content).await {
+        return handle_param_error(err, "submit_config_change");
+    }
+    let base_md5 = match query_current_content(&app, key.clone()).await {
+        Ok((_, md5)) => md5,
+        Err(err) => return handle_system_error(err, "submit_config_change"),
+    };
+    let item = ConfigChangeDo {
+        id: Arc::new(uuid::Uuid::new_v4().simple().to_string()),
+        tenant: key.tenant,
+        group: key.group,
+        data_id: key.data_id,
+        content,
+        config_type: param.config_type,
+        desc: param.desc,
+        base_md5,
+        status: ConfigChangeStatus::Pending,
+        submit_user: username,
+        submit_time: now_millis_i64(),
+        ..Default::default()
+    };
+    match save_change(&app, &item).await {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(item))),
+        Err(err) => handle_system_error(err, "submit_config_change"),
+    }
+}
+
+/// 查询配置变更列表,只返回有命名空间权限的变更
+pub async fn query_config_change_list(
+    req: HttpRequest,
+    app: web::Data<Arc<AppShareData>>,
+    web::Query(param): web::Query<ConfigChangeParam>,
+) -> impl Responder {
+    let tenant = param.get_tenant();
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_option_value_permission(&tenant, true) {
+        user_no_namespace_permission!(&tenant);
+    }
+    let msg = ConfigChangeReq::QueryList {
+        tenant,
+        status: param.status,
+    };
+    match app.config_change_manager.send(msg).await {
+        Ok(Ok(ConfigChangeResult::List(list))) => {
+            let list: Vec<_> = list
+                .into_iter()
+                .filter(|e| namespace_privilege.check_permission(&e.tenant))
+                .collect();
+            HttpResponse::Ok().json(ApiResult::success(Some(list)))
+        }
+        Ok(Err(err)) => handle_system_error(err, "query_config_change_list"),
+        Err(err) => handle_system_error(err, "query_config_change_list"),
+        _ => handle_system_error("unexpected result", "query_config_change_list"),
+    }
+}
+
+/// 查询配置变更详情,包含与当前配置内容的差异
+pub async fn query_config_change_info(
+    req: HttpRequest,
+    app: web::Data<Arc<AppShareData>>,
+    web::Query(param): web::Query<ConfigChangeParam>,
+) -> impl Responder {
+    let id = param.id.unwrap_or_default();
+    let namespace_privilege = user_namespace_privilege!(req);
+    let change = match query_change(&app, id.clone()).await {
+        Ok(Some(v)) if namespace_privilege.check_permission(&v.tenant) => v,
+        Ok(_) => return handle_not_found_error("config change", &id),
+        Err(err) => return handle_system_error(err, "query_config_change_info"),
+    };
+    let (current_content, current_md5) = match query_current_content(&app, change.get_key()).await {
+        Ok(v) => v,
+        Err(err) => return handle_system_error(err, "query_config_change_info"),
+    };
+    let diff = similar::TextDiff::from_lines(current_content.as_str(), change.content.as_str())
+        .unified_diff()
+        .header("current", &format!("change-{}", &change.id))
+        .to_string();
+    HttpResponse::Ok().json(ApiResult::success(Some(ConfigChangeInfo {
+        change,
+        current_content,
+        current_md5,
+        diff,
+    })))
+}
+
+/// 审批配置变更;审批人不能是提交人,通过后发布配置
+pub async fn review_config_change(
+    req: HttpRequest,
+    app: web::Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigChangeParam>,
+    approved: bool,
+) -> HttpResponse {
+    let username = match get_session_username(&req) {
+        Some(v) => v,
+        None => return handle_not_found_error("user session", ""),
+    };
+    let id = param.id.unwrap_or_default();
+    let namespace_privilege = user_namespace_privilege!(req);
+    let change = match query_change(&app, id.clone()).await {
+        Ok(Some(v)) if namespace_privilege.check_permission(&v.tenant) => v,
+        Ok(_) => return handle_not_found_error("config change", &id),
+        Err(err) => return handle_system_error(err, "review_config_change"),
+    };
+    if change.status != ConfigChangeStatus::Pending {
+        return handle_param_error("the config change is not pending", "review_config_change");
+    }
+    if change.submit_user == username {
+        return handle_param_error(
+            "the config change can't be reviewed by the submitter",
+            "review_config_change",
+        );
+    }
+    if approved {
+        let key = change.get_key();
+        if let Err(err) =
+            check_publish_content(&app, &key, &change.config_type, &change.content).await
+        {
+            return handle_param_error(err, "review_config_change");
+        }
+        let mut set_req = SetConfigReq::new(key, change.content.clone());
+        set_req.config_type = change.config_type.clone();
+        set_req.desc = change.desc.clone();
+        set_req.op_user = Some(change.submit_user.clone());
+        if let Err(err) = app.config_route.set_config(set_req).await {
+            return handle_system_error(err, "review_config_change");
+        }
+    }
+    let mut item = change.as_ref().clone();
+    item.status = if approved {
+        ConfigChangeStatus::Approved
+    } else {
+        ConfigChangeStatus::Rejected
+    };
+    item.review_user = Some(username);
+    item.review_time = now_millis_i64();
+    item.review_comment = param.comment;
+    match save_change(&app, &item).await {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(item))),
+        Err(err) => handle_system_error(err, "review_config_change"),
+    }
+}
+
+pub async fn approve_config_change(
+    req: HttpRequest,
+    app: web::Data<Arc<AppShareData>>,
+    param: web::Json<ConfigChangeParam>,
+) -> impl Responder {
+    review_config_change(req, app, param, true).await
+}
+
+pub async fn reject_config_change(
+    req: HttpRequest,
+    app: web::Data<Arc<AppShareData>>,
+    param: web::Json<ConfigChangeParam>,
+) -> impl Responder {
+    review_config_change(req, app, param, false).await
+}
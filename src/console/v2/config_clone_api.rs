@@ -0,0 +1,256 @@
+use crate::common::appdata::AppShareData;
+use crate::common::model::privilege::NamespacePrivilegeGroup;
+use crate::common::model::{ApiResult, UserSession};
+use crate::config::config_index::ConfigQueryParam;
+use crate::config::core::{ConfigCmd, ConfigKey, ConfigResult};
+use crate::config::model::{
+    ConfigCloneItem, ConfigClonePolicy, ConfigCloneResult, ConfigCloneStatus,
+};
+use crate::config::validate::check_publish_content;
+use crate::console::model::config_model::ConfigCloneParams;
+use crate::console::v2::{
+    handle_config_need_approval_error, handle_param_error, handle_system_error,
+};
+use crate::raft::cluster::model::SetConfigReq;
+use crate::user_namespace_privilege;
+use actix_web::web::Data;
+use actix_web::HttpMessage;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use std::sync::Arc;
+
+/// 按分组克隆时单次最多克隆的配置数量
+const MAX_CLONE_COUNT: usize = 1000;
+
+struct CloneSource {
+    key: ConfigKey,
+    target_key: ConfigKey,
+    content: Arc<String>,
+    config_type: Option<Arc<String>>,
+    desc: Option<Arc<String>>,
+    item: ConfigCloneItem,
+}
+
+impl CloneSource {
+    /// 记录到目标配置历史中的来源,格式:命名空间/分组/dataId
+    fn get_clone_from(&self) -> Arc<String> {
+        let tenant = if self.key.tenant.is_empty() {
+            crate::namespace::DEFAULT_NAMESPACE
+        } else {
+            self.key.tenant.as_str()
+        };
+        Arc::new(format!(
+            "{}/{}/{}",
+            tenant, &self.key.group, &self.key.data_id
+        ))
+    }
+}
+
+fn check_tenant_permission(
+    namespace_privilege: &NamespacePrivilegeGroup,
+    param: &ConfigCloneParams,
+) -> anyhow::Result<()> {
+    for tenant in [param.get_source_tenant(), param.get_target_tenant()] {
+        if !namespace_privilege.check_permission(&tenant) {
+            return Err(anyhow::anyhow!(
+                "user no such namespace permission: {}",
+                &tenant
+            ));
+        }
+    }
+    Ok(())
+}
+
+async fn query_config(
+    app: &Arc<AppShareData>,
+    key: ConfigKey,
+) -> anyhow::Result<Option<(Arc<String>, Option<Arc<String>>, Option<Arc<String>>)>> {
+    match app.config_addr.send(ConfigCmd::GET(key)).await?? {
+        ConfigResult::Data {
+            value,
+            config_type,
+            desc,
+            ..
+        } => Ok(Some((value, config_type, desc))),
+        _ => Ok(None),
+    }
+}
+
+async fn query_source_keys(
+    app: &Arc<AppShareData>,
+    param: &ConfigCloneParams,
+) -> anyhow::Result<Vec<ConfigKey>> {
+    let mut keys = param.get_source_keys();
+    if let Some(group) = param.get_group() {
+        let query_param = ConfigQueryParam {
+            tenant: Some(param.get_source_tenant()),
+            group: Some(group),
+            limit: MAX_CLONE_COUNT + 1,
+            ..Default::default()
+        };
+        match app
+            .config_addr
+            .send(ConfigCmd::QueryPageInfo(Box::new(query_param)))
+            .await??
+        {
+            ConfigResult::ConfigInfoPage(size, list) => {
+                if size > MAX_CLONE_COUNT {
+                    return Err(anyhow::anyhow!(
+                        "the group has {} configs, more than {}",
+                        size,
+                        MAX_CLONE_COUNT
+                    ));
+                }
+                for item in list {
+                    let key = ConfigKey::new_by_arc(item.data_id, item.group, item.tenant);
+                    if !keys.contains(&key) {
+                        keys.push(key);
+                    }
+                }
+            }
+            _ => return Err(anyhow::anyhow!("unexpected result")),
+        }
+    }
+    Ok(keys)
+}
+
+///
+/// 查询来源配置并与目标命名空间的同名配置比对
+async fn build_clone_sources(
+    app: &Arc<AppShareData>,
+    param: &ConfigCloneParams,
+) -> anyhow::Result<Vec<CloneSource>> {
+    let target_tenant = param.get_target_tenant();
+    let mut list = vec![];
+    for key in query_source_keys(app, param).await? {
+        let (content, config_type, desc) = match query_config(app, key.clone()).await? {
+            Some(v) => v,
+            None => {
+                return Err(anyhow::anyhow!(
+                    "config not found, dataId:{}, group:{}",
+                    &key.data_id,
+                    &key.group
+                ))
+            }
+        };
+        let target_key = ConfigKey::new_by_arc(
+            key.data_id.clone(),
+            key.group.clone(),
+            target_tenant.clone(),
+        );
+        let target = query_config(app, target_key.clone()).await?;
+        let item = ConfigCloneItem::new(&key, &content, target.as_ref().map(|e| &e.0));
+        list.push(CloneSource {
+            key,
+            target_key,
+            content,
+            config_type,
+            desc,
+            item,
+        });
+    }
+    Ok(list)
+}
+
+/// 预览克隆结果,冲突的配置返回目标内容到来源内容的差异
+pub async fn query_config_clone_preview(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigCloneParams>,
+) -> impl Responder {
+    if let Err(err) = param.check() {
+        return handle_param_error(err, "query_config_clone_preview");
+    }
+    let namespace_privilege = user_namespace_privilege!(req);
+    if let Err(err) = check_tenant_permission(&namespace_privilege, &param) {
+        return handle_param_error(err, "query_config_clone_preview");
+    }
+    match build_clone_sources(&appdata, &param).await {
+        Ok(list) => {
+            let list: Vec<ConfigCloneItem> = list.into_iter().map(|e| e.item).collect();
+            HttpResponse::Ok().json(ApiResult::success(Some(list)))
+        }
+        Err(err) => handle_param_error(err, "query_config_clone_preview"),
+    }
+}
+
+/// 把配置克隆到目标命名空间,目标配置的历史记录会标记克隆来源
+pub async fn clone_config(
+    req: HttpRequest,
+    appdata: Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigCloneParams>,
+) -> impl Responder {
+    if let Err(err) = param.check() {
+        return handle_param_error(err, "clone_config");
+    }
+    let namespace_privilege = user_namespace_privilege!(req);
+    if let Err(err) = check_tenant_permission(&namespace_privilege, &param) {
+        return handle_param_error(err, "clone_config");
+    }
+    let target_tenant = param.get_target_tenant();
+    if appdata.sys_config.need_config_approval(&target_tenant) {
+        return handle_config_need_approval_error(&target_tenant, "clone_config");
+    }
+    let op_user = req
+        .extensions()
+        .get::<Arc<UserSession>>()
+        .map(|session| session.username.clone());
+    let policy = param.policy.unwrap_or_default();
+    let list = match build_clone_sources(&appdata, &param).await {
+        Ok(list) => list,
+        Err(err) => return handle_param_error(err, "clone_config"),
+    };
+    if policy == ConfigClonePolicy::Abort {
+        let conflicts: Vec<String> = list
+            .iter()
+            .filter(|e| e.item.status == ConfigCloneStatus::Conflict)
+            .map(|e| format!("{}@@{}", &e.key.data_id, &e.key.group))
+            .collect();
+        if !conflicts.is_empty() {
+            return handle_param_error(
+                format!("config conflict: {}", conflicts.join(",")),
+                "clone_config",
+            );
+        }
+    }
+    // 先校验全部内容,避免只克隆了部分配置
+    for source in &list {
+        if let Err(err) = check_publish_content(
+            &appdata,
+            &source.target_key,
+            &source.config_type,
+            &source.content,
+        )
+        .await
+        {
+            return handle_param_error(
+                format!("dataId:{}, {}", &source.key.data_id, err),
+                "clone_config",
+            );
+        }
+    }
+    let mut result = ConfigCloneResult::default();
+    for source in list {
+        match source.item.status {
+            ConfigCloneStatus::Same => {
+                result.unchanged += 1;
+                continue;
+            }
+            ConfigCloneStatus::Conflict if policy == ConfigClonePolicy::Skip => {
+                result.skipped += 1;
+                continue;
+            }
+            ConfigCloneStatus::Conflict => result.overwritten += 1,
+            ConfigCloneStatus::New => result.created += 1,
+        }
+        let clone_from = source.get_clone_from();
+        let mut req = SetConfigReq::new(source.target_key, source.content);
+        req.config_type = source.config_type;
+        req.desc = source.desc;
+        req.op_user = op_user.clone();
+        req.clone_from = Some(clone_from);
+        if let Err(err) = appdata.config_route.set_config(req).await {
+            return handle_system_error(err, "clone_config");
+        }
+    }
+    HttpResponse::Ok().json(ApiResult::success(Some(result)))
+}
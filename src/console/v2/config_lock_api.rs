@@ -0,0 +1,99 @@
+use crate::common::appdata::AppShareData;
+use crate::common::constant::CONFIG_LOCK_TABLE_NAME;
+use crate::common::model::{ApiResult, UserSession};
+use crate::config::core::{ConfigCmd, ConfigResult};
+use crate::config::lock::{ConfigLockDo, ConfigLockParam};
+use crate::console::v2::{handle_param_error, handle_system_error};
+use crate::raft::db::table::TableManagerReq;
+use crate::{now_millis_i64, user_namespace_privilege, user_no_namespace_permission};
+use actix_http::HttpMessage;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use std::sync::Arc;
+
+/// 查询配置锁列表,只返回有命名空间权限的锁
+pub async fn query_config_lock_list(
+    req: HttpRequest,
+    app: web::Data<Arc<AppShareData>>,
+    web::Query(param): web::Query<ConfigLockParam>,
+) -> impl Responder {
+    let namespace_privilege = user_namespace_privilege!(req);
+    let tenant = param.tenant.as_ref().map(|_| param.get_tenant());
+    match app.config_addr.send(ConfigCmd::QueryLockList(tenant)).await {
+        Ok(Ok(ConfigResult::LockList(list))) => {
+            let list: Vec<Arc<ConfigLockDo>> = list
+                .into_iter()
+                .filter(|e| namespace_privilege.check_permission(&e.tenant))
+                .collect();
+            HttpResponse::Ok().json(ApiResult::success(Some(list)))
+        }
+        Ok(Err(err)) => handle_system_error(err, "query_config_lock_list"),
+        Err(err) => handle_system_error(err, "query_config_lock_list"),
+        _ => handle_system_error("unexpected result", "query_config_lock_list"),
+    }
+}
+
+/// 锁定配置或分组,锁定后拒绝所有途径的发布与删除
+pub async fn lock_config(
+    req: HttpRequest,
+    app: web::Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigLockParam>,
+) -> impl Responder {
+    if let Err(err) = param.check() {
+        return handle_param_error(err, "lock_config");
+    }
+    let tenant = param.get_tenant();
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&tenant) {
+        user_no_namespace_permission!(&tenant);
+    }
+    let lock_user = req
+        .extensions()
+        .get::<Arc<UserSession>>()
+        .map(|e| e.username.clone());
+    let item = ConfigLockDo {
+        tenant,
+        group: param.group.clone().unwrap_or_default(),
+        data_id: param.get_data_id(),
+        reason: param.reason.clone(),
+        lock_user,
+        lock_time: now_millis_i64(),
+    };
+    let value = match item.to_bytes() {
+        Ok(v) => v,
+        Err(err) => return handle_system_error(err, "lock_config"),
+    };
+    let req = TableManagerReq::Set {
+        table_name: CONFIG_LOCK_TABLE_NAME.clone(),
+        key: item.get_lock_key().build_key().into_bytes(),
+        value,
+        last_seq_id: None,
+    };
+    match app.raft_table_route.request(req).await {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(err) => handle_system_error(err, "lock_config"),
+    }
+}
+
+/// 解除配置或分组的锁
+pub async fn unlock_config(
+    req: HttpRequest,
+    app: web::Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigLockParam>,
+) -> impl Responder {
+    if let Err(err) = param.check() {
+        return handle_param_error(err, "unlock_config");
+    }
+    let tenant = param.get_tenant();
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&tenant) {
+        user_no_namespace_permission!(&tenant);
+    }
+    let req = TableManagerReq::Remove {
+        table_name: CONFIG_LOCK_TABLE_NAME.clone(),
+        key: param.to_lock_key().build_key().into_bytes(),
+    };
+    match app.raft_table_route.request(req).await {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(err) => handle_system_error(err, "unlock_config"),
+    }
+}
@@ -0,0 +1,137 @@
+use crate::common::appdata::AppShareData;
+use crate::common::constant::CONFIG_SCHEMA_TABLE_NAME;
+use crate::common::model::{ApiResult, UserSession};
+use crate::config::schema::model::{
+    ConfigSchemaDo, ConfigSchemaParam, ConfigSchemaReq, ConfigSchemaResult,
+};
+use crate::console::v2::{handle_not_found_error, handle_param_error, handle_system_error};
+use crate::raft::db::table::TableManagerReq;
+use crate::{now_millis_i64, user_namespace_privilege, user_no_namespace_permission};
+use actix_http::HttpMessage;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use std::sync::Arc;
+
+async fn query_schema(
+    app: &Arc<AppShareData>,
+    id: Arc<String>,
+) -> anyhow::Result<Option<Arc<ConfigSchemaDo>>> {
+    match app
+        .config_schema_manager
+        .send(ConfigSchemaReq::Query(id))
+        .await??
+    {
+        ConfigSchemaResult::Schema(v) => Ok(v),
+        _ => Err(anyhow::anyhow!("unexpected result")),
+    }
+}
+
+/// 查询配置schema约束列表,只返回有命名空间权限的规则
+pub async fn query_config_schema_list(
+    req: HttpRequest,
+    app: web::Data<Arc<AppShareData>>,
+    web::Query(param): web::Query<ConfigSchemaParam>,
+) -> impl Responder {
+    let tenant = param.tenant.as_ref().map(|_| param.get_tenant());
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_option_value_permission(&tenant, true) {
+        user_no_namespace_permission!(&tenant);
+    }
+    match app
+        .config_schema_manager
+        .send(ConfigSchemaReq::QueryList { tenant })
+        .await
+    {
+        Ok(Ok(ConfigSchemaResult::List(list))) => {
+            let list: Vec<_> = list
+                .into_iter()
+                .filter(|e| namespace_privilege.check_permission(&e.tenant))
+                .collect();
+            HttpResponse::Ok().json(ApiResult::success(Some(list)))
+        }
+        Ok(Err(err)) => handle_system_error(err, "query_config_schema_list"),
+        Err(err) => handle_system_error(err, "query_config_schema_list"),
+        _ => handle_system_error("unexpected result", "query_config_schema_list"),
+    }
+}
+
+/// 新增或更新配置schema约束,通过raft同步到集群各节点
+pub async fn set_config_schema(
+    req: HttpRequest,
+    app: web::Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigSchemaParam>,
+) -> impl Responder {
+    let namespace_privilege = user_namespace_privilege!(req);
+    if let Err(err) = param.check(&namespace_privilege) {
+        return handle_param_error(err, "set_config_schema");
+    }
+    let now = now_millis_i64();
+    let mut create_time = now;
+    let id = match &param.id {
+        Some(id) if !id.is_empty() => {
+            match query_schema(&app, id.clone()).await {
+                Ok(Some(v)) if namespace_privilege.check_permission(&v.tenant) => {
+                    create_time = v.create_time;
+                }
+                Ok(_) => return handle_not_found_error("config schema", id),
+                Err(err) => return handle_system_error(err, "set_config_schema"),
+            }
+            id.clone()
+        }
+        _ => Arc::new(uuid::Uuid::new_v4().simple().to_string()),
+    };
+    let op_user = req
+        .extensions()
+        .get::<Arc<UserSession>>()
+        .map(|session| session.username.clone());
+    let item = ConfigSchemaDo {
+        id,
+        tenant: param.get_tenant(),
+        group: param.group.unwrap_or_default(),
+        data_id_pattern: param.data_id_pattern.unwrap_or_default(),
+        schema: param.schema.unwrap_or_default(),
+        desc: param.desc,
+        op_user,
+        create_time,
+        update_time: now,
+    };
+    let value = match item.to_bytes() {
+        Ok(v) => v,
+        Err(err) => return handle_system_error(err, "set_config_schema"),
+    };
+    let req = TableManagerReq::Set {
+        table_name: CONFIG_SCHEMA_TABLE_NAME.clone(),
+        key: item.id.as_bytes().to_vec(),
+        value,
+        last_seq_id: None,
+    };
+    match app.raft_table_route.request(req).await {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(item))),
+        Err(err) => handle_system_error(err, "set_config_schema"),
+    }
+}
+
+/// 删除配置schema约束
+pub async fn remove_config_schema(
+    req: HttpRequest,
+    app: web::Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<ConfigSchemaParam>,
+) -> impl Responder {
+    let id = match param.id {
+        Some(id) if !id.is_empty() => id,
+        _ => return handle_param_error("id is empty", "remove_config_schema"),
+    };
+    let namespace_privilege = user_namespace_privilege!(req);
+    match query_schema(&app, id.clone()).await {
+        Ok(Some(v)) if namespace_privilege.check_permission(&v.tenant) => {}
+        Ok(_) => return handle_not_found_error("config schema", &id),
+        Err(err) => return handle_system_error(err, "remove_config_schema"),
+    }
+    let req = TableManagerReq::Remove {
+        table_name: CONFIG_SCHEMA_TABLE_NAME.clone(),
+        key: id.as_bytes().to_vec(),
+    };
+    match app.raft_table_route.request(req).await {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(err) => handle_system_error(err, "remove_config_schema"),
+    }
+}
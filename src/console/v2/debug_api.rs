@@ -0,0 +1,67 @@
+use crate::common::appdata::AppShareData;
+use crate::common::model::ApiResult;
+use crate::console::v2::handle_system_error;
+use crate::metrics::dump::{ActorDump, DebugDumpQuery, DebugDumpResult};
+use crate::raft::filestore::raftapply::StateApplyManager;
+use actix::prelude::*;
+use actix_web::{web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+const DEFAULT_TOP_N: usize = 20;
+const MAX_TOP_N: usize = 500;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugDumpParam {
+    pub top_n: Option<usize>,
+}
+
+async fn query_actor_dump<A>(addr: &Addr<A>, msg: DebugDumpQuery) -> anyhow::Result<ActorDump>
+where
+    A: Actor + Handler<DebugDumpQuery>,
+    A::Context: actix::dev::ToEnvelope<A, DebugDumpQuery>,
+{
+    addr.send(msg).await?
+}
+
+async fn do_debug_dump(
+    appdata: &Arc<AppShareData>,
+    top_n: usize,
+) -> anyhow::Result<DebugDumpResult> {
+    let msg = DebugDumpQuery::new(top_n);
+    let mut config_dump = query_actor_dump(&appdata.config_addr, msg).await?;
+    let mut naming_dump = query_actor_dump(&appdata.naming_addr, msg).await?;
+    let top_configs = std::mem::take(&mut config_dump.top_items);
+    let top_services = std::mem::take(&mut naming_dump.top_items);
+    let mut actors = vec![
+        config_dump,
+        naming_dump,
+        query_actor_dump(&appdata.cache_manager, msg).await?,
+        query_actor_dump(&appdata.mcp_manager, msg).await?,
+    ];
+    let apply_manager: Option<Addr<StateApplyManager>> = appdata.factory_data.get_actor();
+    if let Some(apply_manager) = apply_manager {
+        actors.push(query_actor_dump(&apply_manager, msg).await?);
+    }
+    Ok(DebugDumpResult {
+        node_id: appdata.sys_config.raft_node_id,
+        approx_bytes: actors.iter().map(|e| e.approx_bytes).sum(),
+        actors,
+        top_configs,
+        top_services,
+    })
+}
+
+///
+/// 导出当前节点各actor的消息等待时间与内存结构估算大小,用于排查内存增长
+pub async fn debug_dump(
+    appdata: web::Data<Arc<AppShareData>>,
+    web::Query(param): web::Query<DebugDumpParam>,
+) -> impl Responder {
+    let top_n = param.top_n.unwrap_or(DEFAULT_TOP_N).min(MAX_TOP_N);
+    match do_debug_dump(&appdata, top_n).await {
+        Ok(v) => HttpResponse::Ok().json(ApiResult::success(Some(v))),
+        Err(err) => handle_system_error(err, "debug_dump"),
+    }
+}
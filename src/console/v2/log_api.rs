@@ -0,0 +1,31 @@
+use crate::common::model::ApiResult;
+use crate::console::v2::{handle_param_error, handle_system_error};
+use crate::logger::{self, LogLevelParam};
+use actix_web::{web, HttpResponse, Responder};
+
+/// 查询当前节点的日志过滤规则
+pub async fn query_log_level() -> impl Responder {
+    match logger::get_log_level() {
+        Ok(v) => HttpResponse::Ok().json(ApiResult::success(Some(v))),
+        Err(err) => handle_system_error(err, "query_log_level"),
+    }
+}
+
+///
+/// 运行时调整当前节点的日志等级,重启后恢复为RUST_LOG
+/// 优先级:reset > filter > module与level
+pub async fn update_log_level(web::Json(param): web::Json<LogLevelParam>) -> impl Responder {
+    let r = if param.reset.unwrap_or(false) {
+        logger::reset_log_filter()
+    } else if let Some(filter) = param.filter.as_ref().filter(|e| !e.is_empty()) {
+        logger::set_log_filter(filter)
+    } else if param.module.is_some() || param.level.is_some() {
+        logger::set_module_log_level(param.module.as_deref(), param.level.as_deref())
+    } else {
+        return handle_param_error("filter or level is empty", "update_log_level");
+    };
+    match r {
+        Ok(v) => HttpResponse::Ok().json(ApiResult::success(Some(v))),
+        Err(err) => handle_param_error(err, "update_log_level"),
+    }
+}
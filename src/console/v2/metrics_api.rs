@@ -4,7 +4,10 @@ use crate::console::model::metrics_model::TimelineQueryRequest;
 use crate::grpc::handler::NAMING_ROUTE_REQUEST;
 use crate::grpc::PayloadUtils;
 use crate::metrics::model::{MetricsRequest, MetricsResponse};
-use crate::metrics::timeline::model::TimelineQueryParam;
+use crate::metrics::timeline::core::merge_timeline_response;
+use crate::metrics::timeline::model::{
+    ClusterTimelineQueryResponse, TimelineQueryParam, TimelineQueryResponse,
+};
 use crate::naming::cluster::model::{NamingRouteRequest, NamingRouterResponse};
 use actix_web::web::Data;
 use actix_web::{web, HttpResponse, Responder};
@@ -42,6 +45,39 @@ async fn do_query_metrics_timeline(
     app: Data<Arc<AppShareData>>,
     param: TimelineQueryParam,
 ) -> anyhow::Result<HttpResponse> {
+    if param.all_node {
+        let resp = query_cluster_timeline(&app, param).await?;
+        return Ok(HttpResponse::Ok().json(ApiResult::success(Some(resp))));
+    }
+    let resp = query_node_timeline(&app, param).await?;
+    Ok(HttpResponse::Ok().json(ApiResult::success(Some(resp))))
+}
+
+///
+/// 查询集群各节点时间线并汇总,查询失败的节点不参与汇总
+async fn query_cluster_timeline(
+    app: &Data<Arc<AppShareData>>,
+    param: TimelineQueryParam,
+) -> anyhow::Result<ClusterTimelineQueryResponse> {
+    let nodes = app.naming_node_manage.get_all_valid_nodes().await?;
+    let mut node_list = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        let mut node_param = param.clone();
+        node_param.all_node = false;
+        node_param.node_id = if node.is_local { 0 } else { node.id };
+        match query_node_timeline(app, node_param).await {
+            Ok(v) => node_list.push(v),
+            Err(err) => log::warn!("query node {} timeline error,{}", node.id, err),
+        }
+    }
+    let total = merge_timeline_response(&node_list);
+    Ok(ClusterTimelineQueryResponse { node_list, total })
+}
+
+async fn query_node_timeline(
+    app: &Data<Arc<AppShareData>>,
+    param: TimelineQueryParam,
+) -> anyhow::Result<TimelineQueryResponse> {
     let resp = if param.node_id == 0 || param.node_id == app.sys_config.raft_node_id {
         if let MetricsResponse::TimelineResponse(mut resp) = app
             .metrics_manager
@@ -75,5 +111,5 @@ async fn do_query_metrics_timeline(
             ));
         }
     };
-    Ok(HttpResponse::Ok().json(ApiResult::success(Some(resp))))
+    Ok(resp)
 }
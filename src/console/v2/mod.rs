@@ -1,21 +1,32 @@
 use crate::common::model::ApiResult;
 use actix_web::HttpResponse;
 
+pub mod access_token_api;
+pub mod alert_api;
 pub mod cluster_api;
 pub mod config_api;
+pub mod config_change_api;
+pub mod config_clone_api;
+pub mod config_lock_api;
+pub mod config_schema_api;
+pub mod debug_api;
+pub mod log_api;
 pub mod login_api;
 pub mod mcp_server_api;
 pub mod mcp_tool_spec_api;
 pub mod metrics_api;
 pub mod namespace_api;
 pub mod naming_api;
+pub mod sys_config_api;
 pub mod user_api;
+pub mod webhook_api;
 
 pub const ERROR_CODE_SYSTEM_ERROR: &str = "SYSTEM_ERROR";
 pub const ERROR_CODE_PARAM_ERROR: &str = "PARAM_ERROR";
 pub const ERROR_CODE_NOT_FOUND: &str = "NOT_FOUND";
 pub const ERROR_CODE_MCP_MANAGER_ERROR: &str = "MCP_MANAGER_ERROR";
 pub const ERROR_CODE_RAFT_ERROR: &str = "RAFT_ERROR";
+pub const ERROR_CODE_CONFIG_NEED_APPROVAL: &str = "CONFIG_NEED_APPROVAL";
 
 pub enum ApiResponse<T>
 where
@@ -64,6 +75,19 @@ pub fn handle_not_found_error(resource: &str, identifier: &str) -> HttpResponse
     ))
 }
 
+/// 处理需要审批的配置变更
+pub fn handle_config_need_approval_error(tenant: &str, context: &str) -> HttpResponse {
+    log::warn!(
+        "{}: namespace {} need config change approval",
+        context,
+        tenant
+    );
+    HttpResponse::Ok().json(ApiResult::<()>::error(
+        ERROR_CODE_CONFIG_NEED_APPROVAL.to_string(),
+        Some("the namespace requires config changes to be submitted for approval".to_string()),
+    ))
+}
+
 /// 处理MCP Manager错误
 pub fn handle_mcp_manager_error(error: impl std::fmt::Display, operation: &str) -> HttpResponse {
     log::error!("MCP Manager {} operation failed: {}", operation, error);
@@ -17,7 +17,7 @@ pub async fn query_namespace_list(
     let namespaces = NamespaceUtils::get_namespaces(&app_data)
         .await
         .unwrap_or_default();
-    let namespaces = if namespace_privilege.is_all() {
+    let mut namespaces: Vec<NamespaceInfo> = if namespace_privilege.is_all() {
         namespaces
     } else {
         namespaces
@@ -25,6 +25,9 @@ pub async fn query_namespace_list(
             .filter(|e| namespace_privilege.check_option_value_permission(&e.namespace_id, false))
             .collect()
     };
+    if let Err(err) = NamespaceUtils::fill_namespace_usage(&app_data, &mut namespaces).await {
+        log::warn!("fill_namespace_usage error:{}", err);
+    }
     HttpResponse::Ok().json(ApiResult::success(Some(namespaces)))
 }
 
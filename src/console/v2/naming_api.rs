@@ -3,7 +3,7 @@ use crate::common::model::{ApiResult, PageResult};
 use crate::console::model::naming_model::{
     InstanceParams, ServiceDto, ServiceParam, ServiceQueryListRequest,
 };
-use crate::console::v2::ERROR_CODE_SYSTEM_ERROR;
+use crate::console::v2::{handle_system_error, ERROR_CODE_SYSTEM_ERROR};
 use crate::grpc::handler::NAMING_ROUTE_REQUEST;
 use crate::grpc::PayloadUtils;
 use crate::naming::api_model::InstanceVO;
@@ -175,6 +175,32 @@ pub async fn query_instances_list(
     }
 }
 
+///
+/// 查询服务下实例的上下线、健康状态变更历史与抖动标记,只包含当前节点记录的变更
+pub async fn query_instance_history(
+    req: HttpRequest,
+    param: web::Query<ServiceParam>,
+    appdata: Data<Arc<AppShareData>>,
+) -> impl Responder {
+    let service_key = param.to_key();
+    let namespace_privilege = user_namespace_privilege!(req);
+    if !namespace_privilege.check_permission(&service_key.namespace_id) {
+        user_no_namespace_permission!(&service_key.namespace_id);
+    }
+    match appdata
+        .naming_addr
+        .send(NamingCmd::QueryInstanceHistory(service_key))
+        .await
+    {
+        Ok(Ok(NamingResult::InstanceHistory(list))) => {
+            HttpResponse::Ok().json(ApiResult::success(Some(list)))
+        }
+        Ok(Err(err)) => handle_system_error(err, "query_instance_history"),
+        Err(err) => handle_system_error(err, "query_instance_history"),
+        _ => handle_system_error("unexpected result", "query_instance_history"),
+    }
+}
+
 pub async fn get_instance(
     req: HttpRequest,
     appdata: Data<Arc<AppShareData>>,
@@ -0,0 +1,59 @@
+use crate::common::appdata::AppShareData;
+use crate::common::constant::SYS_CONFIG_TABLE_NAME;
+use crate::common::model::ApiResult;
+use crate::console::v2::{handle_param_error, handle_system_error};
+use crate::limiter::model::{
+    RequestLimitConfig, RequestLimitReq, RequestLimitResult, REQUEST_LIMIT_CONFIG_KEY,
+};
+use crate::raft::db::table::TableManagerReq;
+use actix_web::{web, HttpResponse, Responder};
+use std::sync::Arc;
+
+/// 查询当前生效的请求限流配置
+pub async fn query_request_limit(app: web::Data<Arc<AppShareData>>) -> impl Responder {
+    match app
+        .request_limit_manager
+        .send(RequestLimitReq::QueryConfig)
+        .await
+    {
+        Ok(Ok(RequestLimitResult::Config(config))) => {
+            HttpResponse::Ok().json(ApiResult::success(Some(config)))
+        }
+        Ok(Err(err)) => handle_system_error(err, "query_request_limit"),
+        Err(err) => handle_system_error(err, "query_request_limit"),
+        _ => handle_system_error("unexpected result", "query_request_limit"),
+    }
+}
+
+/// 运行时调整请求限流配置,通过raft同步到集群各节点
+pub async fn update_request_limit(
+    app: web::Data<Arc<AppShareData>>,
+    web::Json(config): web::Json<RequestLimitConfig>,
+) -> impl Responder {
+    let value = match config.to_bytes() {
+        Ok(v) => v,
+        Err(err) => return handle_param_error(err, "update_request_limit"),
+    };
+    let req = TableManagerReq::Set {
+        table_name: SYS_CONFIG_TABLE_NAME.clone(),
+        key: REQUEST_LIMIT_CONFIG_KEY.as_bytes().to_vec(),
+        value,
+        last_seq_id: None,
+    };
+    match app.raft_table_route.request(req).await {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(err) => handle_system_error(err, "update_request_limit"),
+    }
+}
+
+/// 清除运行时限流配置,恢复为启动参数中的配置
+pub async fn reset_request_limit(app: web::Data<Arc<AppShareData>>) -> impl Responder {
+    let req = TableManagerReq::Remove {
+        table_name: SYS_CONFIG_TABLE_NAME.clone(),
+        key: REQUEST_LIMIT_CONFIG_KEY.as_bytes().to_vec(),
+    };
+    match app.raft_table_route.request(req).await {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(err) => handle_system_error(err, "reset_request_limit"),
+    }
+}
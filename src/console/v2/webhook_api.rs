@@ -0,0 +1,146 @@
+use crate::common::appdata::AppShareData;
+use crate::common::constant::WEBHOOK_TABLE_NAME;
+use crate::common::model::privilege::NamespacePrivilegeGroup;
+use crate::common::model::{ApiResult, UserSession};
+use crate::console::v2::{handle_not_found_error, handle_param_error, handle_system_error};
+use crate::raft::db::table::TableManagerReq;
+use crate::webhook::model::{WebhookDo, WebhookDto, WebhookParam, WebhookReq, WebhookResult};
+use crate::{now_millis_i64, user_namespace_privilege};
+use actix_http::HttpMessage;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use std::sync::Arc;
+
+fn check_namespace(
+    namespace_privilege: &NamespacePrivilegeGroup,
+    namespace: &Option<Arc<String>>,
+) -> bool {
+    match namespace {
+        Some(namespace) => namespace_privilege.check_permission(namespace),
+        None => namespace_privilege.is_all(),
+    }
+}
+
+async fn query_webhook(
+    app: &Arc<AppShareData>,
+    id: Arc<String>,
+) -> anyhow::Result<Option<Arc<WebhookDo>>> {
+    match app.webhook_manager.send(WebhookReq::Query(id)).await?? {
+        WebhookResult::Webhook(v) => Ok(v),
+        _ => Err(anyhow::anyhow!("unexpected result")),
+    }
+}
+
+/// 查询webhook订阅列表,只返回有命名空间权限的订阅
+pub async fn query_webhook_list(
+    req: HttpRequest,
+    app: web::Data<Arc<AppShareData>>,
+    web::Query(param): web::Query<WebhookParam>,
+) -> impl Responder {
+    let namespace_privilege = user_namespace_privilege!(req);
+    match app
+        .webhook_manager
+        .send(WebhookReq::QueryList {
+            namespace: param.get_namespace(),
+        })
+        .await
+    {
+        Ok(Ok(WebhookResult::List(list))) => {
+            let list: Vec<WebhookDto> = list
+                .into_iter()
+                .filter(|e| check_namespace(&namespace_privilege, &e.namespace))
+                .collect();
+            HttpResponse::Ok().json(ApiResult::success(Some(list)))
+        }
+        Ok(Err(err)) => handle_system_error(err, "query_webhook_list"),
+        Err(err) => handle_system_error(err, "query_webhook_list"),
+        _ => handle_system_error("unexpected result", "query_webhook_list"),
+    }
+}
+
+/// 新增或更新webhook订阅,通过raft同步到集群各节点
+pub async fn set_webhook(
+    req: HttpRequest,
+    app: web::Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<WebhookParam>,
+) -> impl Responder {
+    let namespace_privilege = user_namespace_privilege!(req);
+    if let Err(err) = param.check(&namespace_privilege) {
+        return handle_param_error(err, "set_webhook");
+    }
+    let now = now_millis_i64();
+    let mut create_time = now;
+    let mut secret = param.secret.clone().filter(|e| !e.is_empty());
+    let id = match &param.id {
+        Some(id) if !id.is_empty() => {
+            match query_webhook(&app, id.clone()).await {
+                Ok(Some(v)) if check_namespace(&namespace_privilege, &v.namespace) => {
+                    create_time = v.create_time;
+                    if secret.is_none() {
+                        secret = v.secret.clone();
+                    }
+                }
+                Ok(_) => return handle_not_found_error("webhook", id),
+                Err(err) => return handle_system_error(err, "set_webhook"),
+            }
+            id.clone()
+        }
+        _ => Arc::new(uuid::Uuid::new_v4().simple().to_string()),
+    };
+    let op_user = req
+        .extensions()
+        .get::<Arc<UserSession>>()
+        .map(|session| session.username.clone());
+    let item = WebhookDo {
+        id,
+        name: param.name.clone().unwrap_or_default(),
+        url: param.url.clone().unwrap_or_default(),
+        event_types: param.event_types.clone().unwrap_or_default(),
+        namespace: param.get_namespace(),
+        group: param.get_group(),
+        secret,
+        enabled: param.enabled.unwrap_or(true),
+        op_user,
+        create_time,
+        update_time: now,
+    };
+    let value = match item.to_bytes() {
+        Ok(v) => v,
+        Err(err) => return handle_system_error(err, "set_webhook"),
+    };
+    let req = TableManagerReq::Set {
+        table_name: WEBHOOK_TABLE_NAME.clone(),
+        key: item.id.as_bytes().to_vec(),
+        value,
+        last_seq_id: None,
+    };
+    match app.raft_table_route.request(req).await {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(WebhookDto::from(&item)))),
+        Err(err) => handle_system_error(err, "set_webhook"),
+    }
+}
+
+/// 删除webhook订阅
+pub async fn remove_webhook(
+    req: HttpRequest,
+    app: web::Data<Arc<AppShareData>>,
+    web::Json(param): web::Json<WebhookParam>,
+) -> impl Responder {
+    let id = match param.id {
+        Some(id) if !id.is_empty() => id,
+        _ => return handle_param_error("id is empty", "remove_webhook"),
+    };
+    let namespace_privilege = user_namespace_privilege!(req);
+    match query_webhook(&app, id.clone()).await {
+        Ok(Some(v)) if check_namespace(&namespace_privilege, &v.namespace) => {}
+        Ok(_) => return handle_not_found_error("webhook", &id),
+        Err(err) => return handle_system_error(err, "remove_webhook"),
+    }
+    let req = TableManagerReq::Remove {
+        table_name: WEBHOOK_TABLE_NAME.clone(),
+        key: id.as_bytes().to_vec(),
+    };
+    match app.raft_table_route.request(req).await {
+        Ok(_) => HttpResponse::Ok().json(ApiResult::success(Some(true))),
+        Err(err) => handle_system_error(err, "remove_webhook"),
+    }
+}
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 pub const SUCCESS_CODE: u16 = 200u16;
 pub const NOT_FOUND: u16 = 300u16;
 pub const ERROR_CODE: u16 = 500u16;
+pub const OVER_THRESHOLD: u16 = 503u16;
 
 pub const INTERNAL_MODEL: &str = "internal";
 pub const CONFIG_MODEL: &str = "config";
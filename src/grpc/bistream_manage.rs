@@ -20,32 +20,77 @@ use super::{
     nacos_proto::Payload,
     PayloadUtils,
 };
-use crate::common::constant::EMPTY_CLIENT_VERSION;
+use crate::common::constant::{EMPTY_ARC_STRING, EMPTY_CLIENT_VERSION};
 use crate::common::model::ClientVersion;
 use crate::grpc::api_model::ConnectionSetupRequest;
 use crate::grpc::bistream_conn::NamespaceType;
+use crate::metrics::core::MetricsManager;
+use crate::metrics::metrics_key::MetricsKey;
+use crate::metrics::model::{MetricsItem, MetricsRecord, MetricsRequest};
+use crate::otel;
 use actix::prelude::*;
 use bean_factory::{bean, Inject};
 use inner_mem_cache::TimeoutSet;
+use opentelemetry::KeyValue;
+use serde::{Deserialize, Serialize};
+
+/// nacos客户端在ConnectionSetupRequest labels中上报应用名的key
+const LABEL_APP_NAME: &str = "AppName";
 
 pub(crate) struct ConnCacheItem {
     last_active_time: u64,
+    connect_time: u64,
     conn: Addr<BiStreamConn>,
+    pub(crate) client_ip: Arc<String>,
     pub(crate) client_version: Arc<ClientVersion>,
     pub(crate) namespace: NamespaceType,
+    pub(crate) app_name: Arc<String>,
+    pub(crate) labels: Arc<HashMap<String, String>>,
 }
 
 impl ConnCacheItem {
-    fn new(last_active_time: u64, conn: Addr<BiStreamConn>) -> Self {
+    fn new(last_active_time: u64, conn: Addr<BiStreamConn>, client_ip: Arc<String>) -> Self {
         Self {
             last_active_time,
+            connect_time: last_active_time,
             conn,
+            client_ip,
             client_version: EMPTY_CLIENT_VERSION.clone(),
             namespace: NamespaceType::Unknown,
+            app_name: EMPTY_ARC_STRING.clone(),
+            labels: Default::default(),
+        }
+    }
+
+    fn to_info(&self, client_id: &Arc<String>) -> ConnectionInfo {
+        ConnectionInfo {
+            client_id: client_id.clone(),
+            client_ip: self.client_ip.clone(),
+            client_version: self.client_version.to_string(),
+            namespace: self.namespace.to_str().to_owned(),
+            app_name: self.app_name.clone(),
+            labels: self.labels.clone(),
+            connect_time: self.connect_time,
+            last_active_time: self.last_active_time,
         }
     }
 }
 
+///
+/// grpc长链接信息
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionInfo {
+    pub client_id: Arc<String>,
+    pub client_ip: Arc<String>,
+    pub client_version: String,
+    pub namespace: String,
+    pub app_name: Arc<String>,
+    pub labels: Arc<HashMap<String, String>>,
+    pub connect_time: u64,
+    pub last_active_time: u64,
+}
+
 #[bean(inject)]
 #[derive(Default)]
 pub struct BiStreamManage {
@@ -57,6 +102,10 @@ pub struct BiStreamManage {
     request_id: u64,
     config_addr: Option<Addr<ConfigActor>>,
     naming_addr: Option<Addr<NamingActor>>,
+    metrics_manager: Option<Addr<MetricsManager>>,
+    /// 单个客户端ip最大长链接数,0表示不限制
+    conn_per_ip_limit: u32,
+    ip_conn_count: HashMap<Arc<String>, u32>,
 }
 
 impl BiStreamManage {
@@ -68,18 +117,83 @@ impl BiStreamManage {
         }
     }
 
-    pub fn add_conn(&mut self, client_id: Arc<String>, sender: Addr<BiStreamConn>) {
+    pub fn add_conn(
+        &mut self,
+        client_id: Arc<String>,
+        sender: Addr<BiStreamConn>,
+        client_ip: Arc<String>,
+    ) {
         log::info!("add_conn client_id:{}", &client_id);
-        let now = now_millis();
-        let item = ConnCacheItem::new(now, sender);
-        if let Some(old_conn) = self.conn_cache.insert(client_id.clone(), item) {
+        if let Some(old_conn) = self.remove_conn(&client_id) {
             log::info!("add_conn remove old conn:{}", &client_id);
             old_conn.conn.do_send(BiStreamSenderCmd::Close);
         }
+        let ip_conn_count = self
+            .ip_conn_count
+            .get(&client_ip)
+            .cloned()
+            .unwrap_or_default();
+        if self.conn_per_ip_limit > 0 && ip_conn_count >= self.conn_per_ip_limit {
+            //与nacos保持一致，超过限制时先通知客户端重置链接再关闭
+            log::warn!(
+                "add_conn reject client_id:{},client ip conn size over limit:{}",
+                &client_id,
+                self.conn_per_ip_limit
+            );
+            sender.do_send(BiStreamSenderCmd::Reset(self.next_request_id(), None, None));
+            sender.do_send(BiStreamSenderCmd::Close);
+            if let Some(metrics_manager) = &self.metrics_manager {
+                metrics_manager.do_send(MetricsRequest::Record(MetricsItem::new(
+                    MetricsKey::GrpcConnLimitedCount,
+                    MetricsRecord::CounterInc(1),
+                )));
+            }
+            return;
+        }
+        self.ip_conn_count
+            .insert(client_ip.clone(), ip_conn_count + 1);
+        let now = now_millis();
+        let item = ConnCacheItem::new(now, sender, client_ip);
+        self.conn_cache.insert(client_id.clone(), item);
         self.active_time_set
             .add(now + self.detection_time_out, client_id);
     }
 
+    fn remove_conn(&mut self, client_id: &Arc<String>) -> Option<ConnCacheItem> {
+        let item = self.conn_cache.remove(client_id)?;
+        if let Some(count) = self.ip_conn_count.get_mut(&item.client_ip) {
+            if *count > 1 {
+                *count -= 1;
+            } else {
+                self.ip_conn_count.remove(&item.client_ip);
+            }
+        }
+        Some(item)
+    }
+
+    ///
+    /// 管理端主动断开客户端链接，客户端收到重置请求后会重新建立链接
+    fn reset_conn(&mut self, client_id: &Arc<String>) -> bool {
+        if let Some(item) = self.remove_conn(client_id) {
+            item.conn
+                .do_send(BiStreamSenderCmd::Reset(self.next_request_id(), None, None));
+            item.conn.do_send(BiStreamSenderCmd::Close);
+            self.remove_client_data(client_id.clone());
+            true
+        } else {
+            false
+        }
+    }
+
+    fn remove_client_data(&self, client_id: Arc<String>) {
+        if let Some(config_addr) = &self.config_addr {
+            config_addr.do_send(ConfigCmd::RemoveSubscribeClient(client_id.clone()))
+        }
+        if let Some(naming_addr) = &self.naming_addr {
+            naming_addr.do_send(NamingCmd::RemoveClient(client_id));
+        }
+    }
+
     fn active_client(&mut self, client_id: Arc<String>) -> anyhow::Result<Arc<ClientVersion>> {
         let now = now_millis();
         if let Some(item) = self.conn_cache.get_mut(&client_id) {
@@ -143,7 +257,7 @@ impl BiStreamManage {
             log::info!("check timeout close client, size:{}", del_keys.len());
         }
         for key in &del_keys {
-            if let Some(item) = self.conn_cache.remove(key) {
+            if let Some(item) = self.remove_conn(key) {
                 //item.conn.do_send(BiStreamSenderCmd::Reset(self.next_request_id(),None,None));
                 item.conn.do_send(BiStreamSenderCmd::Close);
             }
@@ -190,6 +304,7 @@ impl Inject for BiStreamManage {
     ) {
         self.config_addr = factory_data.get_actor();
         self.naming_addr = factory_data.get_actor();
+        self.metrics_manager = factory_data.get_actor();
         log::info!("BiStreamManage inject complete");
     }
 }
@@ -205,15 +320,22 @@ impl Supervised for BiStreamManage {
 pub enum BiStreamManageCmd {
     Response(Arc<String>, Payload),
     ConnClose(Arc<String>),
-    AddConn(Arc<String>, BiStreamConn),
+    AddConn(Arc<String>, BiStreamConn, Arc<String>),
     ActiveClinet(Arc<String>),
     NotifyConfig(ConfigKey, HashSet<Arc<String>>),
     NotifyNaming(ServiceKey, HashSet<Arc<String>>, ServiceInfo),
     QueryConnList,
+    QueryConnInfo(Arc<String>),
+    QueryConnInfoList,
+    ResetConn(Arc<String>),
+    UpdateConnPerIpLimit(u32),
 }
 
 pub enum BiStreamManageResult {
     ConnList(Vec<Arc<String>>),
+    ConnInfo(Option<ConnectionInfo>),
+    ConnInfoList(Vec<ConnectionInfo>),
+    ResetResult(bool),
     ClientInfo(Arc<ClientVersion>),
     None,
 }
@@ -240,23 +362,30 @@ impl Handler<BiStreamManageCmd> for BiStreamManage {
                                     Arc::new(ClientVersion::from_string(&client_version));
                             }
                             item.namespace = NamespaceType::from_option(request.tenant);
+                            let headers = request.headers.unwrap_or_default();
+                            if let Some(app_name) = request
+                                .labels
+                                .as_ref()
+                                .and_then(|v| v.get(LABEL_APP_NAME))
+                                .or_else(|| headers.get("app"))
+                            {
+                                item.app_name = Arc::new(app_name.to_owned());
+                            }
+                            if let Some(labels) = request.labels {
+                                item.labels = Arc::new(labels);
+                            }
                         }
                     }
                     self.active_client(client_id).ok();
                 }
             }
             BiStreamManageCmd::ConnClose(client_id) => {
-                self.conn_cache.remove(&client_id);
-                if let Some(config_addr) = &self.config_addr {
-                    config_addr.do_send(ConfigCmd::RemoveSubscribeClient(client_id.clone()))
-                }
-                if let Some(naming_addr) = &self.naming_addr {
-                    naming_addr.do_send(NamingCmd::RemoveClient(client_id));
-                }
+                self.remove_conn(&client_id);
+                self.remove_client_data(client_id);
                 //println!("|ConnClose|conn size: {}",self.conn_cache.len());
             }
-            BiStreamManageCmd::AddConn(client_id, conn) => {
-                self.add_conn(client_id, conn.start());
+            BiStreamManageCmd::AddConn(client_id, conn, client_ip) => {
+                self.add_conn(client_id, conn.start(), client_ip);
                 //println!("|AddConn|conn size: {}",self.conn_cache.len());
             }
             BiStreamManageCmd::ActiveClinet(client_id) => {
@@ -264,6 +393,15 @@ impl Handler<BiStreamManageCmd> for BiStreamManage {
                 return Ok(BiStreamManageResult::ClientInfo(client_version));
             }
             BiStreamManageCmd::NotifyConfig(config_key, client_id_set) => {
+                let _span = otel::start_internal_span(
+                    "config.notify",
+                    vec![
+                        KeyValue::new("config.data_id", config_key.data_id.as_ref().to_owned()),
+                        KeyValue::new("config.group", config_key.group.as_ref().to_owned()),
+                        KeyValue::new("config.tenant", config_key.tenant.as_ref().to_owned()),
+                        KeyValue::new("notify.client_count", client_id_set.len() as i64),
+                    ],
+                );
                 let tenant = config_key.tenant.clone();
                 let mut request = ConfigChangeNotifyRequest {
                     group: config_key.group,
@@ -301,6 +439,21 @@ impl Handler<BiStreamManageCmd> for BiStreamManage {
                 }
             }
             BiStreamManageCmd::NotifyNaming(service_key, client_id_set, service_info) => {
+                let _span = otel::start_internal_span(
+                    "naming.notify",
+                    vec![
+                        KeyValue::new(
+                            "naming.service_name",
+                            service_key.service_name.as_ref().to_owned(),
+                        ),
+                        KeyValue::new("naming.group", service_key.group_name.as_ref().to_owned()),
+                        KeyValue::new(
+                            "naming.namespace",
+                            service_key.namespace_id.as_ref().to_owned(),
+                        ),
+                        KeyValue::new("notify.client_count", client_id_set.len() as i64),
+                    ],
+                );
                 let service_info = ModelConverter::to_api_service_info(service_info);
                 let request = NotifySubscriberRequest {
                     namespace: Some(service_key.namespace_id),
@@ -328,6 +481,30 @@ impl Handler<BiStreamManageCmd> for BiStreamManage {
                 }
                 return Ok(BiStreamManageResult::ConnList(list));
             }
+            BiStreamManageCmd::QueryConnInfo(client_id) => {
+                let info = self
+                    .conn_cache
+                    .get(&client_id)
+                    .map(|item| item.to_info(&client_id));
+                return Ok(BiStreamManageResult::ConnInfo(info));
+            }
+            BiStreamManageCmd::QueryConnInfoList => {
+                let mut list: Vec<ConnectionInfo> = self
+                    .conn_cache
+                    .iter()
+                    .map(|(client_id, item)| item.to_info(client_id))
+                    .collect();
+                list.sort_by_key(|a| a.connect_time);
+                return Ok(BiStreamManageResult::ConnInfoList(list));
+            }
+            BiStreamManageCmd::ResetConn(client_id) => {
+                log::info!("reset conn by manager,client_id:{}", &client_id);
+                let r = self.reset_conn(&client_id);
+                return Ok(BiStreamManageResult::ResetResult(r));
+            }
+            BiStreamManageCmd::UpdateConnPerIpLimit(limit) => {
+                self.conn_per_ip_limit = limit;
+            }
         }
         Ok(BiStreamManageResult::None)
     }
@@ -4,6 +4,7 @@ use std::sync::Arc;
 
 use crate::common::string_utils::StringUtils;
 use crate::config::config_type::ConfigType;
+use crate::config::validate::check_publish_content;
 use crate::config::ConfigUtils;
 use crate::grpc::HandlerResult;
 use crate::{
@@ -52,6 +53,21 @@ impl PayloadHandler for ConfigPublishRequestHandler {
         );
         req.config_type = config_type;
         req.desc = desc;
+        if let Err(err) = check_publish_content(
+            &self.app_data,
+            &req.config_key,
+            &req.config_type,
+            &req.value,
+        )
+        .await
+        {
+            let mut response = BaseResponse::build_error_response(400u16, err.to_string());
+            response.request_id = request.request_id;
+            return Ok(HandlerResult::success(PayloadUtils::build_payload(
+                "ErrorResponse",
+                serde_json::to_string(&response)?,
+            )));
+        }
         match self.app_data.config_route.set_config(req).await {
             Ok(_res) => {
                 //let res:ConfigResult = res.unwrap();
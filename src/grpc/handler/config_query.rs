@@ -41,7 +41,7 @@ impl PayloadHandler for ConfigQueryRequestHandler {
     ) -> anyhow::Result<HandlerResult> {
         let body_vec = request_payload.body.unwrap_or_default().value;
         let request: ConfigQueryRequest = serde_json::from_slice(&body_vec)?;
-        let cmd = ConfigCmd::GET(ConfigKey::new(
+        let cmd = ConfigCmd::GetResolved(ConfigKey::new(
             &request.data_id,
             &request.group,
             &ConfigUtils::default_tenant(request.tenant),
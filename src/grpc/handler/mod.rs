@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use crate::access_token::model::{check_scopes, AccessTokenScope};
 use crate::common::appdata::AppShareData;
 
 use self::{
@@ -13,13 +14,16 @@ use self::{
 };
 
 use super::{
-    api_model::{BaseResponse, ServerCheckResponse, SUCCESS_CODE},
+    api_model::{BaseResponse, ServerCheckResponse, OVER_THRESHOLD, SUCCESS_CODE},
     nacos_proto::Payload,
     HandleLogArgs, HandlerResult, PayloadHandler, PayloadUtils, RequestMeta,
 };
 use crate::grpc::handler::raft_append::RaftAppendRequestHandler;
 use crate::grpc::handler::raft_snapshot::RaftSnapshotRequestHandler;
 use crate::grpc::handler::raft_vote::RaftVoteRequestHandler;
+use crate::limiter::model::{RequestLimitReq, RequestLimitResult};
+use crate::metrics::metrics_key::MetricsKey;
+use crate::metrics::model::{MetricsItem, MetricsRecord, MetricsRequest};
 use async_trait::async_trait;
 
 pub mod config_change_batch_listen;
@@ -123,6 +127,77 @@ impl InvokerHandler {
             || NAMING_ROUTE_REQUEST.eq(t)
     }
 
+    /// 集群内部请求与检测请求不参与客户端限流
+    pub fn ignore_limit(&self, t: &str) -> bool {
+        self.ignore_auth(t)
+    }
+
+    pub fn is_write_request(&self, t: &str) -> bool {
+        CONFIG_PUBLISH_REQUEST.eq(t)
+            || CONFIG_REMOVE_REQUEST.eq(t)
+            || INSTANCE_REQUEST.eq(t)
+            || BATCH_INSTANCE_REQUEST.eq(t)
+    }
+
+    /// 个人访问令牌按请求涉及的命名空间校验读写权限
+    fn check_access_scopes(
+        &self,
+        url: &str,
+        scopes: &[AccessTokenScope],
+        request_payload: &Payload,
+    ) -> bool {
+        if self.ignore_auth(url) {
+            return true;
+        }
+        let is_write = self.is_write_request(url);
+        let body: serde_json::Value = match &request_payload.body {
+            Some(body) => serde_json::from_slice(&body.value).unwrap_or_default(),
+            None => serde_json::Value::Null,
+        };
+        let mut namespaces = vec![];
+        if let Some(list) = body.get("configListenContexts").and_then(|v| v.as_array()) {
+            for item in list {
+                namespaces.push(item.get("tenant").and_then(|v| v.as_str()).unwrap_or(""));
+            }
+        } else {
+            let namespace = body
+                .get("tenant")
+                .or_else(|| body.get("namespace"))
+                .and_then(|v| v.as_str())
+                .unwrap_or("");
+            namespaces.push(namespace);
+        }
+        namespaces
+            .iter()
+            .all(|namespace| check_scopes(scopes, namespace, is_write))
+    }
+
+    async fn is_over_limit(&self, url: &str, request_meta: &RequestMeta) -> bool {
+        let req = RequestLimitReq::Acquire {
+            client_ip: Arc::new(request_meta.client_ip.clone()),
+            request_type: Arc::new(url.to_owned()),
+        };
+        if let Ok(Ok(RequestLimitResult::Limited(limit_type))) =
+            self.app.request_limit_manager.send(req).await
+        {
+            log::warn!(
+                "grpc request over limit,client_ip:{},type:{},limit_type:{:?}",
+                &request_meta.client_ip,
+                url,
+                limit_type
+            );
+            self.app
+                .metrics_manager
+                .do_send(MetricsRequest::Record(MetricsItem::new(
+                    MetricsKey::GrpcRequestLimitedCount,
+                    MetricsRecord::CounterInc(1),
+                )));
+            true
+        } else {
+            false
+        }
+    }
+
     pub fn add_raft_handler(&mut self, app_data: &Arc<AppShareData>) {
         self.add_handler(
             RAFT_APPEND_REQUEST,
@@ -233,6 +308,24 @@ impl PayloadHandler for InvokerHandler {
                     "request cluster token is invalid".to_string(),
                 ));
             }
+            if let Some(scopes) = request_meta
+                .token_session
+                .as_ref()
+                .and_then(|e| e.access_scopes.as_ref())
+            {
+                if !self.check_access_scopes(url, scopes, &request_payload) {
+                    return Ok(HandlerResult::error(
+                        403u16,
+                        "access token has no permission for the namespace".to_string(),
+                    ));
+                }
+            }
+            if !self.ignore_limit(url) && self.is_over_limit(url, &request_meta).await {
+                return Ok(HandlerResult::error(
+                    OVER_THRESHOLD,
+                    "over threshold, request is limited".to_string(),
+                ));
+            }
             //println!("InvokerHandler type:{}",url);
             if let Some(handler) = self.match_handler(url) {
                 return handler.handle(request_payload, request_meta).await;
@@ -137,9 +137,12 @@ impl PayloadHandler for BatchInstanceRequestHandler {
                 };
                 NamingCmd::Update(instance, Some(update_tag))
             };
-            match self.app_data.naming_addr.send(cmd).await {
+            let res = match self.app_data.naming_addr.send(cmd).await {
+                Ok(res) => res,
+                Err(err) => Err(err.into()),
+            };
+            match res {
                 Ok(_res) => {
-                    //let res:ConfigResult = res.unwrap();
                     response.result_code = SUCCESS_CODE;
                     if is_de_register {
                         response.r#type = Some(DE_REGISTER_INSTANCE.to_string());
@@ -182,4 +182,17 @@ impl PayloadUtils {
         }
         None
     }
+
+    ///
+    /// 获取请求体中的命名空间,服务请求为namespace,配置请求为tenant
+    pub fn get_payload_namespace(payload: &nacos_proto::Payload) -> Option<String> {
+        #[derive(serde::Deserialize)]
+        struct NamespaceInfo {
+            namespace: Option<String>,
+            tenant: Option<String>,
+        }
+        let body = payload.body.as_ref()?;
+        let info: NamespaceInfo = serde_json::from_slice(&body.value).ok()?;
+        info.namespace.or(info.tenant)
+    }
 }
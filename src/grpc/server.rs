@@ -1,19 +1,25 @@
 use std::sync::Arc;
 use std::time::SystemTime;
 
+use crate::access_token::model::{is_access_token, AccessTokenReq, AccessTokenResult};
 use crate::common::appdata::AppShareData;
 use crate::common::constant::{
     ACCESS_TOKEN_HEADER, AUTHORIZATION_HEADER, EMPTY_ARC_STRING, EMPTY_CLIENT_VERSION,
 };
 use crate::common::model::TokenSession;
 use actix::prelude::*;
+use opentelemetry::trace::{FutureExt, SpanKind, Status, TraceContextExt};
+use opentelemetry::{Context, KeyValue};
 //use tokio_stream::StreamExt;
 
 use crate::grpc::bistream_manage::BiStreamManageResult;
 use crate::grpc::nacos_proto::{request_server, Payload};
 use crate::grpc::{PayloadHandler, PayloadUtils, RequestMeta};
+use crate::logger::access::{self, AccessLogRecord};
+use crate::metrics::labeled::MetricsLabels;
 use crate::metrics::metrics_key::MetricsKey;
-use crate::metrics::model::{MetricsItem, MetricsRecord, MetricsRequest};
+use crate::metrics::model::{LabeledMetricsItem, MetricsItem, MetricsRecord, MetricsRequest};
+use crate::otel::{self, GrpcHeaderExtractor};
 use crate::raft::cache::model::{CacheKey, CacheType, CacheValue};
 use crate::raft::cache::{CacheManager, CacheManagerReq, CacheManagerResult};
 
@@ -47,7 +53,16 @@ impl RequestServerImpl {
         } else {
             EMPTY_ARC_STRING.clone()
         };
-        if self.app.sys_config.openapi_enable_auth && !token.is_empty() {
+        if self.app.sys_config.openapi_enable_auth && is_access_token(&token) {
+            if let Ok(Ok(AccessTokenResult::Session(Some(session)))) = self
+                .app
+                .access_token_manager
+                .send(AccessTokenReq::Check(token))
+                .await
+            {
+                request_meta.token_session = Some(session);
+            }
+        } else if self.app.sys_config.openapi_enable_auth && !token.is_empty() {
             if let Ok(Some(session)) = get_user_session(
                 &self.app.cache_manager,
                 CacheManagerReq::Get(CacheKey::new(CacheType::ApiTokenSession, token.clone())),
@@ -69,19 +84,61 @@ impl RequestServerImpl {
         Ok(())
     }
 
-    fn record_req_metrics(&self, duration: f64, _success: bool) {
+    fn record_req_metrics(
+        &self,
+        request_type: &str,
+        client_ip: &str,
+        namespace: &str,
+        duration: f64,
+        code: u16,
+        trace_cx: &Context,
+    ) {
+        if otel::is_enable() {
+            let span = trace_cx.span();
+            span.set_attribute(KeyValue::new("rpc.nacos.code", code as i64));
+            if code != 200 {
+                span.set_status(Status::error(format!("code {}", code)));
+            }
+        }
+        let rt = duration as f32 * 1000f32;
+        access::write_access_log(&AccessLogRecord {
+            protocol: "grpc",
+            client_ip,
+            request_type,
+            namespace,
+            rt: duration * 1000f64,
+            code,
+            ..Default::default()
+        });
         self.app
             .metrics_manager
             .do_send(MetricsRequest::BatchRecord(vec![
                 MetricsItem::new(
                     MetricsKey::GrpcRequestHandleRtHistogram,
-                    MetricsRecord::HistogramRecord(duration as f32 * 1000f32),
+                    MetricsRecord::HistogramRecord(rt),
                 ),
                 MetricsItem::new(
                     MetricsKey::GrpcRequestTotalCount,
                     MetricsRecord::CounterInc(1),
                 ),
             ]));
+        let labels = MetricsLabels::default()
+            .with("request_type", request_type.to_owned())
+            .with("code", code.to_string());
+        self.app
+            .metrics_manager
+            .do_send(MetricsRequest::BatchLabeledRecord(vec![
+                LabeledMetricsItem::new(
+                    MetricsKey::GrpcRequestTypeHandleRtHistogram,
+                    labels.clone(),
+                    MetricsRecord::HistogramRecord(rt),
+                ),
+                LabeledMetricsItem::new(
+                    MetricsKey::GrpcRequestTypeTotalCount,
+                    labels,
+                    MetricsRecord::CounterInc(1),
+                ),
+            ]));
     }
 }
 
@@ -93,6 +150,14 @@ impl request_server::Request for RequestServerImpl {
     ) -> Result<tonic::Response<Payload>, tonic::Status> {
         let start = SystemTime::now();
         let remote_addr = request.remote_addr().unwrap();
+        let parent_cx = if otel::is_enable() {
+            otel::extract_context(&GrpcHeaderExtractor {
+                metadata: request.metadata(),
+                headers: request.get_ref().metadata.as_ref().map(|e| &e.headers),
+            })
+        } else {
+            Context::new()
+        };
         let payload = request.into_inner();
         let mut request_meta = RequestMeta {
             client_ip: remote_addr.ip().to_string(),
@@ -109,6 +174,27 @@ impl request_server::Request for RequestServerImpl {
             &request_meta.connection_id, &request_type
         );
         let ignore_active_err = self.invoker.ignore_active_err(request_type);
+        let client_ip = request_meta.client_ip.clone();
+        // 只在开启访问日志时解析请求体中的命名空间
+        let namespace = if access::is_enable() {
+            PayloadUtils::get_payload_namespace(&payload).unwrap_or_default()
+        } else {
+            String::new()
+        };
+        let request_type = request_type.to_owned();
+        let trace_cx = match otel::start_span(
+            request_type.clone(),
+            SpanKind::Server,
+            vec![
+                KeyValue::new("rpc.system", "grpc"),
+                KeyValue::new("rpc.method", request_type.clone()),
+                KeyValue::new("client.address", request_meta.client_ip.clone()),
+            ],
+            &parent_cx,
+        ) {
+            Some(span) => parent_cx.with_span(span),
+            None => parent_cx,
+        };
         //self.bistream_manage_addr.do_send(BiStreamManageCmd::ActiveClinet(request_meta.connection_id.clone()));
         let active_result = self
             .app
@@ -136,7 +222,14 @@ impl request_server::Request for RequestServerImpl {
                                 .unwrap_or_default()
                                 .as_secs_f64();
                             log::error!("{}|err|{}|{}", request_log_info, duration, &err_msg);
-                            self.record_req_metrics(duration, false);
+                            self.record_req_metrics(
+                                &request_type,
+                                &client_ip,
+                                &namespace,
+                                duration,
+                                301,
+                                &trace_cx,
+                            );
                             return Ok(tonic::Response::new(PayloadUtils::build_error_payload(
                                 301, err_msg,
                             )));
@@ -154,7 +247,14 @@ impl request_server::Request for RequestServerImpl {
                         .unwrap_or_default()
                         .as_secs_f64();
                     log::error!("{}|err|{}|{}", request_log_info, duration, &err_msg);
-                    self.record_req_metrics(duration, false);
+                    self.record_req_metrics(
+                        &request_type,
+                        &client_ip,
+                        &namespace,
+                        duration,
+                        301,
+                        &trace_cx,
+                    );
                     return Ok(tonic::Response::new(PayloadUtils::build_error_payload(
                         301, err_msg,
                     )));
@@ -172,7 +272,11 @@ impl request_server::Request for RequestServerImpl {
             .await
             .ok();
         let args = self.invoker.get_log_args(&payload, &request_meta);
-        let handle_result = self.invoker.handle(payload, request_meta).await;
+        let handle_result = self
+            .invoker
+            .handle(payload, request_meta)
+            .with_context(trace_cx.clone())
+            .await;
         let duration = SystemTime::now()
             .duration_since(start)
             .unwrap_or_default()
@@ -189,18 +293,39 @@ impl request_server::Request for RequestServerImpl {
                         ""
                     };
                     log::error!("{}|err|{}|{}|{}", request_log_info, duration, &args, msg);
-                    self.record_req_metrics(duration, false);
+                    self.record_req_metrics(
+                        &request_type,
+                        &client_ip,
+                        &namespace,
+                        duration,
+                        500,
+                        &trace_cx,
+                    );
                 } else if duration < 1f64 {
                     if args.enable_log() {
                         log::info!("{}|ok|{}|{}", request_log_info, duration, &args);
                     }
-                    self.record_req_metrics(duration, true);
+                    self.record_req_metrics(
+                        &request_type,
+                        &client_ip,
+                        &namespace,
+                        duration,
+                        200,
+                        &trace_cx,
+                    );
                 } else {
                     if args.enable_log() {
                         //slow request handle
                         log::warn!("{}|ok|{}|{}", request_log_info, duration, &args);
                     }
-                    self.record_req_metrics(duration, true);
+                    self.record_req_metrics(
+                        &request_type,
+                        &client_ip,
+                        &namespace,
+                        duration,
+                        200,
+                        &trace_cx,
+                    );
                 }
                 Ok(tonic::Response::new(res.payload))
             }
@@ -208,7 +333,14 @@ impl request_server::Request for RequestServerImpl {
                 //Err(tonic::Status::aborted(e.to_string()))
                 //log::error!("request_server handler error:{:?}",e);
                 log::error!("{}|err|{}|{}|{}", request_log_info, duration, &args, e);
-                self.record_req_metrics(duration, false);
+                self.record_req_metrics(
+                    &request_type,
+                    &client_ip,
+                    &namespace,
+                    duration,
+                    500,
+                    &trace_cx,
+                );
                 Ok(tonic::Response::new(PayloadUtils::build_error_payload(
                     500u16,
                     e.to_string(),
@@ -237,11 +369,12 @@ impl BiRequestStream for BiRequestStreamServerImpl {
         &self,
         request: tonic::Request<tonic::Streaming<Payload>>,
     ) -> Result<tonic::Response<Self::requestBiStreamStream>, tonic::Status> {
+        let remote_addr = request.remote_addr().unwrap();
         let client_id = Arc::new(format!(
             "{}_{}",
-            self.app.sys_config.raft_node_id,
-            &request.remote_addr().unwrap()
+            self.app.sys_config.raft_node_id, &remote_addr
         ));
+        let client_ip = Arc::new(remote_addr.ip().to_string());
         let req = request.into_inner();
         let (tx, rx) = tokio::sync::mpsc::channel(10);
         let r_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
@@ -253,7 +386,7 @@ impl BiRequestStream for BiRequestStreamServerImpl {
         );
         self.app
             .bi_stream_manage
-            .do_send(BiStreamManageCmd::AddConn(client_id, conn));
+            .do_send(BiStreamManageCmd::AddConn(client_id, conn, client_ip));
         Ok(tonic::Response::new(r_stream))
     }
 }
@@ -1,7 +1,7 @@
 use crate::config::core::ConfigActor;
 use crate::health::model::{
     CheckHealthResult, HealthBackRequest, HealthCheckItem, HealthCheckRequest, HealthCheckType,
-    HealthManagerRequest, HealthManagerResponse,
+    HealthDetail, HealthManagerRequest, HealthManagerResponse, NodeReadyState, RaftReadyInfo,
 };
 use crate::naming::core::NamingActor;
 use crate::now_millis;
@@ -17,6 +17,9 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
+/// 已提交未应用的raft日志数不超过该值才视为追上
+const READY_MAX_APPLY_LAG: u64 = 100;
+
 #[bean(inject)]
 #[derive(Clone, Default)]
 pub struct HealthManager {
@@ -29,6 +32,8 @@ pub struct HealthManager {
     raft_apply_manager: Option<Addr<StateApplyManager>>,
     raft: Option<Arc<NacosRaft>>,
     health_item_map: HashMap<HealthCheckType, HealthCheckItem>,
+    raft_data_loaded: bool,
+    naming_distro_synced: bool,
 }
 
 impl HealthManager {
@@ -78,6 +83,8 @@ impl HealthManager {
             raft_apply_manager: None,
             raft: None,
             health_item_map,
+            raft_data_loaded: false,
+            naming_distro_synced: false,
         }
     }
 
@@ -99,6 +106,73 @@ impl HealthManager {
         }
     }
 
+    fn get_raft_info(&self) -> Option<(RaftReadyInfo, NodeReadyState)> {
+        let raft = self.raft.as_ref()?;
+        let metrics = raft.metrics().borrow().clone();
+        let state = if metrics.current_leader.is_none()
+            || !(metrics.state.is_leader()
+                || metrics.state.is_follower()
+                || metrics.state.is_non_voter())
+        {
+            NodeReadyState::Degraded
+        } else if metrics.state.is_non_voter()
+            || metrics.last_log_index.saturating_sub(metrics.last_applied) > READY_MAX_APPLY_LAG
+        {
+            NodeReadyState::CatchUp
+        } else if metrics.state.is_leader() {
+            NodeReadyState::Leader
+        } else {
+            NodeReadyState::Follower
+        };
+        let info = RaftReadyInfo {
+            state: format!("{:?}", metrics.state),
+            current_term: metrics.current_term,
+            current_leader: metrics.current_leader,
+            last_log_index: metrics.last_log_index,
+            last_applied: metrics.last_applied,
+        };
+        Some((info, state))
+    }
+
+    ///
+    /// 就绪要求:raft有主且日志已应用、raft数据已加载、naming数据已同步、各模块无异常
+    fn get_detail(&self, now: u64) -> HealthDetail {
+        let mut modules: Vec<_> = self
+            .health_item_map
+            .values()
+            .map(|item| item.get_module_info(now))
+            .collect();
+        modules.sort_by_key(|e| e.name);
+        let has_error = self
+            .health_item_map
+            .values()
+            .any(|e| e.check(now).is_error());
+        let live = !self
+            .health_item_map
+            .values()
+            .any(|e| e.check_type != HealthCheckType::RaftCluster && e.check(now).is_error());
+        let (raft, raft_state) = match self.get_raft_info() {
+            Some((info, state)) => (Some(info), state),
+            None => (None, NodeReadyState::Degraded),
+        };
+        let state = if has_error || raft_state == NodeReadyState::Degraded {
+            NodeReadyState::Degraded
+        } else if !self.raft_data_loaded || !self.naming_distro_synced {
+            NodeReadyState::CatchUp
+        } else {
+            raft_state
+        };
+        HealthDetail {
+            state,
+            live,
+            ready: state.is_ready(),
+            raft_data_loaded: self.raft_data_loaded,
+            naming_distro_synced: self.naming_distro_synced,
+            raft,
+            modules,
+        }
+    }
+
     fn do_check(&mut self, ctx: &mut Context<Self>) -> anyhow::Result<()> {
         let self_addr = ctx.address();
         if let Some(config) = self.config_actor.as_ref() {
@@ -174,6 +248,12 @@ impl Handler<HealthBackRequest> for HealthManager {
             HealthBackRequest::Pong(check_type) => {
                 self.update_success_status(check_type);
             }
+            HealthBackRequest::RaftDataLoaded => {
+                self.raft_data_loaded = true;
+            }
+            HealthBackRequest::NamingDistroSynced => {
+                self.naming_distro_synced = true;
+            }
         }
         Ok(())
     }
@@ -182,16 +262,31 @@ impl Handler<HealthBackRequest> for HealthManager {
 impl Handler<HealthManagerRequest> for HealthManager {
     type Result = anyhow::Result<HealthManagerResponse>;
 
-    fn handle(&mut self, _msg: HealthManagerRequest, _ctx: &mut Self::Context) -> Self::Result {
+    fn handle(&mut self, msg: HealthManagerRequest, _ctx: &mut Self::Context) -> Self::Result {
         let now = now_millis();
-        for item in self.health_item_map.values() {
-            let result = item.check(now);
-            if !result.is_success() {
-                return Ok(HealthManagerResponse::StatusResult(result));
+        match msg {
+            HealthManagerRequest::Status => {
+                for item in self.health_item_map.values() {
+                    let result = item.check(now);
+                    if result.is_error() {
+                        return Ok(HealthManagerResponse::StatusResult(result));
+                    }
+                }
+                Ok(HealthManagerResponse::StatusResult(
+                    CheckHealthResult::Success,
+                ))
+            }
+            HealthManagerRequest::ModuleStatus => {
+                let list = self
+                    .health_item_map
+                    .values()
+                    .map(|item| (item.check_type.clone(), item.check(now)))
+                    .collect();
+                Ok(HealthManagerResponse::ModuleStatusResult(list))
+            }
+            HealthManagerRequest::Detail => {
+                Ok(HealthManagerResponse::DetailResult(self.get_detail(now)))
             }
         }
-        Ok(HealthManagerResponse::StatusResult(
-            CheckHealthResult::Success,
-        ))
     }
 }
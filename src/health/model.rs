@@ -1,10 +1,12 @@
 use crate::health::core::HealthManager;
 use actix::{Addr, Message};
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone)]
 pub enum CheckHealthResult {
     Success,
-    //Warning(String),
+    /// 模块响应变慢,超过一半超时时间未响应
+    Warning(String),
     Error(String),
 }
 
@@ -12,7 +14,26 @@ impl CheckHealthResult {
     pub fn is_success(&self) -> bool {
         match self {
             CheckHealthResult::Success => true,
-            CheckHealthResult::Error(_) => false,
+            CheckHealthResult::Warning(_) | CheckHealthResult::Error(_) => false,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self, CheckHealthResult::Error(_))
+    }
+
+    pub fn get_status(&self) -> HealthStatus {
+        match self {
+            CheckHealthResult::Success => HealthStatus::Up,
+            CheckHealthResult::Warning(_) => HealthStatus::Warning,
+            CheckHealthResult::Error(_) => HealthStatus::Down,
+        }
+    }
+
+    pub fn get_message(&self) -> Option<String> {
+        match self {
+            CheckHealthResult::Success => None,
+            CheckHealthResult::Warning(msg) | CheckHealthResult::Error(msg) => Some(msg.clone()),
         }
     }
 }
@@ -54,17 +75,96 @@ pub enum HealthCheckRequest {
 #[rtype(result = "anyhow::Result<()>")]
 pub enum HealthBackRequest {
     Pong(HealthCheckType),
+    /// raft镜像与日志已加载到状态机
+    RaftDataLoaded,
+    /// naming已完成从其它节点同步distro数据
+    NamingDistroSynced,
 }
 
 #[derive(Message, Debug, Clone)]
 #[rtype(result = "anyhow::Result<HealthManagerResponse>")]
 pub enum HealthManagerRequest {
     Status,
+    /// 查询各模块的健康状态
+    ModuleStatus,
+    /// 查询节点存活与就绪的详细状态
+    Detail,
 }
 
 #[derive(Debug, Clone)]
 pub enum HealthManagerResponse {
     StatusResult(CheckHealthResult),
+    ModuleStatusResult(Vec<(HealthCheckType, CheckHealthResult)>),
+    DetailResult(HealthDetail),
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HealthStatus {
+    #[default]
+    Up,
+    Warning,
+    Down,
+}
+
+///
+/// 节点就绪状态
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NodeReadyState {
+    Leader,
+    Follower,
+    /// 数据追赶中:raft数据未加载完、日志未应用完或naming数据未同步完
+    CatchUp,
+    /// 模块异常或raft集群没有主节点
+    #[default]
+    Degraded,
+}
+
+impl NodeReadyState {
+    pub fn is_ready(&self) -> bool {
+        matches!(self, NodeReadyState::Leader | NodeReadyState::Follower)
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthModuleInfo {
+    pub name: &'static str,
+    pub status: HealthStatus,
+    pub last_success_time: u64,
+    pub message: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RaftReadyInfo {
+    pub state: String,
+    pub current_term: u64,
+    pub current_leader: Option<u64>,
+    pub last_log_index: u64,
+    pub last_applied: u64,
+}
+
+impl RaftReadyInfo {
+    pub fn apply_lag(&self) -> u64 {
+        self.last_log_index.saturating_sub(self.last_applied)
+    }
+}
+
+///
+/// 节点存活与就绪详情
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthDetail {
+    pub state: NodeReadyState,
+    /// 除raft集群外的本地模块都未异常
+    pub live: bool,
+    pub ready: bool,
+    pub raft_data_loaded: bool,
+    pub naming_distro_synced: bool,
+    pub raft: Option<RaftReadyInfo>,
+    pub modules: Vec<HealthModuleInfo>,
 }
 
 #[derive(Debug, Clone)]
@@ -90,8 +190,38 @@ impl HealthCheckItem {
     pub fn check(&self, now: u64) -> CheckHealthResult {
         if now > self.last_success_time + self.timeout {
             CheckHealthResult::Error(format!("{} module ill.", self.check_type.name()))
+        } else if now > self.last_success_time + self.timeout / 2 {
+            CheckHealthResult::Warning(format!("{} module slow.", self.check_type.name()))
         } else {
             CheckHealthResult::Success
         }
     }
+
+    pub fn get_module_info(&self, now: u64) -> HealthModuleInfo {
+        let result = self.check(now);
+        HealthModuleInfo {
+            name: self.check_type.name(),
+            status: result.get_status(),
+            last_success_time: self.last_success_time,
+            message: result.get_message(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_health_item() {
+        let item = HealthCheckItem::new(HealthCheckType::Naming, 4000, 10_000);
+        assert!(item.check(11_000).is_success());
+        let result = item.check(13_000);
+        assert!(!result.is_success() && !result.is_error());
+        assert_eq!(result.get_status(), HealthStatus::Warning);
+        let info = item.get_module_info(15_000);
+        assert_eq!(info.status, HealthStatus::Down);
+        assert_eq!(info.last_success_time, 10_000);
+        assert!(info.message.is_some());
+    }
 }
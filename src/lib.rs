@@ -12,12 +12,18 @@ pub mod user;
 pub mod utils;
 pub mod web_config;
 
+pub mod access_token;
+pub mod alert;
 pub mod health;
+pub mod limiter;
+pub mod logger;
 pub mod transfer;
+pub mod webhook;
 
 pub mod ldap;
 pub mod mcp;
 pub mod oauth2;
+pub mod otel;
 pub mod sequence;
 
 pub use inner_mem_cache::TimeoutSet;
@@ -0,0 +1,190 @@
+use crate::grpc::bistream_manage::{BiStreamManage, BiStreamManageCmd};
+use crate::limiter::model::{
+    LimitType, LimiterItem, RequestLimitConfig, RequestLimitReq, RequestLimitResult,
+};
+use crate::now_millis_i64;
+use actix::prelude::*;
+use bean_factory::{bean, BeanFactory, FactoryData, Inject};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 限流器闲置超过该时间后回收
+const LIMITER_IDLE_TIMEOUT_MILLIS: i64 = 60_000;
+
+///
+/// 客户端请求限流管理器
+/// 按客户端ip、按客户端ip+请求类型分别维护令牌桶
+#[bean(inject)]
+pub struct RequestLimitManager {
+    default_config: Arc<RequestLimitConfig>,
+    config: Arc<RequestLimitConfig>,
+    ip_limiters: HashMap<Arc<String>, LimiterItem>,
+    type_limiters: HashMap<(Arc<String>, Arc<String>), LimiterItem>,
+    bi_stream_manage: Option<Addr<BiStreamManage>>,
+}
+
+impl RequestLimitManager {
+    pub fn new(default_config: RequestLimitConfig) -> Self {
+        let default_config = Arc::new(default_config);
+        Self {
+            config: default_config.clone(),
+            default_config,
+            ip_limiters: HashMap::new(),
+            type_limiters: HashMap::new(),
+            bi_stream_manage: None,
+        }
+    }
+
+    fn acquire(
+        &mut self,
+        client_ip: Arc<String>,
+        request_type: Arc<String>,
+        now: i64,
+    ) -> RequestLimitResult {
+        if self.config.ip_qps > 0 {
+            let item = self
+                .ip_limiters
+                .entry(client_ip.clone())
+                .or_insert_with(|| LimiterItem::new(now));
+            if !item.acquire(self.config.ip_qps, now) {
+                return RequestLimitResult::Limited(LimitType::Ip);
+            }
+        }
+        if let Some(qps) = self.config.request_type_qps.get(request_type.as_str()) {
+            if *qps > 0 {
+                let item = self
+                    .type_limiters
+                    .entry((client_ip, request_type))
+                    .or_insert_with(|| LimiterItem::new(now));
+                if !item.acquire(*qps, now) {
+                    return RequestLimitResult::Limited(LimitType::RequestType);
+                }
+            }
+        }
+        RequestLimitResult::Pass
+    }
+
+    fn update_config(&mut self, config: Option<RequestLimitConfig>) {
+        let config = config
+            .map(Arc::new)
+            .unwrap_or_else(|| self.default_config.clone());
+        log::info!("RequestLimitManager|update config:{:?}", &config);
+        self.config = config;
+        self.ip_limiters.clear();
+        self.type_limiters.clear();
+        self.notify_conn_limit();
+    }
+
+    fn notify_conn_limit(&self) {
+        if let Some(bi_stream_manage) = &self.bi_stream_manage {
+            bi_stream_manage.do_send(BiStreamManageCmd::UpdateConnPerIpLimit(
+                self.config.grpc_conn_per_ip,
+            ));
+        }
+    }
+
+    fn clear_idle_limiters(&mut self) {
+        let timeout = now_millis_i64() - LIMITER_IDLE_TIMEOUT_MILLIS;
+        self.ip_limiters.retain(|_, v| v.last_use_time > timeout);
+        self.type_limiters.retain(|_, v| v.last_use_time > timeout);
+    }
+
+    fn clear_heartbeat(&self, ctx: &mut Context<Self>) {
+        ctx.run_later(Duration::from_secs(30), |act, ctx| {
+            act.clear_idle_limiters();
+            act.clear_heartbeat(ctx);
+        });
+    }
+}
+
+impl Actor for RequestLimitManager {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        log::info!("RequestLimitManager started");
+        self.clear_heartbeat(ctx);
+    }
+}
+
+impl Inject for RequestLimitManager {
+    type Context = Context<Self>;
+
+    fn inject(
+        &mut self,
+        factory_data: FactoryData,
+        _factory: BeanFactory,
+        _ctx: &mut Self::Context,
+    ) {
+        self.bi_stream_manage = factory_data.get_actor();
+        self.notify_conn_limit();
+    }
+}
+
+impl Handler<RequestLimitReq> for RequestLimitManager {
+    type Result = anyhow::Result<RequestLimitResult>;
+
+    fn handle(&mut self, msg: RequestLimitReq, _ctx: &mut Self::Context) -> Self::Result {
+        match msg {
+            RequestLimitReq::Acquire {
+                client_ip,
+                request_type,
+            } => Ok(self.acquire(client_ip, request_type, now_millis_i64())),
+            RequestLimitReq::UpdateConfig(config) => {
+                self.update_config(config);
+                Ok(RequestLimitResult::None)
+            }
+            RequestLimitReq::QueryConfig => Ok(RequestLimitResult::Config(self.config.clone())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_type_qps() {
+        let map = RequestLimitConfig::parse_type_qps(
+            "ConfigQueryRequest:100, /nacos/v1/cs/configs:50,bad,:1",
+        );
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get("ConfigQueryRequest"), Some(&100));
+        assert_eq!(map.get("/nacos/v1/cs/configs"), Some(&50));
+    }
+
+    #[test]
+    fn acquire_limit() {
+        let mut request_type_qps = HashMap::new();
+        request_type_qps.insert("ConfigQueryRequest".to_owned(), 2);
+        let mut manager = RequestLimitManager::new(RequestLimitConfig {
+            grpc_conn_per_ip: 0,
+            ip_qps: 3,
+            request_type_qps,
+        });
+        let ip = Arc::new("127.0.0.1".to_owned());
+        let query = Arc::new("ConfigQueryRequest".to_owned());
+        let other = Arc::new("ServiceQueryRequest".to_owned());
+        let now = 1_000_000;
+        assert!(matches!(
+            manager.acquire(ip.clone(), query.clone(), now),
+            RequestLimitResult::Pass
+        ));
+        assert!(matches!(
+            manager.acquire(ip.clone(), query.clone(), now),
+            RequestLimitResult::Pass
+        ));
+        assert!(matches!(
+            manager.acquire(ip.clone(), query.clone(), now),
+            RequestLimitResult::Limited(LimitType::RequestType)
+        ));
+        assert!(matches!(
+            manager.acquire(ip.clone(), other.clone(), now),
+            RequestLimitResult::Limited(LimitType::Ip)
+        ));
+        assert!(matches!(
+            manager.acquire(ip, other, now + 1000),
+            RequestLimitResult::Pass
+        ));
+    }
+}
@@ -0,0 +1,104 @@
+use actix::prelude::*;
+use ratelimiter_rs::RateLimiter;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 请求限流配置在T_SYS_CONFIG表中的key
+pub const REQUEST_LIMIT_CONFIG_KEY: &str = "request_limit";
+
+///
+/// 客户端请求限流配置,值为0表示不限制
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestLimitConfig {
+    /// 单个客户端ip最大grpc长链接数
+    #[serde(default)]
+    pub grpc_conn_per_ip: u32,
+    /// 单个客户端ip每秒最大请求数
+    #[serde(default)]
+    pub ip_qps: u32,
+    /// 单个客户端ip按请求类型的每秒最大请求数;
+    /// grpc请求类型为请求类名(如ConfigQueryRequest),http请求类型为请求路径(如/nacos/v1/cs/configs)
+    #[serde(default)]
+    pub request_type_qps: HashMap<String, u32>,
+}
+
+impl RequestLimitConfig {
+    ///
+    /// 解析按请求类型限流配置,格式: ConfigQueryRequest:100,/nacos/v1/cs/configs:50
+    pub fn parse_type_qps(input: &str) -> HashMap<String, u32> {
+        let mut map = HashMap::new();
+        for item in input.split(',') {
+            if let Some((key, value)) = item.rsplit_once(':') {
+                let key = key.trim();
+                if key.is_empty() {
+                    continue;
+                }
+                if let Ok(v) = value.trim().parse::<u32>() {
+                    map.insert(key.to_owned(), v);
+                }
+            }
+        }
+        map
+    }
+
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn from_bytes(v: &[u8]) -> anyhow::Result<Self> {
+        Ok(serde_json::from_slice(v)?)
+    }
+
+    /// 是否需要对请求做限流检查
+    pub fn need_check_request(&self) -> bool {
+        self.ip_qps > 0 || self.request_type_qps.values().any(|v| *v > 0)
+    }
+}
+
+pub(crate) struct LimiterItem {
+    pub(crate) limiter: RateLimiter,
+    pub(crate) last_use_time: i64,
+}
+
+impl LimiterItem {
+    pub(crate) fn new(now: i64) -> Self {
+        Self {
+            limiter: RateLimiter::load(1000, 0, now),
+            last_use_time: now,
+        }
+    }
+
+    pub(crate) fn acquire(&mut self, qps: u32, now: i64) -> bool {
+        self.last_use_time = now;
+        self.limiter.acquire_by_time(qps as i32, qps as i64, now)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LimitType {
+    Ip,
+    RequestType,
+}
+
+#[derive(Message, Clone, Debug)]
+#[rtype(result = "anyhow::Result<RequestLimitResult>")]
+pub enum RequestLimitReq {
+    Acquire {
+        client_ip: Arc<String>,
+        request_type: Arc<String>,
+    },
+    /// 运行时调整限流配置,None表示恢复为启动参数配置
+    UpdateConfig(Option<RequestLimitConfig>),
+    QueryConfig,
+}
+
+#[derive(Clone, Debug)]
+pub enum RequestLimitResult {
+    None,
+    Pass,
+    Limited(LimitType),
+    Config(Arc<RequestLimitConfig>),
+}
@@ -0,0 +1,128 @@
+use crate::logger::writer::SharedWriter;
+use crate::logger::{format_time, LogFormat};
+use chrono::FixedOffset;
+use serde::Serialize;
+use std::io::Write;
+use std::sync::OnceLock;
+
+const TEXT_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f %:z";
+const JSON_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3f%:z";
+
+static ACCESS_LOGGER: OnceLock<AccessLogger> = OnceLock::new();
+
+///
+/// http、grpc请求访问记录
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessLogRecord<'a> {
+    /// http或grpc
+    pub protocol: &'a str,
+    pub client_ip: &'a str,
+    /// http请求方法,grpc请求为空
+    pub method: &'a str,
+    /// http请求路径或grpc请求类型
+    pub request_type: &'a str,
+    pub namespace: &'a str,
+    /// 请求耗时,单位毫秒
+    pub rt: f64,
+    /// http状态码或grpc处理结果码
+    pub code: u16,
+}
+
+struct AccessLogger {
+    format: LogFormat,
+    offset: FixedOffset,
+    writer: Option<SharedWriter>,
+}
+
+impl AccessLogger {
+    fn build_line(&self, record: &AccessLogRecord) -> String {
+        match self.format {
+            LogFormat::Text => format!(
+                "[{}] {}|{}|{}|{}|{}|{:.3}|{}\n",
+                format_time(&self.offset, TEXT_TIME_FORMAT),
+                record.protocol,
+                record.client_ip,
+                record.method,
+                record.request_type,
+                record.namespace,
+                record.rt,
+                record.code
+            ),
+            LogFormat::Json => {
+                let mut value = serde_json::to_value(record).unwrap_or_default();
+                if let Some(map) = value.as_object_mut() {
+                    map.insert(
+                        "time".to_owned(),
+                        format_time(&self.offset, JSON_TIME_FORMAT).into(),
+                    );
+                }
+                format!("{}\n", value)
+            }
+        }
+    }
+
+    fn write(&self, record: &AccessLogRecord) {
+        let line = self.build_line(record);
+        let r = match self.writer.clone() {
+            Some(mut writer) => writer.write_all(line.as_bytes()),
+            None => std::io::stdout().lock().write_all(line.as_bytes()),
+        };
+        if let Err(err) = r {
+            log::warn!("write access log error,{}", err);
+        }
+    }
+}
+
+pub(crate) fn init_access_logger(
+    format: LogFormat,
+    offset: FixedOffset,
+    writer: Option<SharedWriter>,
+) {
+    ACCESS_LOGGER.get_or_init(|| AccessLogger {
+        format,
+        offset,
+        writer,
+    });
+}
+
+pub fn is_enable() -> bool {
+    ACCESS_LOGGER.get().is_some()
+}
+
+///
+/// 写访问日志;未开启访问日志时直接忽略
+pub fn write_access_log(record: &AccessLogRecord) {
+    if let Some(logger) = ACCESS_LOGGER.get() {
+        logger.write(record);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_access_log_line() {
+        let logger = AccessLogger {
+            format: LogFormat::Json,
+            offset: FixedOffset::east_opt(8 * 3600).unwrap(),
+            writer: None,
+        };
+        let record = AccessLogRecord {
+            protocol: "grpc",
+            client_ip: "127.0.0.1",
+            request_type: "InstanceRequest",
+            namespace: "dev",
+            rt: 1.5,
+            code: 200,
+            ..Default::default()
+        };
+        let line = logger.build_line(&record);
+        let value: serde_json::Value = serde_json::from_str(line.trim()).unwrap();
+        assert_eq!(value["requestType"], "InstanceRequest");
+        assert_eq!(value["namespace"], "dev");
+        assert_eq!(value["code"], 200);
+        assert!(value["time"].as_str().unwrap().ends_with("+08:00"));
+    }
+}
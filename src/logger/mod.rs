@@ -0,0 +1,348 @@
+use crate::common::AppSysConfig;
+use crate::logger::writer::{RotateFileWriter, RotateOption, RotateTime, SharedWriter};
+use chrono::{FixedOffset, Utc};
+use env_logger::{Target, TimestampPrecision};
+use env_logger_timezone_fmt::{TimeZoneFormat, TimeZoneFormatEnv};
+use log::{LevelFilter, Log, Metadata, Record};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::sync::{Arc, OnceLock, RwLock};
+
+pub mod access;
+pub mod writer;
+
+pub const APP_LOG_FILE_NAME: &str = "rnacos.log";
+pub const ACCESS_LOG_FILE_NAME: &str = "access.log";
+const JSON_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.6f%:z";
+
+static LOGGER: OnceLock<RuntimeLogger> = OnceLock::new();
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl LogFormat {
+    pub fn new_by_value(v: &str) -> Self {
+        if v.eq_ignore_ascii_case("json") {
+            LogFormat::Json
+        } else {
+            LogFormat::Text
+        }
+    }
+}
+
+#[derive(Clone)]
+struct LoggerOption {
+    format: LogFormat,
+    timezone_fmt: Arc<TimeZoneFormatEnv>,
+    writer: Option<SharedWriter>,
+}
+
+///
+/// 支持运行时调整过滤规则的日志实现
+/// 过滤规则与RUST_LOG格式一致,调整时按新规则重建内部的env_logger
+struct RuntimeLogger {
+    option: LoggerOption,
+    default_filter: Arc<String>,
+    inner: RwLock<(Arc<String>, env_logger::Logger)>,
+}
+
+impl RuntimeLogger {
+    fn new(option: LoggerOption, filter: &str) -> Self {
+        let logger = build_env_logger(&option, filter);
+        Self {
+            option,
+            default_filter: Arc::new(filter.to_owned()),
+            inner: RwLock::new((Arc::new(filter.to_owned()), logger)),
+        }
+    }
+
+    fn current_filter(&self) -> Arc<String> {
+        match self.inner.read() {
+            Ok(inner) => inner.0.clone(),
+            Err(_) => self.default_filter.clone(),
+        }
+    }
+
+    fn set_filter(&self, filter: String) {
+        let logger = build_env_logger(&self.option, &filter);
+        let max_level = logger.filter();
+        if let Ok(mut inner) = self.inner.write() {
+            *inner = (Arc::new(filter), logger);
+            log::set_max_level(max_level);
+        }
+    }
+}
+
+impl Log for RuntimeLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        match self.inner.read() {
+            Ok(inner) => inner.1.enabled(metadata),
+            Err(_) => false,
+        }
+    }
+
+    fn log(&self, record: &Record) {
+        if let Ok(inner) = self.inner.read() {
+            inner.1.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(inner) = self.inner.read() {
+            inner.1.flush();
+        }
+    }
+}
+
+fn build_env_logger(option: &LoggerOption, filter: &str) -> env_logger::Logger {
+    let mut builder = env_logger::Builder::new();
+    builder.parse_filters(filter);
+    match option.format {
+        LogFormat::Text => {
+            let timezone_fmt = option.timezone_fmt.clone();
+            builder
+                .format(move |buf, record| TimeZoneFormat::new(buf, &timezone_fmt).write(record));
+        }
+        LogFormat::Json => {
+            let offset = option.timezone_fmt.offset;
+            builder.format(move |buf, record| {
+                let value = serde_json::json!({
+                    "time": format_time(&offset, JSON_TIME_FORMAT),
+                    "level": record.level().as_str(),
+                    "target": record.target(),
+                    "message": record.args().to_string(),
+                });
+                writeln!(buf, "{}", value)
+            });
+        }
+    }
+    if let Some(writer) = &option.writer {
+        builder.target(Target::Pipe(Box::new(writer.clone())));
+    }
+    builder.build()
+}
+
+pub(crate) fn format_time(offset: &FixedOffset, format: &str) -> String {
+    Utc::now().with_timezone(offset).format(format).to_string()
+}
+
+fn build_file_writer(
+    sys_config: &AppSysConfig,
+    offset: FixedOffset,
+    file_name: &str,
+) -> anyhow::Result<Option<SharedWriter>> {
+    if sys_config.log_dir.is_empty() {
+        return Ok(None);
+    }
+    let option = RotateOption {
+        max_size: sys_config.log_rotate_size_mb * 1024 * 1024,
+        rotate_time: RotateTime::new_by_value(&sys_config.log_rotate_time),
+        max_files: sys_config.log_max_files,
+        offset,
+    };
+    let writer = RotateFileWriter::new(&sys_config.log_dir, file_name, option)?;
+    Ok(Some(SharedWriter::new(writer)))
+}
+
+///
+/// 初始化应用日志与访问日志,filter为启动时的RUST_LOG
+pub fn init_logger(sys_config: &AppSysConfig, filter: &str) -> anyhow::Result<()> {
+    let timezone_fmt = Arc::new(TimeZoneFormatEnv::new(
+        sys_config.gmt_fixed_offset_hours.map(|v| v * 60 * 60),
+        Some(TimestampPrecision::Micros),
+    ));
+    let offset = timezone_fmt.offset;
+    let format = LogFormat::new_by_value(&sys_config.log_format);
+    let option = LoggerOption {
+        format,
+        timezone_fmt,
+        writer: build_file_writer(sys_config, offset, APP_LOG_FILE_NAME)?,
+    };
+    let logger = LOGGER.get_or_init(|| RuntimeLogger::new(option, filter));
+    log::set_logger(logger).map_err(|err| anyhow::anyhow!("init logger error,{}", err))?;
+    log::set_max_level(
+        logger
+            .inner
+            .read()
+            .map(|e| e.1.filter())
+            .unwrap_or(LevelFilter::Info),
+    );
+    if sys_config.access_log_enable {
+        let writer = build_file_writer(sys_config, offset, ACCESS_LOG_FILE_NAME)?;
+        access::init_access_logger(format, offset, writer);
+    }
+    Ok(())
+}
+
+///
+/// 单个模块的日志等级,module为空表示默认等级
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogDirective {
+    pub module: Option<String>,
+    pub level: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogLevelInfo {
+    /// 启动时的RUST_LOG
+    pub default_filter: Arc<String>,
+    pub current_filter: Arc<String>,
+    pub directives: Vec<LogDirective>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogLevelParam {
+    /// RUST_LOG格式的完整过滤规则,如info,rnacos::naming=debug
+    pub filter: Option<String>,
+    /// 单独调整等级的模块,为空表示默认等级
+    pub module: Option<String>,
+    /// 模块日志等级,为空表示移除该模块的单独设置
+    pub level: Option<String>,
+    /// 恢复为启动时的RUST_LOG
+    pub reset: Option<bool>,
+}
+
+fn parse_level(v: &str) -> anyhow::Result<LevelFilter> {
+    v.trim()
+        .parse::<LevelFilter>()
+        .map_err(|_| anyhow::anyhow!("log level is invalid: {}", v))
+}
+
+///
+/// 解析RUST_LOG格式的过滤规则,不支持`/`正则过滤
+pub fn parse_directives(filter: &str) -> anyhow::Result<Vec<(Option<String>, LevelFilter)>> {
+    if filter.contains('/') {
+        return Err(anyhow::anyhow!("log filter regex is not supported"));
+    }
+    let mut list: Vec<(Option<String>, LevelFilter)> = vec![];
+    for item in filter
+        .split(',')
+        .map(|e| e.trim())
+        .filter(|e| !e.is_empty())
+    {
+        let directive = match item.split_once('=') {
+            Some((module, level)) => (Some(module.trim().to_owned()), parse_level(level)?),
+            None => match item.parse::<LevelFilter>() {
+                Ok(level) => (None, level),
+                Err(_) => (Some(item.to_owned()), LevelFilter::Trace),
+            },
+        };
+        list.retain(|e| e.0 != directive.0);
+        list.push(directive);
+    }
+    Ok(list)
+}
+
+fn directives_to_filter(list: &[(Option<String>, LevelFilter)]) -> String {
+    list.iter()
+        .map(|(module, level)| match module {
+            Some(module) => format!("{}={}", module, level.as_str().to_ascii_lowercase()),
+            None => level.as_str().to_ascii_lowercase(),
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+///
+/// 模块名不含`::`时视为rnacos内部模块,如naming对应rnacos::naming
+pub fn get_module_path(module: &str) -> String {
+    if module.contains("::") || module == "rnacos" {
+        module.to_owned()
+    } else {
+        format!("rnacos::{}", module)
+    }
+}
+
+///
+/// 在原过滤规则上设置单个模块的日志等级;level为空表示移除该模块的单独设置
+pub fn merge_module_level(
+    filter: &str,
+    module: Option<&str>,
+    level: Option<&str>,
+) -> anyhow::Result<String> {
+    let mut list = parse_directives(filter)?;
+    let module = module.filter(|e| !e.is_empty()).map(get_module_path);
+    list.retain(|e| e.0 != module);
+    if let Some(level) = level.filter(|e| !e.is_empty()) {
+        list.push((module, parse_level(level)?));
+    }
+    Ok(directives_to_filter(&list))
+}
+
+pub fn get_log_level() -> anyhow::Result<LogLevelInfo> {
+    let logger = LOGGER
+        .get()
+        .ok_or_else(|| anyhow::anyhow!("logger is not initialized"))?;
+    let current_filter = logger.current_filter();
+    let directives = parse_directives(&current_filter)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(module, level)| LogDirective {
+            module,
+            level: level.as_str().to_ascii_lowercase(),
+        })
+        .collect();
+    Ok(LogLevelInfo {
+        default_filter: logger.default_filter.clone(),
+        current_filter,
+        directives,
+    })
+}
+
+///
+/// 运行时替换日志过滤规则,只对当前节点生效,重启后恢复为RUST_LOG
+pub fn set_log_filter(filter: &str) -> anyhow::Result<LogLevelInfo> {
+    let logger = LOGGER
+        .get()
+        .ok_or_else(|| anyhow::anyhow!("logger is not initialized"))?;
+    let filter = directives_to_filter(&parse_directives(filter)?);
+    if filter.is_empty() {
+        return Err(anyhow::anyhow!("log filter is empty"));
+    }
+    log::warn!("change log filter to {}", &filter);
+    logger.set_filter(filter);
+    get_log_level()
+}
+
+pub fn set_module_log_level(
+    module: Option<&str>,
+    level: Option<&str>,
+) -> anyhow::Result<LogLevelInfo> {
+    let current_filter = get_log_level()?.current_filter;
+    let filter = merge_module_level(&current_filter, module, level)?;
+    set_log_filter(&filter)
+}
+
+pub fn reset_log_filter() -> anyhow::Result<LogLevelInfo> {
+    let logger = LOGGER
+        .get()
+        .ok_or_else(|| anyhow::anyhow!("logger is not initialized"))?;
+    log::warn!("reset log filter to {}", &logger.default_filter);
+    logger.set_filter(logger.default_filter.as_ref().to_owned());
+    get_log_level()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_module_log_level() {
+        let filter =
+            merge_module_level("info,actix_web=warn", Some("naming"), Some("debug")).unwrap();
+        assert_eq!(filter, "info,actix_web=warn,rnacos::naming=debug");
+        let filter = merge_module_level(&filter, Some("naming"), None).unwrap();
+        assert_eq!(filter, "info,actix_web=warn");
+        let filter = merge_module_level(&filter, None, Some("error")).unwrap();
+        assert_eq!(filter, "actix_web=warn,error");
+        assert!(merge_module_level(&filter, Some("raft"), Some("verbose")).is_err());
+        assert!(parse_directives("info,rnacos/naming").is_err());
+    }
+}
@@ -0,0 +1,228 @@
+use chrono::{FixedOffset, Utc};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum RotateTime {
+    None,
+    Hour,
+    #[default]
+    Day,
+}
+
+impl RotateTime {
+    pub fn new_by_value(v: &str) -> Self {
+        match v.to_ascii_lowercase().as_str() {
+            "none" => RotateTime::None,
+            "hour" => RotateTime::Hour,
+            _ => RotateTime::Day,
+        }
+    }
+
+    fn period_format(&self) -> &'static str {
+        match self {
+            RotateTime::None => "",
+            RotateTime::Hour => "%Y%m%d%H",
+            RotateTime::Day => "%Y%m%d",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RotateOption {
+    /// 单个文件大小上限,0表示不按大小切割
+    pub max_size: u64,
+    pub rotate_time: RotateTime,
+    /// 保留的历史文件数
+    pub max_files: usize,
+    pub offset: FixedOffset,
+}
+
+///
+/// 按大小与时间切割的日志文件
+/// 切割时把当前文件重命名为`{file_name}.{时间}`,并删除超出保留数量的最早文件
+pub struct RotateFileWriter {
+    dir: PathBuf,
+    file_name: String,
+    option: RotateOption,
+    file: Option<File>,
+    size: u64,
+    period: String,
+}
+
+impl RotateFileWriter {
+    pub fn new(dir: &str, file_name: &str, option: RotateOption) -> anyhow::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let mut writer = Self {
+            dir: PathBuf::from(dir),
+            file_name: file_name.to_owned(),
+            option,
+            file: None,
+            size: 0,
+            period: String::new(),
+        };
+        writer.open()?;
+        Ok(writer)
+    }
+
+    fn path(&self) -> PathBuf {
+        self.dir.join(&self.file_name)
+    }
+
+    fn current_period(&self) -> String {
+        let format = self.option.rotate_time.period_format();
+        if format.is_empty() {
+            return String::new();
+        }
+        Utc::now()
+            .with_timezone(&self.option.offset)
+            .format(format)
+            .to_string()
+    }
+
+    fn open(&mut self) -> std::io::Result<()> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path())?;
+        self.size = file.metadata().map(|e| e.len()).unwrap_or_default();
+        self.period = self.current_period();
+        self.file = Some(file);
+        Ok(())
+    }
+
+    fn need_rotate(&self, len: usize) -> bool {
+        if self.size == 0 {
+            return false;
+        }
+        if self.option.max_size > 0 && self.size + len as u64 > self.option.max_size {
+            return true;
+        }
+        self.period != self.current_period()
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        if let Some(mut file) = self.file.take() {
+            file.flush()?;
+        }
+        let suffix = Utc::now()
+            .with_timezone(&self.option.offset)
+            .format("%Y%m%d%H%M%S%3f");
+        let target = self.dir.join(format!("{}.{}", &self.file_name, suffix));
+        std::fs::rename(self.path(), target)?;
+        self.remove_expired_files();
+        self.open()
+    }
+
+    fn remove_expired_files(&self) {
+        let prefix = format!("{}.", &self.file_name);
+        let mut names: Vec<String> = match std::fs::read_dir(&self.dir) {
+            Ok(dir) => dir
+                .filter_map(|e| e.ok())
+                .filter_map(|e| e.file_name().into_string().ok())
+                .filter(|e| e.starts_with(&prefix))
+                .collect(),
+            Err(_) => return,
+        };
+        if names.len() <= self.option.max_files {
+            return;
+        }
+        // 后缀为时间,按名称排序即为时间顺序
+        names.sort();
+        for name in names.iter().take(names.len() - self.option.max_files) {
+            std::fs::remove_file(self.dir.join(name)).ok();
+        }
+    }
+}
+
+impl Write for RotateFileWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.need_rotate(buf.len()) {
+            self.rotate()?;
+        }
+        if self.file.is_none() {
+            self.open()?;
+        }
+        let len = self.file.as_mut().unwrap().write(buf)?;
+        self.size += len as u64;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if let Some(file) = self.file.as_mut() {
+            file.flush()?;
+        }
+        Ok(())
+    }
+}
+
+///
+/// 多处共享的日志文件,env_logger每次重建时都需要一个新的输出对象
+#[derive(Clone)]
+pub struct SharedWriter(Arc<Mutex<RotateFileWriter>>);
+
+impl SharedWriter {
+    pub fn new(writer: RotateFileWriter) -> Self {
+        Self(Arc::new(Mutex::new(writer)))
+    }
+}
+
+impl Write for SharedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self.0.lock() {
+            Ok(mut writer) => writer.write(buf),
+            Err(_) => Ok(buf.len()),
+        }
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        // 保证一条日志在同一个文件中,切割只发生在两条日志之间
+        match self.0.lock() {
+            Ok(mut writer) => writer.write_all(buf),
+            Err(_) => Ok(()),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self.0.lock() {
+            Ok(mut writer) => writer.flush(),
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotate_by_size() {
+        let dir =
+            std::env::temp_dir().join(format!("rnacos_log_{}", uuid::Uuid::new_v4().simple()));
+        let dir_str = dir.to_string_lossy().to_string();
+        let option = RotateOption {
+            max_size: 64,
+            rotate_time: RotateTime::None,
+            max_files: 2,
+            offset: FixedOffset::east_opt(0).unwrap(),
+        };
+        let mut writer = RotateFileWriter::new(&dir_str, "test.log", option).unwrap();
+        let line = [b'a'; 40];
+        for _ in 0..5 {
+            writer.write_all(&line).unwrap();
+            // 历史文件名精确到毫秒,避免同名覆盖
+            std::thread::sleep(std::time::Duration::from_millis(2));
+        }
+        writer.flush().unwrap();
+        let names: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter_map(|e| e.file_name().into_string().ok())
+            .collect();
+        assert_eq!(names.len(), 3);
+        assert_eq!(std::fs::metadata(dir.join("test.log")).unwrap().len(), 40);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -34,12 +34,11 @@ use tonic::transport::Server;
 use crate::cli::{Cli, Commands};
 use actix_web::{middleware, HttpServer};
 use clap::Parser;
-use env_logger::TimestampPrecision;
-use env_logger_timezone_fmt::{TimeZoneFormat, TimeZoneFormatEnv};
 #[cfg(feature = "mimalloc")]
 use mimalloc::MiMalloc;
 use rnacos::common::appdata::AppShareData;
 use rnacos::openapi::middle::auth_middle::ApiCheckAuth;
+use rnacos::openapi::middle::trace_middle::OtelTrace;
 use rnacos::raft::NacosRaft;
 use rnacos::transfer::data_to_sqlite::data_to_sqlite;
 use rnacos::transfer::mysql_to_data::mysql_to_data;
@@ -57,13 +56,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let rust_log = std::env::var("RUST_LOG").unwrap_or("info".to_owned());
     std::env::set_var("RUST_LOG", &rust_log);
     let sys_config = Arc::new(AppSysConfig::init_from_env());
-    let timezone_fmt = Arc::new(TimeZoneFormatEnv::new(
-        sys_config.gmt_fixed_offset_hours.map(|v| v * 60 * 60),
-        Some(TimestampPrecision::Micros),
-    ));
-    env_logger::Builder::from_default_env()
-        .format(move |buf, record| TimeZoneFormat::new(buf, &timezone_fmt).write(record))
-        .init();
+    rnacos::logger::init_logger(&sys_config, &rust_log)?;
     if let Some(cmd) = cli_opt.command {
         return run_subcommand(cmd).await;
     }
@@ -78,6 +71,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
         "system default"
     };
     println!("allocator: {}", allocator_name);
+    rnacos::otel::init_tracer(&sys_config)?;
     let factory_data = config_factory(sys_config.clone()).await?;
     let app_data = build_share_data(factory_data.clone())?;
     let http_addr = sys_config.get_http_addr();
@@ -131,6 +125,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
             .app_data(Data::new(naming_addr))
             .app_data(Data::new(bistream_manage_http_addr))
             .wrap(ApiCheckAuth::new(source_app_data))
+            .wrap(OtelTrace)
             .wrap(middleware::Logger::default())
             .configure(app_config(app_config_shard))
     });
@@ -140,6 +135,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
     // 这里不使用log:info避免日志等级高于info时不打印
     println!("rnacos started");
     server.bind(http_addr)?.run().await?;
+    rnacos::otel::shutdown_tracer();
     Ok(())
 }
 
@@ -195,6 +191,7 @@ async fn run_console_web(source_app_data: Arc<AppShareData>) {
             .app_data(Data::new(naming_addr))
             .app_data(Data::new(bistream_manage_http_addr))
             .wrap(CheckLogin::new(source_app_data))
+            .wrap(OtelTrace)
             .wrap(middleware::Logger::default())
             .wrap(middleware::Compress::default())
             .configure(console_config)
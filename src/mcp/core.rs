@@ -10,6 +10,7 @@ use crate::mcp::model::mcp::{
 };
 use crate::mcp::model::tools::{ToolKey, ToolSpec, ToolSpecParam};
 use crate::mcp::utils::ToolSpecUtils;
+use crate::metrics::dump::{entry_bytes, str_bytes, ActorDump, DebugDumpQuery, StructureSize};
 use crate::raft::filestore::model::SnapshotRecordDto;
 use crate::raft::filestore::raftapply::{RaftApplyDataRequest, RaftApplyDataResponse};
 use crate::raft::filestore::raftsnapshot::{SnapshotWriterActor, SnapshotWriterRequest};
@@ -628,3 +629,59 @@ impl Handler<RaftApplyDataRequest> for McpManager {
         Ok(RaftApplyDataResponse::None)
     }
 }
+
+impl Handler<DebugDumpQuery> for McpManager {
+    type Result = anyhow::Result<ActorDump>;
+
+    fn handle(&mut self, msg: DebugDumpQuery, _ctx: &mut Self::Context) -> Self::Result {
+        // 结构嵌套较深,按json序列化后的长度估算
+        let server_bytes: usize = self
+            .server_map
+            .values()
+            .map(|e| {
+                entry_bytes::<u64, Arc<McpServer>>()
+                    + serde_json::to_vec(e.as_ref())
+                        .map(|v| v.len())
+                        .unwrap_or_default()
+            })
+            .sum();
+        let tool_spec_bytes: usize = self
+            .tool_spec_map
+            .values()
+            .map(|e| {
+                entry_bytes::<ToolKey, Arc<ToolSpec>>()
+                    + serde_json::to_vec(e.as_ref())
+                        .map(|v| v.len())
+                        .unwrap_or_default()
+            })
+            .sum();
+        let structures = vec![
+            StructureSize::new("mcp_server_map", self.server_map.len(), server_bytes),
+            StructureSize::new(
+                "mcp_tool_spec_map",
+                self.tool_spec_map.len(),
+                tool_spec_bytes,
+            ),
+            StructureSize::new(
+                "mcp_tool_spec_version_ref_map",
+                self.tool_spec_version_ref_map.len(),
+                self.tool_spec_version_ref_map
+                    .values()
+                    .map(|e| {
+                        entry_bytes::<ToolKey, HashMap<u64, i64>>()
+                            + e.len() * entry_bytes::<u64, i64>()
+                    })
+                    .sum(),
+            ),
+            StructureSize::new(
+                "mcp_server_key_map",
+                self.server_key_to_id_map.len(),
+                self.server_key_to_id_map
+                    .keys()
+                    .map(|e| entry_bytes::<Arc<String>, u64>() + str_bytes(e))
+                    .sum(),
+            ),
+        ];
+        Ok(ActorDump::new("McpManager", &msg, structures))
+    }
+}
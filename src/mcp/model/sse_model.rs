@@ -2,10 +2,19 @@ use actix::Message;
 use bytes::Bytes;
 use std::sync::Arc;
 
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SseConnType {
+    #[default]
+    Mcp,
+    /// 配置监听
+    ConfigWatch,
+}
+
 #[derive(Clone, Debug)]
 pub struct SseConnMetaInfo {
     pub session_id: Arc<String>,
     pub mcp_server_key: Arc<String>,
+    pub conn_type: SseConnType,
 }
 
 /// SSE 流管理器命令
@@ -1,5 +1,7 @@
+use crate::config::core::{ConfigActor, ConfigCmd};
 use crate::mcp::model::sse_model::{
-    SseConnMetaInfo, SseStreamManageAsyncCmd, SseStreamManageCmd, SseStreamManageResult,
+    SseConnMetaInfo, SseConnType, SseStreamManageAsyncCmd, SseStreamManageCmd,
+    SseStreamManageResult,
 };
 use crate::{now_millis, now_second_i32};
 use actix::prelude::*;
@@ -43,6 +45,8 @@ pub struct SseStreamManager {
 
     /// 心跳检测超时时间（默认15秒）
     detection_time_out: u64,
+
+    config_addr: Option<Addr<ConfigActor>>,
 }
 
 impl SseStreamManager {
@@ -124,7 +128,13 @@ impl SseStreamManager {
     /// 移除指定的 SSE 连接
     pub fn remove_conn(&mut self, session_id: Arc<String>) {
         log::info!("remove_sse_conn session_id:{}", &session_id);
-        self.conn_cache.remove(&session_id);
+        if let Some(item) = self.conn_cache.remove(&session_id) {
+            if item.meta.conn_type == SseConnType::ConfigWatch {
+                if let Some(config_addr) = &self.config_addr {
+                    config_addr.do_send(ConfigCmd::RemoveSseWatcher(session_id));
+                }
+            }
+        }
     }
 
     /// 定时心跳检测
@@ -151,10 +161,11 @@ impl Inject for SseStreamManager {
 
     fn inject(
         &mut self,
-        _factory_data: bean_factory::FactoryData,
+        factory_data: bean_factory::FactoryData,
         _factory: bean_factory::BeanFactory,
         _ctx: &mut Self::Context,
     ) {
+        self.config_addr = factory_data.get_actor();
         log::info!("SseStreamManage inject complete");
     }
 }
@@ -244,6 +255,12 @@ impl SseConnUtils {
         format!("event: message\ndata: {}\n\n", json_string)
     }
 
+    /// 创建指定事件名的 SSE 消息
+    pub fn create_sse_event_message<T: Serialize>(event: &str, data: &T) -> String {
+        let json_string = serde_json::to_string(data).unwrap_or_default();
+        format!("event: {}\ndata: {}\n\n", event, json_string)
+    }
+
     /// 创建 SSE 心跳消息
     pub fn create_heartbeat_message() -> String {
         format!(": ping - {}\n\n", now_millis())
@@ -4,18 +4,22 @@ use crate::grpc::bistream_manage::BiStreamManage;
 use crate::metrics::counter::CounterManager;
 use crate::metrics::gauge::GaugeManager;
 use crate::metrics::histogram::HistogramManager;
+use crate::metrics::labeled::LabeledMetricsManager;
 use crate::metrics::metrics_key::MetricsKey;
 use crate::metrics::model::{
-    MetricsItem, MetricsQuery, MetricsRecord, MetricsRequest, MetricsResponse,
+    LabeledMetricsItem, LabeledMetricsQuery, MetricsItem, MetricsQuery, MetricsRecord,
+    MetricsRequest, MetricsResponse,
 };
 use crate::metrics::summary::SummaryManager;
 use crate::metrics::timeline::core::MetricsTimelineManager;
 use crate::metrics::timeline::model::{MetricsSnapshot, TimelineGroupType};
 use crate::naming::core::NamingActor;
 use crate::now_millis;
+use crate::raft::metrics::RaftMetricsCollector;
 use actix::prelude::*;
 use bean_factory::{bean, BeanFactory, FactoryData, Inject};
 use bytes::BytesMut;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use sysinfo::{Pid, System};
@@ -27,10 +31,12 @@ pub struct MetricsManager {
     gauge_manager: GaugeManager,
     histogram_manager: HistogramManager,
     summary_manager: SummaryManager,
+    labeled_manager: LabeledMetricsManager,
     summary_key_config: Vec<(MetricsKey, MetricsKey)>,
     naming_actor: Option<Addr<NamingActor>>,
     config_actor: Option<Addr<ConfigActor>>,
     bi_stream_manage: Option<Addr<BiStreamManage>>,
+    raft_metrics_collector: Option<Arc<RaftMetricsCollector>>,
     metrics_timeline_manager: MetricsTimelineManager,
     system: System,
     current_process_id: u32,
@@ -55,10 +61,12 @@ impl MetricsManager {
             gauge_manager,
             histogram_manager: Default::default(),
             summary_manager: Default::default(),
+            labeled_manager: LabeledMetricsManager::new(app_sys_config.metrics_label_max_values),
             summary_key_config: Default::default(),
             naming_actor: None,
             config_actor: None,
             bi_stream_manage: None,
+            raft_metrics_collector: None,
             metrics_timeline_manager: MetricsTimelineManager::new(),
             system,
             current_process_id,
@@ -98,6 +106,19 @@ impl MetricsManager {
             &[0.5f32, 0.6f32, 0.7f32, 0.8f32, 0.9f32, 0.95f32, 1f32],
         );
 
+        self.labeled_manager.init_histogram(
+            MetricsKey::GrpcRequestTypeHandleRtHistogram,
+            &[
+                0.25f32, 0.5f32, 1f32, 3f32, 5f32, 10f32, 25f32, 50f32, 100f32, 300f32, 500f32,
+            ],
+        );
+        self.labeled_manager.init_histogram(
+            MetricsKey::HttpRequestRouteHandleRtHistogram,
+            &[
+                0.25f32, 0.5f32, 1f32, 3f32, 5f32, 10f32, 25f32, 50f32, 100f32, 300f32, 500f32,
+            ],
+        );
+
         //summary from histogram
         self.summary_key_config.push((
             MetricsKey::HttpRequestHandleRtSummary,
@@ -122,28 +143,43 @@ impl MetricsManager {
         naming_actor: Option<Addr<NamingActor>>,
         config_actor: Option<Addr<ConfigActor>>,
         bi_stream_manage: Option<Addr<BiStreamManage>>,
-    ) -> anyhow::Result<Vec<MetricsItem>> {
+        raft_metrics_collector: Option<Arc<RaftMetricsCollector>>,
+    ) -> anyhow::Result<(Vec<MetricsItem>, Vec<LabeledMetricsItem>)> {
         let mut list = vec![];
+        let mut labeled_list = vec![];
         if let Some(naming_actor) = naming_actor {
             let mut t = naming_actor.send(MetricsQuery).await??;
             list.append(&mut t);
+            let mut t = naming_actor.send(LabeledMetricsQuery).await??;
+            labeled_list.append(&mut t);
         }
         if let Some(config_actor) = config_actor {
             let mut t = config_actor.send(MetricsQuery).await??;
             list.append(&mut t);
+            let mut t = config_actor.send(LabeledMetricsQuery).await??;
+            labeled_list.append(&mut t);
         }
         if let Some(bi_stream_manage) = bi_stream_manage {
             let mut t = bi_stream_manage.send(MetricsQuery).await??;
             list.append(&mut t);
         }
-        Ok(list)
+        if let Some(raft_metrics_collector) = raft_metrics_collector {
+            let (mut t, mut labeled_t) = raft_metrics_collector.collect().await?;
+            list.append(&mut t);
+            labeled_list.append(&mut labeled_t);
+        }
+        Ok((list, labeled_list))
     }
 
-    fn update_peek_metrics(&mut self, r: anyhow::Result<Vec<MetricsItem>>) {
-        if let Ok(list) = r {
+    fn update_peek_metrics(
+        &mut self,
+        r: anyhow::Result<(Vec<MetricsItem>, Vec<LabeledMetricsItem>)>,
+    ) {
+        if let Ok((list, labeled_list)) = r {
             for item in list {
                 self.update_item_record(item);
             }
+            self.labeled_manager.reset_gauges(labeled_list);
         }
     }
 
@@ -236,15 +272,24 @@ impl MetricsManager {
         let naming_actor = self.naming_actor.clone();
         let config_actor = self.config_actor.clone();
         let bi_stream_manage = self.bi_stream_manage.clone();
-        async move { Self::do_peek_metrics(naming_actor, config_actor, bi_stream_manage).await }
-            .into_actor(self)
-            .map(|r, act, ctx| {
-                //Self::log_metrics(&r);
-                act.update_peek_metrics(r);
-                act.after_peek_metrics();
-                act.hb(ctx);
-            })
-            .spawn(ctx);
+        let raft_metrics_collector = self.raft_metrics_collector.clone();
+        async move {
+            Self::do_peek_metrics(
+                naming_actor,
+                config_actor,
+                bi_stream_manage,
+                raft_metrics_collector,
+            )
+            .await
+        }
+        .into_actor(self)
+        .map(|r, act, ctx| {
+            //Self::log_metrics(&r);
+            act.update_peek_metrics(r);
+            act.after_peek_metrics();
+            act.hb(ctx);
+        })
+        .spawn(ctx);
     }
 
     fn build_snapshot(&self, now_ms: u64) -> MetricsSnapshot {
@@ -284,6 +329,7 @@ impl MetricsManager {
         self.histogram_manager.export(&mut bytes_mut)?;
         self.reset_summary();
         self.summary_manager.export(&mut bytes_mut)?;
+        self.labeled_manager.export(&mut bytes_mut)?;
         Ok(String::from_utf8(bytes_mut.to_vec())?)
     }
 }
@@ -307,8 +353,24 @@ impl Inject for MetricsManager {
         self.naming_actor = factory_data.get_actor();
         self.config_actor = factory_data.get_actor();
         self.bi_stream_manage = factory_data.get_actor();
+        self.raft_metrics_collector = factory_data.get_bean();
         self.metrics_timeline_manager
             .set_least_interval(self.app_sys_config.metrics_collect_interval_second);
+        self.metrics_timeline_manager
+            .set_hour_limit(self.app_sys_config.metrics_timeline_retention_hour);
+        if self.app_sys_config.metrics_enable && self.app_sys_config.metrics_timeline_persist_enable
+        {
+            let dir = std::path::Path::new(&self.app_sys_config.local_db_dir)
+                .join("metrics_timeline")
+                .to_string_lossy()
+                .to_string();
+            if let Err(err) = self
+                .metrics_timeline_manager
+                .init_persist(&dir, now_millis())
+            {
+                log::warn!("init metrics timeline persist error,{}", err);
+            }
+        }
         if self.app_sys_config.metrics_enable {
             log::info!(
                 "metrics enable! log_interval: {}s",
@@ -339,6 +401,12 @@ impl Handler<MetricsRequest> for MetricsManager {
                 }
                 Ok(MetricsResponse::None)
             }
+            MetricsRequest::BatchLabeledRecord(items) => {
+                for item in items {
+                    self.labeled_manager.record(item);
+                }
+                Ok(MetricsResponse::None)
+            }
             MetricsRequest::Export => {
                 let v = self.export()?;
                 Ok(MetricsResponse::ExportInfo(v))
@@ -347,6 +415,19 @@ impl Handler<MetricsRequest> for MetricsManager {
                 let response = self.metrics_timeline_manager.query(param);
                 Ok(MetricsResponse::TimelineResponse(response))
             }
+            MetricsRequest::QueryValues => {
+                let mut values = HashMap::new();
+                for (key, value) in &self.counter_manager.data_map {
+                    values.insert(key.to_owned(), value.0 as f32);
+                }
+                for (key, value) in &self.gauge_manager.data_map {
+                    values.insert(key.to_owned(), value.0);
+                }
+                Ok(MetricsResponse::Values {
+                    values,
+                    labeled_gauges: self.labeled_manager.gauge_values(),
+                })
+            }
         }
     }
 }
@@ -0,0 +1,142 @@
+use crate::now_millis;
+use actix::prelude::*;
+use serde::Serialize;
+use std::mem::size_of;
+
+/// Arc<String>的引用计数与String头部开销
+const ARC_STRING_OVERHEAD: usize = 16 + size_of::<String>();
+/// map中单个条目的哈希与指针开销
+const MAP_ENTRY_OVERHEAD: usize = 16;
+
+///
+/// 字符串按内容长度加头部开销估算;共享的Arc会被重复计算,结果偏大
+pub fn str_bytes(v: &str) -> usize {
+    v.len() + ARC_STRING_OVERHEAD
+}
+
+pub fn entry_bytes<K, V>() -> usize {
+    size_of::<K>() + size_of::<V>() + MAP_ENTRY_OVERHEAD
+}
+
+///
+/// 查询actor内存结构大小;send_time用于计算消息在邮箱中的等待时间
+#[derive(Message, Clone, Copy, Debug)]
+#[rtype(result = "anyhow::Result<ActorDump>")]
+pub struct DebugDumpQuery {
+    pub top_n: usize,
+    pub send_time: u64,
+}
+
+impl DebugDumpQuery {
+    pub fn new(top_n: usize) -> Self {
+        Self {
+            top_n,
+            send_time: now_millis(),
+        }
+    }
+
+    pub fn queue_wait_ms(&self) -> u64 {
+        now_millis().saturating_sub(self.send_time)
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructureSize {
+    pub name: &'static str,
+    pub count: usize,
+    /// 估算的内存字节数
+    pub approx_bytes: usize,
+}
+
+impl StructureSize {
+    pub fn new(name: &'static str, count: usize, approx_bytes: usize) -> Self {
+        Self {
+            name,
+            count,
+            approx_bytes,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopItem {
+    pub key: String,
+    pub value: usize,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActorDump {
+    pub actor: &'static str,
+    /// 消息从发送到开始处理的等待时间,近似反映邮箱积压
+    pub queue_wait_ms: u64,
+    pub structures: Vec<StructureSize>,
+    pub approx_bytes: usize,
+    #[serde(skip)]
+    pub top_items: Vec<TopItem>,
+}
+
+impl ActorDump {
+    pub fn new(actor: &'static str, msg: &DebugDumpQuery, structures: Vec<StructureSize>) -> Self {
+        let approx_bytes = structures.iter().map(|e| e.approx_bytes).sum();
+        Self {
+            actor,
+            queue_wait_ms: msg.queue_wait_ms(),
+            structures,
+            approx_bytes,
+            top_items: vec![],
+        }
+    }
+
+    pub fn with_top_items(mut self, top_items: Vec<TopItem>) -> Self {
+        self.top_items = top_items;
+        self
+    }
+}
+
+///
+/// 按value倒序取前top_n项
+pub fn top_n_items<I>(items: I, top_n: usize) -> Vec<TopItem>
+where
+    I: Iterator<Item = (usize, String)>,
+{
+    let mut list: Vec<(usize, String)> = items.collect();
+    list.sort_unstable_by_key(|e| std::cmp::Reverse(e.0));
+    list.truncate(top_n);
+    list.into_iter()
+        .map(|(value, key)| TopItem { key, value })
+        .collect()
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugDumpResult {
+    pub node_id: u64,
+    pub approx_bytes: usize,
+    pub actors: Vec<ActorDump>,
+    /// 内容最大的配置,value为配置内容加历史记录的字节数
+    pub top_configs: Vec<TopItem>,
+    /// 实例数最多的服务
+    pub top_services: Vec<TopItem>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_top_n_items() {
+        let items = vec![
+            (3usize, "a".to_owned()),
+            (10, "b".to_owned()),
+            (1, "c".to_owned()),
+            (7, "d".to_owned()),
+        ];
+        let list = top_n_items(items.into_iter(), 2);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list[0].key, "b");
+        assert_eq!(list[1].value, 7);
+    }
+}
@@ -0,0 +1,319 @@
+use crate::metrics::metrics_key::{Label, MetricsKey};
+use crate::metrics::model::{
+    CounterValue, GaugeValue, HistogramValue, LabeledMetricsItem, MetricsRecord, MetricsType,
+};
+use bytes::BytesMut;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// 超过标签取值数量上限后,新的标签值统一归到该值
+pub const OTHER_LABEL_VALUE: &str = "other";
+
+///
+/// 指标标签集合,按标签名排序后作为序列的唯一标识
+#[derive(Debug, Eq, PartialEq, Clone, Hash, Default)]
+pub struct MetricsLabels(pub Vec<Label>);
+
+impl MetricsLabels {
+    pub fn new(mut labels: Vec<Label>) -> Self {
+        labels.sort_by(|a, b| a.0.cmp(&b.0));
+        Self(labels)
+    }
+
+    pub fn with(mut self, key: &'static str, value: impl Into<Cow<'static, str>>) -> Self {
+        self.0.push(Label(Cow::Borrowed(key), value.into()));
+        self.0.sort_by(|a, b| a.0.cmp(&b.0));
+        self
+    }
+
+    pub fn get_value(&self, key: &str) -> Option<&str> {
+        self.0.iter().find(|e| e.0 == key).map(|e| e.1.as_ref())
+    }
+
+    ///
+    /// 输出prometheus标签格式:{k="v",k2="v2"},extra为histogram的le等附加标签
+    fn fmt_labels(&self, extra: Option<(&str, &str)>) -> String {
+        let mut items: Vec<String> = self
+            .0
+            .iter()
+            .map(|e| format!("{}=\"{}\"", e.0, escape_label_value(&e.1)))
+            .collect();
+        if let Some((k, v)) = extra {
+            items.push(format!("{}=\"{}\"", k, v));
+        }
+        if items.is_empty() {
+            String::new()
+        } else {
+            format!("{{{}}}", items.join(","))
+        }
+    }
+}
+
+fn escape_label_value(v: &str) -> Cow<'_, str> {
+    if v.contains(['\\', '"', '\n']) {
+        Cow::Owned(
+            v.replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n"),
+        )
+    } else {
+        Cow::Borrowed(v)
+    }
+}
+
+///
+/// 带标签的指标管理器
+/// 每个指标的每个标签名最多保留max_label_values个取值,避免标签基数无限增长
+#[derive(Debug)]
+pub struct LabeledMetricsManager {
+    counter_map: HashMap<MetricsKey, HashMap<MetricsLabels, CounterValue>>,
+    gauge_map: HashMap<MetricsKey, HashMap<MetricsLabels, GaugeValue>>,
+    histogram_map: HashMap<MetricsKey, HashMap<MetricsLabels, HistogramValue>>,
+    histogram_bounds: HashMap<MetricsKey, Vec<f32>>,
+    label_values: HashMap<(MetricsKey, Cow<'static, str>), HashSet<Cow<'static, str>>>,
+    max_label_values: usize,
+}
+
+impl LabeledMetricsManager {
+    pub fn new(max_label_values: usize) -> Self {
+        Self {
+            counter_map: Default::default(),
+            gauge_map: Default::default(),
+            histogram_map: Default::default(),
+            histogram_bounds: Default::default(),
+            label_values: Default::default(),
+            max_label_values,
+        }
+    }
+
+    pub fn init_histogram(&mut self, key: MetricsKey, bounds: &[f32]) {
+        self.histogram_bounds.insert(key, bounds.to_vec());
+    }
+
+    fn guard_labels(&mut self, key: &MetricsKey, labels: MetricsLabels) -> MetricsLabels {
+        let mut items = Vec::with_capacity(labels.0.len());
+        for Label(name, value) in labels.0 {
+            let values = self
+                .label_values
+                .entry((key.clone(), name.clone()))
+                .or_default();
+            if values.contains(&value) || values.len() < self.max_label_values {
+                values.insert(value.clone());
+                items.push(Label(name, value));
+            } else {
+                items.push(Label(name, Cow::Borrowed(OTHER_LABEL_VALUE)));
+            }
+        }
+        MetricsLabels(items)
+    }
+
+    pub fn record(&mut self, item: LabeledMetricsItem) {
+        let labels = self.guard_labels(&item.metrics_type, item.labels);
+        match item.record {
+            MetricsRecord::CounterInc(v) => {
+                self.counter_map
+                    .entry(item.metrics_type)
+                    .or_default()
+                    .entry(labels)
+                    .or_default()
+                    .increment(v);
+            }
+            MetricsRecord::Gauge(v) => {
+                self.gauge_map
+                    .entry(item.metrics_type)
+                    .or_default()
+                    .entry(labels)
+                    .or_default()
+                    .set(v);
+            }
+            MetricsRecord::HistogramRecord(v) => {
+                if let Some(value) = self.get_histogram_mut(item.metrics_type, labels) {
+                    value.record(v);
+                }
+            }
+            MetricsRecord::HistogramRecords(batch_value) => {
+                if let Some(value) = self.get_histogram_mut(item.metrics_type, labels) {
+                    value.record_many(&batch_value);
+                }
+            }
+        }
+    }
+
+    fn get_histogram_mut(
+        &mut self,
+        key: MetricsKey,
+        labels: MetricsLabels,
+    ) -> Option<&mut HistogramValue> {
+        let bounds = self.histogram_bounds.get(&key)?;
+        let map = self.histogram_map.entry(key).or_default();
+        if !map.contains_key(&labels) {
+            map.insert(labels.clone(), HistogramValue::new(bounds)?);
+        }
+        map.get_mut(&labels)
+    }
+
+    ///
+    /// 带标签的gauge指标都由定时采集产生,按采集结果整体替换
+    /// 已不存在的序列(如删除的命名空间、切换为follower后的复制延迟)随之清除
+    pub fn reset_gauges(&mut self, items: Vec<LabeledMetricsItem>) {
+        self.gauge_map.clear();
+        for item in items {
+            self.record(item);
+        }
+    }
+
+    pub fn gauge_values(&self) -> Vec<(MetricsKey, MetricsLabels, f32)> {
+        let mut list = vec![];
+        for (key, map) in &self.gauge_map {
+            for (labels, value) in map {
+                list.push((key.to_owned(), labels.to_owned(), value.0));
+            }
+        }
+        list
+    }
+
+    pub fn export(&self, bytes_mut: &mut BytesMut) -> anyhow::Result<()> {
+        for (key, map) in &self.counter_map {
+            write_header(bytes_mut, key, MetricsType::Counter)?;
+            for (labels, value) in map {
+                writeln!(
+                    bytes_mut,
+                    "{}{} {}",
+                    key.get_key(),
+                    labels.fmt_labels(None),
+                    value.0
+                )?;
+            }
+        }
+        for (key, map) in &self.gauge_map {
+            write_header(bytes_mut, key, MetricsType::Gauge)?;
+            for (labels, value) in map {
+                writeln!(
+                    bytes_mut,
+                    "{}{} {:.3}",
+                    key.get_key(),
+                    labels.fmt_labels(None),
+                    value.0
+                )?;
+            }
+        }
+        for (key, map) in &self.histogram_map {
+            write_header(bytes_mut, key, MetricsType::Histogram)?;
+            let key_name = key.get_key();
+            for (labels, value) in map {
+                for (bound, count) in value.buckets() {
+                    let le = bound.to_string();
+                    writeln!(
+                        bytes_mut,
+                        "{}_bucket{} {}",
+                        key_name,
+                        labels.fmt_labels(Some(("le", &le))),
+                        count
+                    )?;
+                }
+                writeln!(
+                    bytes_mut,
+                    "{}_bucket{} {}",
+                    key_name,
+                    labels.fmt_labels(Some(("le", "+Inf"))),
+                    value.count
+                )?;
+                writeln!(
+                    bytes_mut,
+                    "{}_sum{} {:.3}",
+                    key_name,
+                    labels.fmt_labels(None),
+                    value.sum
+                )?;
+                writeln!(
+                    bytes_mut,
+                    "{}_count{} {}",
+                    key_name,
+                    labels.fmt_labels(None),
+                    value.count
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn write_header(
+    bytes_mut: &mut BytesMut,
+    key: &MetricsKey,
+    metrics_type: MetricsType,
+) -> anyhow::Result<()> {
+    writeln!(
+        bytes_mut,
+        "# HELP {} {}\n# TYPE {} {}",
+        key.get_key(),
+        key.get_describe(),
+        key.get_key(),
+        metrics_type.get_name()
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request_labels(request_type: &str) -> MetricsLabels {
+        MetricsLabels::default()
+            .with("request_type", request_type.to_owned())
+            .with("code", "200")
+    }
+
+    #[test]
+    fn labeled_metrics_cardinality_guard() {
+        let mut manager = LabeledMetricsManager::new(2);
+        manager.init_histogram(MetricsKey::GrpcRequestTypeHandleRtHistogram, &[1f32, 5f32]);
+        for request_type in ["A", "B", "C", "D", "A"] {
+            manager.record(LabeledMetricsItem::new(
+                MetricsKey::GrpcRequestTypeTotalCount,
+                request_labels(request_type),
+                MetricsRecord::CounterInc(1),
+            ));
+            manager.record(LabeledMetricsItem::new(
+                MetricsKey::GrpcRequestTypeHandleRtHistogram,
+                request_labels(request_type),
+                MetricsRecord::HistogramRecord(2f32),
+            ));
+        }
+        let map = manager
+            .counter_map
+            .get(&MetricsKey::GrpcRequestTypeTotalCount)
+            .unwrap();
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get(&request_labels("A")).unwrap().0, 2);
+        assert_eq!(map.get(&request_labels(OTHER_LABEL_VALUE)).unwrap().0, 2);
+        let mut bytes_mut = BytesMut::new();
+        manager.export(&mut bytes_mut).unwrap();
+        let text = String::from_utf8(bytes_mut.to_vec()).unwrap();
+        assert!(text.contains("grpc_request_type_total_count{code=\"200\",request_type=\"A\"} 2"));
+        assert!(text.contains(
+            "grpc_request_type_handle_rt_histogram_bucket{code=\"200\",request_type=\"B\",le=\"5\"} 1"
+        ));
+    }
+
+    #[test]
+    fn reset_namespace_gauges() {
+        let mut manager = LabeledMetricsManager::new(10);
+        let item = |namespace: &str, v: f32| {
+            LabeledMetricsItem::new(
+                MetricsKey::ConfigNamespaceDataSize,
+                MetricsLabels::default().with("namespace", namespace.to_owned()),
+                MetricsRecord::Gauge(v),
+            )
+        };
+        manager.reset_gauges(vec![item("public", 1f32), item("dev", 2f32)]);
+        manager.reset_gauges(vec![item("public", 3f32)]);
+        let map = manager
+            .gauge_map
+            .get(&MetricsKey::ConfigNamespaceDataSize)
+            .unwrap();
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.values().next().unwrap().0, 3f32);
+    }
+}
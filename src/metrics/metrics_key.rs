@@ -57,6 +57,30 @@ pub enum MetricsKey {
     HttpRequestHandleRtHistogram,
     HttpRequestHandleRtSummary,
     HttpRequestTotalCount,
+    //request limit
+    GrpcConnLimitedCount,
+    GrpcRequestLimitedCount,
+    HttpRequestLimitedCount,
+    //labeled
+    GrpcRequestTypeHandleRtHistogram,
+    GrpcRequestTypeTotalCount,
+    HttpRequestRouteHandleRtHistogram,
+    HttpRequestRouteTotalCount,
+    ConfigNamespaceDataSize,
+    NamingNamespaceServiceSize,
+    NamingNamespaceInstanceSize,
+    RaftReplicationLag,
+    //raft
+    RaftCurrentTerm,
+    RaftLeaderId,
+    RaftLastLogIndex,
+    RaftLastAppliedIndex,
+    RaftApplyQueueDepth,
+    RaftSnapshotCount,
+    RaftSnapshotBytes,
+    RaftLastSnapshotDuration,
+    RaftLogSegmentCount,
+    RaftLogBytes,
 }
 
 lazy_static! {
@@ -110,6 +134,21 @@ lazy_static! {
         MetricsKey::HttpRequestHandleRtHistogram,
         MetricsKey::HttpRequestHandleRtSummary,
         MetricsKey::HttpRequestTotalCount,
+        //request limit
+        MetricsKey::GrpcConnLimitedCount,
+        MetricsKey::GrpcRequestLimitedCount,
+        MetricsKey::HttpRequestLimitedCount,
+        //raft
+        MetricsKey::RaftCurrentTerm,
+        MetricsKey::RaftLeaderId,
+        MetricsKey::RaftLastLogIndex,
+        MetricsKey::RaftLastAppliedIndex,
+        MetricsKey::RaftApplyQueueDepth,
+        MetricsKey::RaftSnapshotCount,
+        MetricsKey::RaftSnapshotBytes,
+        MetricsKey::RaftLastSnapshotDuration,
+        MetricsKey::RaftLogSegmentCount,
+        MetricsKey::RaftLogBytes,
     ];
 
     pub static ref HISTOGRAM_SUMMARY_MAP: HashMap<MetricsKey,MetricsKey> = MetricsKey::build_histogram_summary_map();
@@ -173,6 +212,29 @@ impl MetricsKey {
             MetricsKey::HttpRequestHandleRtHistogram => "http_request_handle_rt_histogram",
             MetricsKey::HttpRequestHandleRtSummary => "http_request_handle_rt_summary",
             MetricsKey::HttpRequestTotalCount => "http_request_total_count",
+            MetricsKey::GrpcConnLimitedCount => "grpc_conn_limited_count",
+            MetricsKey::GrpcRequestLimitedCount => "grpc_request_limited_count",
+            MetricsKey::HttpRequestLimitedCount => "http_request_limited_count",
+            MetricsKey::GrpcRequestTypeHandleRtHistogram => "grpc_request_type_handle_rt_histogram",
+            MetricsKey::GrpcRequestTypeTotalCount => "grpc_request_type_total_count",
+            MetricsKey::HttpRequestRouteHandleRtHistogram => {
+                "http_request_route_handle_rt_histogram"
+            }
+            MetricsKey::HttpRequestRouteTotalCount => "http_request_route_total_count",
+            MetricsKey::ConfigNamespaceDataSize => "config_namespace_data_size",
+            MetricsKey::NamingNamespaceServiceSize => "naming_namespace_service_size",
+            MetricsKey::NamingNamespaceInstanceSize => "naming_namespace_instance_size",
+            MetricsKey::RaftReplicationLag => "raft_replication_lag",
+            MetricsKey::RaftCurrentTerm => "raft_current_term",
+            MetricsKey::RaftLeaderId => "raft_leader_id",
+            MetricsKey::RaftLastLogIndex => "raft_last_log_index",
+            MetricsKey::RaftLastAppliedIndex => "raft_last_applied_index",
+            MetricsKey::RaftApplyQueueDepth => "raft_apply_queue_depth",
+            MetricsKey::RaftSnapshotCount => "raft_snapshot_count",
+            MetricsKey::RaftSnapshotBytes => "raft_snapshot_bytes",
+            MetricsKey::RaftLastSnapshotDuration => "raft_last_snapshot_duration",
+            MetricsKey::RaftLogSegmentCount => "raft_log_segment_count",
+            MetricsKey::RaftLogBytes => "raft_log_bytes",
         }
     }
 
@@ -251,6 +313,37 @@ impl MetricsKey {
             }
             MetricsKey::HttpRequestHandleRtSummary => "Http request handle rt summary,unit is ms",
             MetricsKey::HttpRequestTotalCount => "Http request total count",
+            MetricsKey::GrpcConnLimitedCount => "Grpc conn rejected by client ip limit count",
+            MetricsKey::GrpcRequestLimitedCount => "Grpc request rejected by qps limit count",
+            MetricsKey::HttpRequestLimitedCount => "Http request rejected by qps limit count",
+            MetricsKey::GrpcRequestTypeHandleRtHistogram => {
+                "Grpc request handle rt histogram by request type and code,unit is ms"
+            }
+            MetricsKey::GrpcRequestTypeTotalCount => {
+                "Grpc request total count by request type and code"
+            }
+            MetricsKey::HttpRequestRouteHandleRtHistogram => {
+                "Http request handle rt histogram by route and status,unit is ms"
+            }
+            MetricsKey::HttpRequestRouteTotalCount => {
+                "Http request total count by route and status"
+            }
+            MetricsKey::ConfigNamespaceDataSize => "Config data size by namespace",
+            MetricsKey::NamingNamespaceServiceSize => "Naming service size by namespace",
+            MetricsKey::NamingNamespaceInstanceSize => "Naming instance size by namespace",
+            MetricsKey::RaftReplicationLag => {
+                "Raft log index lag of follower behind the leader,only reported by leader"
+            }
+            MetricsKey::RaftCurrentTerm => "Raft current term",
+            MetricsKey::RaftLeaderId => "Raft current leader node id,0 is unknown",
+            MetricsKey::RaftLastLogIndex => "Raft last log index",
+            MetricsKey::RaftLastAppliedIndex => "Raft last applied log index",
+            MetricsKey::RaftApplyQueueDepth => "Raft log size waiting to apply to state machine",
+            MetricsKey::RaftSnapshotCount => "Raft snapshot file count",
+            MetricsKey::RaftSnapshotBytes => "Raft snapshot file bytes",
+            MetricsKey::RaftLastSnapshotDuration => "Raft last snapshot build duration,unit is ms",
+            MetricsKey::RaftLogSegmentCount => "Raft log segment file count",
+            MetricsKey::RaftLogBytes => "Raft log segment file bytes",
             //default describe
             //_ => "Some help info",
         }
@@ -1,7 +1,9 @@
 pub mod core;
 pub mod counter;
+pub mod dump;
 pub mod gauge;
 pub mod histogram;
+pub mod labeled;
 pub mod metrics_key;
 pub mod model;
 pub mod summary;
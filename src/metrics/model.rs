@@ -1,7 +1,9 @@
+use crate::metrics::labeled::MetricsLabels;
 use crate::metrics::metrics_key::MetricsKey;
 use crate::metrics::timeline::model::{TimelineQueryParam, TimelineQueryResponse};
 use actix::prelude::*;
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 
 pub enum MetricsType {
@@ -430,12 +432,21 @@ impl Display for SummaryValueFmtWrap<'_> {
 #[rtype(result = "anyhow::Result<Vec<MetricsItem>>")]
 pub struct MetricsQuery;
 
+///
+/// 查询带标签的指标,如按命名空间统计的数量
+#[derive(Message)]
+#[rtype(result = "anyhow::Result<Vec<LabeledMetricsItem>>")]
+pub struct LabeledMetricsQuery;
+
 #[derive(Message, Clone, Debug)]
 #[rtype(result = "anyhow::Result<MetricsResponse>")]
 pub enum MetricsRequest {
     Record(MetricsItem),
     BatchRecord(Vec<MetricsItem>),
+    BatchLabeledRecord(Vec<LabeledMetricsItem>),
     TimelineQuery(TimelineQueryParam),
+    /// 查询当前gauge与counter指标值,供告警规则检查
+    QueryValues,
     Export,
 }
 
@@ -444,6 +455,10 @@ pub enum MetricsResponse {
     None,
     ExportInfo(String),
     TimelineResponse(TimelineQueryResponse),
+    Values {
+        values: HashMap<MetricsKey, f32>,
+        labeled_gauges: Vec<(MetricsKey, MetricsLabels, f32)>,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -469,6 +484,23 @@ impl MetricsItem {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct LabeledMetricsItem {
+    pub metrics_type: MetricsKey,
+    pub labels: MetricsLabels,
+    pub record: MetricsRecord,
+}
+
+impl LabeledMetricsItem {
+    pub fn new(metrics_type: MetricsKey, labels: MetricsLabels, record: MetricsRecord) -> Self {
+        Self {
+            metrics_type,
+            labels,
+            record,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::metrics::model::{HistogramValue, SummaryValue};
@@ -3,7 +3,8 @@ use crate::metrics::timeline::model::{
     MetricsSnapshot, SummaryWrapValue, TimelineGroupType, TimelineQueryParam,
     TimelineQueryResponse, TimelineSummary, TimelineValue,
 };
-use std::collections::{HashMap, HashSet, LinkedList};
+use crate::metrics::timeline::persist::TimelinePersist;
+use std::collections::{BTreeMap, HashMap, HashSet, LinkedList};
 
 #[derive(Debug, Default, Clone)]
 pub struct TimelineGroup {
@@ -11,6 +12,7 @@ pub struct TimelineGroup {
     pub(crate) limit_count: usize,
     pub(crate) last_time: u64,
     pub(crate) interval_second: u64,
+    persist: Option<TimelinePersist>,
 }
 
 impl TimelineGroup {
@@ -20,16 +22,49 @@ impl TimelineGroup {
             limit_count,
             last_time: 0,
             interval_second: interval,
+            persist: None,
         }
     }
 
+    ///
+    /// 开启持久化并加载保留期内的历史记录
+    pub fn init_persist(&mut self, mut persist: TimelinePersist, now_ms: u64) {
+        let retention_ms = self.limit_count as u64 * self.interval_second * 1000;
+        match persist.load(self.limit_count, now_ms.saturating_sub(retention_ms)) {
+            Ok(list) => {
+                if let Some(last) = list.last() {
+                    self.last_time = last.snapshot.snapshot_time;
+                }
+                self.timelines = list.into_iter().collect();
+            }
+            Err(err) => {
+                log::warn!("load metrics timeline error,{}", err);
+            }
+        }
+        self.persist = Some(persist);
+    }
+
     pub fn add_record(&mut self, snapshot: MetricsSnapshot) {
         self.last_time = snapshot.snapshot_time;
-        let record = TimelineValue::new(snapshot, self.timelines.iter().last());
+        //重启前的记录没有原始快照,重启后的第一条记录不计算差值
+        let last = self.timelines.back().filter(|e| !e.restored);
+        let record = TimelineValue::new(snapshot, last);
+        if let Some(persist) = self.persist.as_mut() {
+            if let Err(err) = persist.append(&record) {
+                log::warn!("append metrics timeline error,{}", err);
+            }
+        }
         self.timelines.push_back(record);
         while self.timelines.len() > self.limit_count {
             self.timelines.pop_front();
         }
+        if let Some(persist) = self.persist.as_mut() {
+            if persist.need_rewrite(self.limit_count) {
+                if let Err(err) = persist.rewrite(self.timelines.iter()) {
+                    log::warn!("rewrite metrics timeline error,{}", err);
+                }
+            }
+        }
     }
 
     pub fn query(&self, param: TimelineQueryParam) -> TimelineQueryResponse {
@@ -156,6 +191,24 @@ impl MetricsTimelineManager {
         self.least_timeline_group.interval_second = least_interval;
     }
 
+    pub fn set_hour_limit(&mut self, limit_count: usize) {
+        self.hour_timeline_group.limit_count = limit_count;
+    }
+
+    pub fn init_persist(&mut self, dir: &str, now_ms: u64) -> anyhow::Result<()> {
+        std::fs::create_dir_all(dir)?;
+        for group_type in [
+            TimelineGroupType::Least,
+            TimelineGroupType::Minute,
+            TimelineGroupType::Hour,
+        ] {
+            let persist = TimelinePersist::new(dir, &group_type);
+            self.get_timeline_group_mut(&group_type)
+                .init_persist(persist, now_ms);
+        }
+        Ok(())
+    }
+
     fn get_timeline_group_mut(&mut self, group_type: &TimelineGroupType) -> &mut TimelineGroup {
         match group_type {
             TimelineGroupType::Least => &mut self.least_timeline_group,
@@ -189,3 +242,147 @@ impl MetricsTimelineManager {
         self.get_timeline_group(group_type).last_time
     }
 }
+
+///
+/// 合并多个节点的时间线
+/// 各节点采集时间不完全一致,按时间间隔对齐后汇总;gauge与请求数、rps按节点求和,
+/// 平均耗时按请求数加权,分位耗时取各节点最大值
+pub fn merge_timeline_response(list: &[TimelineQueryResponse]) -> TimelineQueryResponse {
+    let interval_second = list.first().map(|e| e.interval_second).unwrap_or_default();
+    let interval_ms = (interval_second * 1000).max(1);
+    let mut time_set = BTreeMap::new();
+    for item in list {
+        for time in &item.time_index {
+            time_set.insert(time / interval_ms, *time);
+        }
+    }
+    let time_index: Vec<u64> = time_set.values().cloned().collect();
+    let slot_index: HashMap<u64, usize> = time_set
+        .keys()
+        .enumerate()
+        .map(|(i, slot)| (*slot, i))
+        .collect();
+    let len = time_index.len();
+    let mut gauge_data: HashMap<String, Vec<f32>> = HashMap::new();
+    let mut summery_data: HashMap<String, TimelineSummary> = HashMap::new();
+    for item in list {
+        for (i, time) in item.time_index.iter().enumerate() {
+            let index = slot_index[&(time / interval_ms)];
+            for (key, values) in &item.gauge_data {
+                if let Some(v) = values.get(i) {
+                    gauge_data
+                        .entry(key.to_owned())
+                        .or_insert_with(|| vec![0f32; len])[index] += v;
+                }
+            }
+            for (key, summary) in &item.summery_data {
+                let total = summery_data
+                    .entry(key.to_owned())
+                    .or_insert_with(|| TimelineSummary {
+                        bound_keys: summary.bound_keys.clone(),
+                        bounds: summary.bounds.clone(),
+                        rps_data: vec![0f32; len],
+                        average_data: vec![0f32; len],
+                        count_data: vec![0u64; len],
+                        items_data: Default::default(),
+                    });
+                let count = summary.count_data.get(i).cloned().unwrap_or_default();
+                let average = summary.average_data.get(i).cloned().unwrap_or_default();
+                let total_count = total.count_data[index] + count;
+                if total_count > 0 {
+                    total.average_data[index] = (total.average_data[index]
+                        * total.count_data[index] as f32
+                        + average * count as f32)
+                        / total_count as f32;
+                }
+                total.count_data[index] = total_count;
+                total.rps_data[index] += summary.rps_data.get(i).cloned().unwrap_or_default();
+                for (sub_key, values) in &summary.items_data {
+                    if let Some(v) = values.get(i) {
+                        let total_v = &mut total
+                            .items_data
+                            .entry(sub_key.to_owned())
+                            .or_insert_with(|| vec![0f32; len])[index];
+                        *total_v = total_v.max(*v);
+                    }
+                }
+            }
+        }
+    }
+    TimelineQueryResponse {
+        last_time: time_index.last().cloned().unwrap_or_default(),
+        from_node_id: 0,
+        time_index,
+        interval_second,
+        gauge_data,
+        summery_data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::model::GaugeValue;
+
+    fn build_snapshot(time: u64, value: f32) -> MetricsSnapshot {
+        let mut snapshot = MetricsSnapshot {
+            snapshot_time: time,
+            ..Default::default()
+        };
+        snapshot
+            .gauge_data_map
+            .insert(MetricsKey::AppCpuUsage, GaugeValue(value));
+        snapshot
+    }
+
+    #[test]
+    fn timeline_persist_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir_path = dir.path().to_str().unwrap();
+        let now = 3_600_000u64 * 10;
+        let mut group = TimelineGroup::new(2, 60);
+        group.init_persist(
+            TimelinePersist::new(dir_path, &TimelineGroupType::Minute),
+            now,
+        );
+        for i in 0..3 {
+            group.add_record(build_snapshot(now + i * 60_000, i as f32));
+        }
+        let mut reload_group = TimelineGroup::new(2, 60);
+        reload_group.init_persist(
+            TimelinePersist::new(dir_path, &TimelineGroupType::Minute),
+            now + 3 * 60_000,
+        );
+        assert_eq!(reload_group.last_time, now + 2 * 60_000);
+        let resp = reload_group.query(TimelineQueryParam {
+            keys: vec![MetricsKey::AppCpuUsage.get_key().to_owned()],
+            ..Default::default()
+        });
+        assert_eq!(
+            resp.gauge_data[MetricsKey::AppCpuUsage.get_key()],
+            vec![1f32, 2f32]
+        );
+    }
+
+    #[test]
+    fn merge_node_timeline() {
+        let key = "app_cpu_usage".to_owned();
+        let node1 = TimelineQueryResponse {
+            from_node_id: 1,
+            time_index: vec![60_100, 120_100],
+            interval_second: 60,
+            gauge_data: HashMap::from([(key.clone(), vec![1f32, 2f32])]),
+            ..Default::default()
+        };
+        let node2 = TimelineQueryResponse {
+            from_node_id: 2,
+            time_index: vec![120_900, 180_900],
+            interval_second: 60,
+            gauge_data: HashMap::from([(key.clone(), vec![3f32, 4f32])]),
+            ..Default::default()
+        };
+        let total = merge_timeline_response(&[node1, node2]);
+        assert_eq!(total.time_index.len(), 3);
+        assert_eq!(total.gauge_data[&key], vec![1f32, 5f32, 4f32]);
+    }
+}
@@ -1,3 +1,4 @@
 pub mod core;
 pub mod model;
+pub mod persist;
 pub mod timeline_key;
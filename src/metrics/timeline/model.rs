@@ -131,6 +131,8 @@ pub struct TimelineValue {
     pub(crate) snapshot: MetricsSnapshot,
     pub(crate) section_gauge: HashMap<MetricsKey, f32>,
     pub(crate) section_summary: HashMap<MetricsKey, SummaryWrapValue>,
+    /// 从磁盘恢复的记录,只有区间值,不能作为后续记录计算差值的基准
+    pub(crate) restored: bool,
 }
 
 impl TimelineValue {
@@ -139,6 +141,7 @@ impl TimelineValue {
             snapshot,
             section_gauge: HashMap::new(),
             section_summary: HashMap::new(),
+            restored: false,
         };
         s.init(last_snapshot);
         s
@@ -202,6 +205,100 @@ impl TimelineValue {
     }
 }
 
+///
+/// 时间线记录持久化对象,指标key使用字符串保存
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineValueDo {
+    pub snapshot_time: u64,
+    pub gauge: HashMap<String, f32>,
+    pub summary: HashMap<String, SummaryWrapValueDo>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryWrapValueDo {
+    pub count: u64,
+    pub sum: f32,
+    pub bounds: Vec<f32>,
+    pub buckets: Vec<f32>,
+    pub rps: f32,
+    pub average: f32,
+}
+
+impl From<&TimelineValue> for TimelineValueDo {
+    fn from(value: &TimelineValue) -> Self {
+        let gauge = value
+            .section_gauge
+            .iter()
+            .map(|(k, v)| (k.get_key().to_owned(), *v))
+            .collect();
+        let summary = value
+            .section_summary
+            .iter()
+            .map(|(k, v)| {
+                (
+                    k.get_key().to_owned(),
+                    SummaryWrapValueDo {
+                        count: v.value.count,
+                        sum: v.value.sum,
+                        bounds: v.value.bounds.clone(),
+                        buckets: v.value.buckets.iter().map(|e| e.0).collect(),
+                        rps: v.rps,
+                        average: v.average,
+                    },
+                )
+            })
+            .collect();
+        Self {
+            snapshot_time: value.snapshot.snapshot_time,
+            gauge,
+            summary,
+        }
+    }
+}
+
+impl From<TimelineValueDo> for TimelineValue {
+    fn from(value: TimelineValueDo) -> Self {
+        let section_gauge = value
+            .gauge
+            .into_iter()
+            .filter_map(|(k, v)| MetricsKey::of_key(&k).map(|k| (k, v)))
+            .collect();
+        let section_summary = value
+            .summary
+            .into_iter()
+            .filter_map(|(k, v)| {
+                MetricsKey::of_key(&k).map(|k| {
+                    let summary = SummaryValue {
+                        count: v.count,
+                        sum: v.sum,
+                        bounds: v.bounds,
+                        buckets: v.buckets.into_iter().map(GaugeValue).collect(),
+                    };
+                    (
+                        k,
+                        SummaryWrapValue {
+                            value: summary,
+                            rps: v.rps,
+                            average: v.average,
+                        },
+                    )
+                })
+            })
+            .collect();
+        Self {
+            snapshot: MetricsSnapshot {
+                snapshot_time: value.snapshot_time,
+                ..Default::default()
+            },
+            section_gauge,
+            section_summary,
+            restored: true,
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TimelineQueryParam {
@@ -211,6 +308,9 @@ pub struct TimelineQueryParam {
     //pub query_all_key: bool,
     pub keys: Vec<String>,
     pub node_id: u64,
+    /// 查询集群所有节点的时间线并汇总
+    #[serde(default)]
+    pub all_node: bool,
 }
 
 #[derive(Debug, Default, Clone, Deserialize, Serialize)]
@@ -234,3 +334,12 @@ pub struct TimelineQueryResponse {
     pub gauge_data: HashMap<String, Vec<f32>>,
     pub summery_data: HashMap<String, TimelineSummary>,
 }
+
+///
+/// 集群时间线查询结果,包含各节点时间线与汇总时间线
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterTimelineQueryResponse {
+    pub node_list: Vec<TimelineQueryResponse>,
+    pub total: TimelineQueryResponse,
+}
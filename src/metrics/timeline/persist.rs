@@ -0,0 +1,100 @@
+use crate::metrics::timeline::model::{TimelineGroupType, TimelineValue, TimelineValueDo};
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+///
+/// 监控时间线本地持久化
+/// 每个时间线分组一个文件,每行一条json记录;追加写入,记录数超过上限两倍后按内存数据重写文件
+#[derive(Debug, Clone)]
+pub struct TimelinePersist {
+    path: PathBuf,
+    append_count: usize,
+}
+
+impl TimelinePersist {
+    pub fn new(dir: &str, group_type: &TimelineGroupType) -> Self {
+        let path =
+            PathBuf::from(dir).join(format!("{}.log", group_type.get_key().to_ascii_lowercase()));
+        Self {
+            path,
+            append_count: 0,
+        }
+    }
+
+    ///
+    /// 加载不早于min_time的最近limit_count条记录
+    pub fn load(
+        &mut self,
+        limit_count: usize,
+        min_time: u64,
+    ) -> anyhow::Result<Vec<TimelineValue>> {
+        if !self.path.exists() {
+            return Ok(vec![]);
+        }
+        let file = OpenOptions::new().read(true).open(&self.path)?;
+        let mut list = vec![];
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<TimelineValueDo>(&line) {
+                Ok(v) => {
+                    if v.snapshot_time >= min_time {
+                        list.push(v);
+                    }
+                }
+                Err(err) => {
+                    log::warn!("load metrics timeline record error,{}", err);
+                }
+            }
+        }
+        list.sort_by_key(|e| e.snapshot_time);
+        if list.len() > limit_count {
+            list.drain(..list.len() - limit_count);
+        }
+        let list: Vec<TimelineValue> = list.into_iter().map(|e| e.into()).collect();
+        self.rewrite(list.iter())?;
+        Ok(list)
+    }
+
+    pub fn append(&mut self, value: &TimelineValue) -> anyhow::Result<()> {
+        let mut line = serde_json::to_string(&TimelineValueDo::from(value))?;
+        line.push('\n');
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(line.as_bytes())?;
+        self.append_count += 1;
+        Ok(())
+    }
+
+    pub fn need_rewrite(&self, limit_count: usize) -> bool {
+        self.append_count > limit_count * 2
+    }
+
+    pub fn rewrite<'a>(
+        &mut self,
+        values: impl Iterator<Item = &'a TimelineValue>,
+    ) -> anyhow::Result<()> {
+        let tmp_path = self.path.with_extension("log.tmp");
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&tmp_path)?;
+        let mut count = 0;
+        for value in values {
+            let mut line = serde_json::to_string(&TimelineValueDo::from(value))?;
+            line.push('\n');
+            file.write_all(line.as_bytes())?;
+            count += 1;
+        }
+        file.flush()?;
+        std::fs::rename(&tmp_path, &self.path)?;
+        self.append_count = count;
+        Ok(())
+    }
+}
@@ -1,14 +1,14 @@
 pub mod model;
 
 use crate::common::constant::{EMPTY_ARC_STRING, NAMESPACE_TREE_NAME};
-use crate::config::core::ConfigActor;
+use crate::config::core::{ConfigActor, ConfigCmd};
 use crate::console::NamespaceUtilsOld;
 use crate::namespace::model::{
     Namespace, NamespaceActorReq, NamespaceActorResult, NamespaceDO, NamespaceFromFlags,
-    NamespaceParam, NamespaceQueryReq, NamespaceQueryResult, NamespaceRaftReq, NamespaceRaftResult,
-    WeakNamespaceFromType, FROM_SYSTEM_VALUE,
+    NamespaceParam, NamespaceQueryReq, NamespaceQueryResult, NamespaceQuota, NamespaceRaftReq,
+    NamespaceRaftResult, WeakNamespaceFromType, FROM_SYSTEM_VALUE,
 };
-use crate::naming::core::NamingActor;
+use crate::naming::core::{NamingActor, NamingCmd};
 use crate::raft::filestore::model::SnapshotRecordDto;
 use crate::raft::filestore::raftapply::{RaftApplyDataRequest, RaftApplyDataResponse};
 use crate::raft::filestore::raftsnapshot::{SnapshotWriterActor, SnapshotWriterRequest};
@@ -19,7 +19,6 @@ use crate::transfer::model::{
 };
 use crate::transfer::writer::TransferWriterActor;
 use actix::prelude::*;
-use async_raft_ext::raft::ClientWriteRequest;
 use bean_factory::{bean, BeanFactory, FactoryData, Inject};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -34,6 +33,7 @@ pub fn build_already_mark_param() -> NamespaceParam {
         namespace_id: Arc::new(ALREADY_SYNC_FROM_CONFIG_KEY.to_string()),
         namespace_name: None,
         r#type: None,
+        quota: None,
     }
 }
 
@@ -104,6 +104,7 @@ impl NamespaceActor {
                 namespace_id: EMPTY_ARC_STRING.clone(),
                 namespace_name: Some(DEFAULT_NAMESPACE.to_owned()),
                 r#type: Some(FROM_SYSTEM_VALUE.to_owned()),
+                quota: None,
             },
             false,
             false,
@@ -127,6 +128,8 @@ impl NamespaceActor {
         } else {
             NamespaceFromFlags::USER.bits()
         };
+        let quota_changed = param.quota.is_some();
+        let param_quota = param.quota.filter(|e| !e.is_empty()).map(Arc::new);
         let value = if let Some(v) = self.data.get(&param.namespace_id) {
             // only_add只在兼容从v0.5数据升级时发生；
             // 如果已经存在用户创建或变更过的数据则直接退出，否则更新
@@ -143,6 +146,11 @@ impl NamespaceActor {
                 v.namespace_name.to_owned()
             };
             value.flag = v.flag | param_flag;
+            value.quota = if quota_changed {
+                param_quota
+            } else {
+                v.quota.clone()
+            };
             value
         } else {
             if only_update {
@@ -153,12 +161,30 @@ impl NamespaceActor {
                 namespace_id: param.namespace_id,
                 namespace_name: param.namespace_name.unwrap_or_default(),
                 flag: param_flag,
+                quota: param_quota,
             }
         };
+        if quota_changed {
+            self.notify_quota(value.namespace_id.clone(), value.quota.clone());
+        }
         self.data
             .insert(value.namespace_id.clone(), Arc::new(value));
     }
 
+    ///
+    /// 把命名空间配额同步到配置与服务模块,由其在写入时校验
+    fn notify_quota(&self, namespace_id: Arc<String>, quota: Option<Arc<NamespaceQuota>>) {
+        if let Some(config_addr) = &self.config_addr {
+            config_addr.do_send(ConfigCmd::SetNamespaceQuota(
+                namespace_id.clone(),
+                quota.clone(),
+            ));
+        }
+        if let Some(naming_addr) = &self.naming_addr {
+            naming_addr.do_send(NamingCmd::SetNamespaceQuota(namespace_id, quota));
+        }
+    }
+
     fn set_weak_namespace(&mut self, namespace_id: Arc<String>, from_type: WeakNamespaceFromType) {
         if namespace_id.is_empty() {
             return;
@@ -179,6 +205,7 @@ impl NamespaceActor {
                 namespace_id: namespace_id.clone(),
                 namespace_name: namespace_id.as_str().to_owned(),
                 flag,
+                quota: None,
             };
             self.data.insert(namespace_id.clone(), Arc::new(value));
         }
@@ -212,7 +239,11 @@ impl NamespaceActor {
     }
 
     fn remove_id(&mut self, id: &Arc<String>) {
-        self.data.remove(id);
+        if let Some(v) = self.data.remove(id) {
+            if v.quota.is_some() {
+                self.notify_quota(id.clone(), None);
+            }
+        }
         for (i, item) in self.id_order_list.iter().enumerate() {
             if id == item {
                 self.id_order_list.remove(i);
@@ -231,10 +262,19 @@ impl NamespaceActor {
         list
     }
 
+    ///
+    /// 非用户数据不记录;默认命名空间设置了配额时需要记录
+    fn need_persist(key: &Arc<String>, value: &Namespace) -> bool {
+        if key.is_empty() {
+            value.quota.is_some()
+        } else {
+            value.flag & NamespaceFromFlags::USER.bits() > 0
+        }
+    }
+
     fn build_snapshot(&self, writer: Addr<SnapshotWriterActor>) -> anyhow::Result<()> {
         for (key, value) in &self.data {
-            if key.is_empty() || value.flag & NamespaceFromFlags::USER.bits() == 0 {
-                //非用户数据不记录
+            if !Self::need_persist(key, value) {
                 continue;
             }
             let value_db: NamespaceDO = value.as_ref().to_owned().into();
@@ -252,6 +292,7 @@ impl NamespaceActor {
                 namespace_id: param.namespace_id,
                 namespace_name: param.namespace_name.unwrap_or_default(),
                 flag: NamespaceFromFlags::USER.bits(),
+                quota: None,
             };
             let key = value.namespace_id.clone();
             let value_db: NamespaceDO = value.into();
@@ -270,8 +311,7 @@ impl NamespaceActor {
     /// 迁移数据备件
     fn transfer_backup(&self, writer: Addr<TransferWriterActor>) -> anyhow::Result<()> {
         for (key, value) in &self.data {
-            if key.is_empty() || value.flag & NamespaceFromFlags::USER.bits() == 0 {
-                //非用户数据不记录
+            if !Self::need_persist(key, value) {
                 continue;
             }
             let value_db: NamespaceDO = value.as_ref().to_owned().into();
@@ -294,6 +334,7 @@ impl NamespaceActor {
                 namespace_id: value.namespace_id,
                 namespace_name: Some(value.namespace_name),
                 r#type: Some(NamespaceFromFlags::get_db_type(value.flag)),
+                quota: value.quota.map(|e| e.as_ref().to_owned()),
             },
             false,
             false,
@@ -317,6 +358,7 @@ impl NamespaceActor {
                     namespace_id,
                     namespace_name: item.namespace_name,
                     r#type: item.r#type,
+                    quota: None,
                 },
                 true,
                 false,
@@ -374,9 +416,10 @@ impl NamespaceActor {
         if let Some(node_id) = raft.current_leader().await {
             if node_id == local_id {
                 let v = NamespaceUtilsOld::get_namespace_source(&config_addr).await;
-                raft.client_write(ClientWriteRequest::new(ClientRequest::NamespaceReq(
-                    NamespaceRaftReq::InitFromOldValue(v),
-                )))
+                crate::raft::client_write(
+                    &raft,
+                    ClientRequest::NamespaceReq(NamespaceRaftReq::InitFromOldValue(v)),
+                )
                 .await?;
                 return Ok(true);
             }
@@ -13,12 +13,72 @@ lazy_static::lazy_static! {
 pub(crate) const FROM_SYSTEM_VALUE: &str = "0";
 pub(crate) const FROM_USER_VALUE: &str = "2";
 
+///
+/// 命名空间配额,为空表示不限制
+#[derive(Clone, PartialEq, Eq, prost_derive::Message, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamespaceQuota {
+    /// 配置数量
+    #[prost(uint64, optional, tag = "1")]
+    pub max_config_count: Option<u64>,
+    /// 配置内容总字节数
+    #[prost(uint64, optional, tag = "2")]
+    pub max_config_bytes: Option<u64>,
+    /// 单个配置保留的历史记录数
+    #[prost(uint64, optional, tag = "3")]
+    pub max_history_count: Option<u64>,
+    /// 服务数量
+    #[prost(uint64, optional, tag = "4")]
+    pub max_service_count: Option<u64>,
+    /// 实例数量
+    #[prost(uint64, optional, tag = "5")]
+    pub max_instance_count: Option<u64>,
+}
+
+impl NamespaceQuota {
+    pub fn is_empty(&self) -> bool {
+        self.max_config_count.is_none()
+            && self.max_config_bytes.is_none()
+            && self.max_history_count.is_none()
+            && self.max_service_count.is_none()
+            && self.max_instance_count.is_none()
+    }
+
+    ///
+    /// 检查新增后的使用量是否超过配额
+    pub fn check(name: &str, limit: Option<u64>, new_value: u64) -> anyhow::Result<()> {
+        if let Some(limit) = limit {
+            if new_value > limit {
+                return Err(anyhow::anyhow!(
+                    "namespace quota exceeded, {}: {} > {}",
+                    name,
+                    new_value,
+                    limit
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+///
+/// 命名空间使用量
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamespaceUsage {
+    pub config_count: u64,
+    pub config_bytes: u64,
+    pub service_count: u64,
+    pub instance_count: u64,
+}
+
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct Namespace {
     pub namespace_id: Arc<String>,
     pub namespace_name: String,
     //pub r#type: String,
     pub flag: u32,
+    pub quota: Option<Arc<NamespaceQuota>>,
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +86,9 @@ pub struct NamespaceParam {
     pub namespace_id: Arc<String>,
     pub namespace_name: Option<String>,
     pub r#type: Option<String>,
+    /// 为空时保留原配额;配额各项都为空时清除配额
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quota: Option<NamespaceQuota>,
 }
 
 #[derive(Clone, PartialEq, prost_derive::Message, Deserialize, Serialize)]
@@ -36,6 +99,8 @@ pub struct NamespaceDO {
     pub namespace_name: Option<String>,
     #[prost(string, optional, tag = "3")]
     pub r#type: Option<String>,
+    #[prost(message, optional, tag = "4")]
+    pub quota: Option<NamespaceQuota>,
 }
 
 impl NamespaceDO {
@@ -64,6 +129,7 @@ impl From<NamespaceDO> for Namespace {
             namespace_id: Arc::new(value.namespace_id.unwrap_or_default()),
             namespace_name: value.namespace_name.unwrap_or_default(),
             flag,
+            quota: value.quota.filter(|e| !e.is_empty()).map(Arc::new),
         }
     }
 }
@@ -75,6 +141,7 @@ impl From<Namespace> for NamespaceDO {
             namespace_id: Some(value.namespace_id.as_str().to_string()),
             namespace_name: Some(value.namespace_name),
             r#type: Some(t),
+            quota: value.quota.map(|e| e.as_ref().to_owned()),
         }
     }
 }
@@ -164,6 +164,8 @@ pub async fn handle_naming_route(
             //增量数据
             app.naming_addr
                 .do_send(NamingCmd::ReceiveSnapshot(snapshot_receive));
+            app.naming_inner_node_manage
+                .do_send(NodeManageRequest::ReceiveSnapshot(cluster_id));
         }
         NamingRouteRequest::MetricsTimelineQuery(param) => {
             let resp = app
@@ -5,6 +5,8 @@ use super::model::SnapshotForSend;
 use super::model::SyncSenderRequest;
 use super::model::{NamingRouteAddr, SyncSenderSetCmd};
 use super::sync_sender::ClusteSyncSender;
+use crate::health::core::HealthManager;
+use crate::health::model::HealthBackRequest;
 use crate::naming::core::NamingResult;
 use crate::naming::model::{DistroData, InstanceKey};
 use crate::{
@@ -22,6 +24,9 @@ use std::{
     time::Duration,
 };
 
+/// 启动后最长等待该时间,其它节点未返回snapshot也视为同步完成
+const DISTRO_SYNC_TIMEOUT_MILLIS: u64 = 60_000;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum NodeStatus {
     Valid,
@@ -79,7 +84,11 @@ pub struct InnerNodeManage {
     all_nodes: BTreeMap<u64, ClusterInnerNode>,
     cluster_sender: Option<Arc<RaftClusterRequestSender>>,
     naming_actor: Option<Addr<NamingActor>>,
+    health_manager: Option<Addr<HealthManager>>,
     first_query_snapshot: bool,
+    //已返回snapshot的节点
+    snapshot_received_nodes: HashSet<u64>,
+    distro_synced: bool,
     current_range: ProcessRange,
     history_ranges: Vec<(ProcessRange, u64)>,
     last_send_distor_data_time: i32,
@@ -92,7 +101,10 @@ impl InnerNodeManage {
             cluster_sender: None,
             all_nodes: Default::default(),
             naming_actor: None,
+            health_manager: None,
             first_query_snapshot: false,
+            snapshot_received_nodes: HashSet::new(),
+            distro_synced: false,
             current_range: ProcessRange { index: 0, len: 1 },
             history_ranges: Vec::new(),
             last_send_distor_data_time: 0,
@@ -170,10 +182,12 @@ impl InnerNodeManage {
             //1秒
             ctx.run_later(Duration::from_millis(1000), |act, _ctx| {
                 act.load_snapshot_from_node();
+                act.check_distro_synced();
             });
             //15秒
             ctx.run_later(Duration::from_millis(15000), |act, _ctx| {
                 act.load_snapshot_from_node();
+                act.check_distro_synced();
             });
             //45秒
             ctx.run_later(Duration::from_millis(45_000), |act, _ctx| {
@@ -187,6 +201,33 @@ impl InnerNodeManage {
         }
     }
 
+    ///
+    /// 所有可用的其它节点都返回过snapshot后,通知naming数据同步完成
+    fn check_distro_synced(&mut self) {
+        if self.distro_synced {
+            return;
+        }
+        let all_received = self
+            .all_nodes
+            .values()
+            .filter(|e| !e.is_local && e.is_valid())
+            .all(|e| self.snapshot_received_nodes.contains(&e.id));
+        if all_received {
+            self.set_distro_synced();
+        }
+    }
+
+    fn set_distro_synced(&mut self) {
+        if self.distro_synced {
+            return;
+        }
+        self.distro_synced = true;
+        log::info!("naming distro data sync finished.");
+        if let Some(health_manager) = self.health_manager.as_ref() {
+            health_manager.do_send(HealthBackRequest::NamingDistroSynced);
+        }
+    }
+
     fn refresh_process_range(&mut self) {
         if let Some(naming_actor) = &self.naming_actor {
             naming_actor.do_send(NamingCmd::ClusterRefreshProcessRange(
@@ -493,6 +534,15 @@ impl Actor for InnerNodeManage {
 
         //定时检测节点的可用性
         self.hb(ctx);
+        ctx.run_later(
+            Duration::from_millis(DISTRO_SYNC_TIMEOUT_MILLIS),
+            |act, _ctx| {
+                if !act.distro_synced {
+                    log::warn!("naming distro data sync timeout");
+                    act.set_distro_synced();
+                }
+            },
+        );
     }
 }
 
@@ -507,6 +557,7 @@ impl Inject for InnerNodeManage {
     ) {
         self.naming_actor = factory_data.get_actor();
         self.cluster_sender = factory_data.get_bean();
+        self.health_manager = factory_data.get_actor();
         log::info!("InnerNodeManage inject complete!");
     }
 }
@@ -527,6 +578,8 @@ pub enum NodeManageRequest {
     //QueryClusterIds(),
     QueryOwnerRange(ProcessRange),
     SendSnapshot(u64, SnapshotForSend),
+    /// 收到其它节点返回的snapshot
+    ReceiveSnapshot(u64),
     QueryDiffClientInstances(u64, Vec<InstanceKey>),
 }
 
@@ -595,6 +648,11 @@ impl Handler<NodeManageRequest> for InnerNodeManage {
                 self.send_snapshot_to_node(node_id, snapshot);
                 Ok(NodeManageResponse::None)
             }
+            NodeManageRequest::ReceiveSnapshot(node_id) => {
+                self.snapshot_received_nodes.insert(node_id);
+                self.check_distro_synced();
+                Ok(NodeManageResponse::None)
+            }
             NodeManageRequest::QueryDiffClientInstances(node_id, diff_instances) => {
                 self.send_diff_instance_to_node(node_id, diff_instances);
                 Ok(NodeManageResponse::None)
@@ -15,6 +15,10 @@ use super::cluster::model::{
 };
 use super::cluster::node_manage::{InnerNodeManage, NodeManageRequest};
 use super::filter::InstanceFilterUtils;
+use super::health_history::{
+    FlapConfig, InstanceHealthHistory, InstanceHistoryDto, REASON_CLIENT_DISCONNECT,
+    REASON_CLUSTER_SYNC, REASON_DEREGISTER, REASON_PROBE_FAILED,
+};
 use super::listener::{InnerNamingListener, ListenerItem, NamingListenerCmd};
 use super::model::InstanceShortKey;
 use super::model::InstanceUpdateTag;
@@ -28,6 +32,7 @@ use super::naming_delay_nofity::DelayNotifyActor;
 use super::naming_delay_nofity::DelayNotifyCmd;
 use super::naming_subscriber::NamingListenerItem;
 use super::naming_subscriber::Subscriber;
+use super::prometheus_sd::{build_target_groups, PrometheusSdParam, PrometheusTargetGroup};
 use super::service::ServiceInfoDto;
 use super::service::ServiceMetadata;
 use super::service::{Service, SubscriberInfoDto};
@@ -61,6 +66,7 @@ use crate::common::model::privilege::NamespacePrivilegeGroup;
 use crate::common::pb::data_object::InstanceDo;
 use crate::metrics::metrics_key::MetricsKey;
 use crate::metrics::model::{MetricsItem, MetricsQuery, MetricsRecord};
+use crate::namespace::model::{NamespaceQuota, NamespaceUsage};
 use crate::namespace::NamespaceActor;
 use crate::naming::model::actor_model::{
     InstanceRegisterParam, LoadResult, NamingRaftReq, NamingRaftResult, SnapshotBuildRequest,
@@ -102,6 +108,7 @@ pub struct NamingActor {
     pub(crate) last_perpetual_instance_probe_time: i32,
     //dal_addr: Addr<ServiceDalActor>,
     pub(crate) raft_router: Option<Arc<RaftRequestRoute>>,
+    pub(crate) namespace_quota: HashMap<Arc<String>, Arc<NamespaceQuota>>,
 }
 
 impl Actor for NamingActor {
@@ -137,6 +144,11 @@ impl Inject for NamingActor {
             self.sys_config.instance_timeout_millis =
                 sys_config.naming_instance_timeout as i64 + 3000;
             self.node_id = sys_config.raft_node_id;
+            self.sys_config.flap_config = FlapConfig {
+                threshold: sys_config.naming_flap_threshold,
+                window_millis: sys_config.naming_flap_window_second as i64 * 1000,
+                quarantine_millis: sys_config.naming_flap_quarantine_second as i64 * 1000,
+            };
             log::info!("NamingActor change naming timeout info from env,health_timeout:{},instance_timeout:{}"
                 ,self.sys_config.instance_health_timeout_millis,self.sys_config.instance_timeout_millis);
             if sys_config.naming_perpetual_instance_probe_interval > 0 {
@@ -181,6 +193,7 @@ impl NamingActor {
             net_sniffing_addr: None,
             last_perpetual_instance_probe_time: 0,
             raft_router: None,
+            namespace_quota: Default::default(),
         }
     }
 
@@ -220,6 +233,7 @@ impl NamingActor {
                     key.group_name.as_ref(),
                 ));
                 service.last_modified_millis = current_time;
+                service.health_history = InstanceHealthHistory::new(self.sys_config.flap_config);
                 service.recalculate_checksum();
                 self.namespace_index.insert_service(key.clone());
                 //self.dal_addr.do_send(ServiceDalMsg::AddService(service.get_service_do()));
@@ -232,6 +246,72 @@ impl NamingActor {
         }
     }
 
+    fn get_namespace_instance_size(&self, namespace_id: &Arc<String>) -> u64 {
+        let mut size = 0;
+        if let Some(index) = self.namespace_index.namespace_group.get(namespace_id) {
+            for (group, set) in &index.group_service {
+                for service_name in set {
+                    let key = ServiceKey::new_by_arc(
+                        namespace_id.clone(),
+                        group.clone(),
+                        service_name.clone(),
+                    );
+                    if let Some(service) = self.service_map.get(&key) {
+                        size += service.instance_size.max(0) as u64;
+                    }
+                }
+            }
+        }
+        size
+    }
+
+    pub(crate) fn query_namespace_usage(&self) -> HashMap<Arc<String>, NamespaceUsage> {
+        let mut map = HashMap::new();
+        for (namespace_id, index) in &self.namespace_index.namespace_group {
+            let usage = NamespaceUsage {
+                service_count: index.get_service_count() as u64,
+                instance_count: self.get_namespace_instance_size(namespace_id),
+                ..Default::default()
+            };
+            map.insert(namespace_id.clone(), usage);
+        }
+        map
+    }
+
+    ///
+    /// 校验新增服务或实例后命名空间是否超过配额
+    pub(crate) fn check_namespace_quota(
+        &self,
+        key: &ServiceKey,
+        instance: Option<&Instance>,
+    ) -> anyhow::Result<()> {
+        let quota = if let Some(v) = self.namespace_quota.get(&key.namespace_id) {
+            v
+        } else {
+            return Ok(());
+        };
+        let service = self.service_map.get(key);
+        if service.is_none() && quota.max_service_count.is_some() {
+            let count = self
+                .namespace_index
+                .namespace_group
+                .get(&key.namespace_id)
+                .map(|e| e.get_service_count())
+                .unwrap_or_default() as u64;
+            NamespaceQuota::check("serviceCount", quota.max_service_count, count + 1)?;
+        }
+        if let Some(instance) = instance {
+            let exist = service
+                .map(|e| e.instances.contains_key(&instance.get_short_key()))
+                .unwrap_or(false);
+            if !exist && quota.max_instance_count.is_some() {
+                let count = self.get_namespace_instance_size(&key.namespace_id);
+                NamespaceQuota::check("instanceCount", quota.max_instance_count, count + 1)?;
+            }
+        }
+        Ok(())
+    }
+
     pub(crate) fn update_service(&mut self, service_info: ServiceDetailDto) {
         let key = ServiceKey::new_by_arc(
             service_info.namespace_id,
@@ -369,6 +449,7 @@ impl NamingActor {
         key: &ServiceKey,
         instance_id: &InstanceShortKey,
         client_id: Option<&Arc<String>>,
+        reason: &str,
     ) -> (UpdateInstanceType, UpdatePerpetualType) {
         let service = if let Some(service) = self.service_map.get_mut(key) {
             service
@@ -377,7 +458,7 @@ impl NamingActor {
         };
         let mut real_client_id = None;
         let mut perpetual_tag = UpdatePerpetualType::None;
-        let old_instance = service.remove_instance(instance_id, client_id);
+        let old_instance = service.remove_instance(instance_id, client_id, reason);
         let now = now_millis();
         let tag = if let Some(old_instance) = &old_instance {
             real_client_id = Some(old_instance.client_id.clone());
@@ -505,7 +586,12 @@ impl NamingActor {
             for instance_key in keys {
                 let service_key = instance_key.get_service_key();
                 let short_key = instance_key.get_short_key();
-                self.remove_instance(&service_key, &short_key, Some(client_id));
+                self.remove_instance(
+                    &service_key,
+                    &short_key,
+                    Some(client_id),
+                    REASON_CLIENT_DISCONNECT,
+                );
             }
         }
     }
@@ -704,7 +790,7 @@ impl NamingActor {
                 if sniffing_result {
                     server.update_perpetual_instance_healthy_valid(&host);
                 } else {
-                    server.update_instance_healthy_invalid(&host);
+                    server.update_instance_healthy_invalid(&host, REASON_PROBE_FAILED);
                 }
             }
         }
@@ -1045,7 +1131,7 @@ impl NamingActor {
             }
         }
         for (service_key, client_key) in remove_keys {
-            self.remove_instance(&service_key, &client_key, None);
+            self.remove_instance(&service_key, &client_key, None, REASON_CLUSTER_SYNC);
         }
         new_items
     }
@@ -1181,7 +1267,7 @@ impl NamingActor {
             NamingRaftReq::RemoveInstance(instance_key) => {
                 let service_key = instance_key.get_service_key();
                 let instance_short_key = instance_key.get_short_key();
-                self.remove_instance(&service_key, &instance_short_key, None);
+                self.remove_instance(&service_key, &instance_short_key, None, REASON_DEREGISTER);
                 Ok(NamingRaftResult::None)
             }
         }
@@ -1341,6 +1427,8 @@ pub enum NamingCmd {
     QueryList(ServiceKey, String, bool, Option<SocketAddr>),
     SelectOneInstance(ServiceKey),
     QueryAllInstanceList(ServiceKey),
+    /// 查询服务下实例的状态变更历史
+    QueryInstanceHistory(ServiceKey),
     QueryListString(ServiceKey, String, bool, Option<SocketAddr>),
     QueryServiceInfo(ServiceKey, String, bool),
     QueryServicePage(ServiceKey, usize, usize),
@@ -1362,6 +1450,7 @@ pub enum NamingCmd {
     RemoveClientsFromCluster(Vec<Arc<String>>),
     RemoveClientFromCluster(Arc<String>),
     QueryClientInstanceCount,
+    QueryClientDetail(Arc<String>),
     QueryDalAddr,
     QuerySnapshot(Vec<ProcessRange>),
     ClusterRefreshProcessRange(ProcessRange),
@@ -1379,6 +1468,11 @@ pub enum NamingCmd {
     },
     NotifyUpdateRaftInstance(Arc<Instance>),
     NotifyRemoveRaftInstance(InstanceKey),
+    /// 设置命名空间配额,为空表示清除
+    SetNamespaceQuota(Arc<String>, Option<Arc<NamespaceQuota>>),
+    QueryNamespaceUsage,
+    /// 查询prometheus服务发现的目标组
+    QueryPrometheusTargets(PrometheusSdParam),
 }
 
 pub enum NamingResult {
@@ -1386,6 +1480,7 @@ pub enum NamingResult {
     Instance(Arc<Instance>),
     SelectInstance(Option<Arc<Instance>>),
     InstanceList(Vec<Arc<Instance>>),
+    InstanceHistory(Vec<InstanceHistoryDto>),
     InstanceListString(String),
     ServiceInfo(ServiceInfo),
     ServicePage((usize, Vec<Arc<String>>)),
@@ -1393,11 +1488,17 @@ pub enum NamingResult {
     ServiceInfoPage((usize, Vec<ServiceInfoDto>)),
     ServiceDto(Option<ServiceInfoDto>),
     ClientInstanceCount(Vec<(Arc<String>, usize)>),
+    ClientDetail {
+        subscribe_services: Vec<ServiceKey>,
+        instances: Vec<InstanceKey>,
+    },
     RewriteToCluster(u64, Instance),
     Snapshot(SnapshotForSend),
     GrpcDistroData(DistroData),
     DiffDistroData(DistroData),
     DistroInstancesSnapshot(Vec<Arc<Instance>>),
+    NamespaceUsage(HashMap<Arc<String>, NamespaceUsage>),
+    PrometheusTargets(Vec<PrometheusTargetGroup>),
 }
 
 impl Supervised for NamingActor {
@@ -1414,6 +1515,7 @@ impl Handler<NamingCmd> for NamingActor {
         //log::info!("NamingActor handle:{:?}", &msg);
         match msg {
             NamingCmd::Update(instance, tag) => {
+                self.check_namespace_quota(&instance.get_service_key(), Some(&instance))?;
                 let tag = self.update_instance(
                     &instance.get_service_key(),
                     instance,
@@ -1447,6 +1549,7 @@ impl Handler<NamingCmd> for NamingActor {
                     &instance.get_service_key(),
                     &instance.get_short_key(),
                     Some(&instance.client_id),
+                    REASON_DEREGISTER,
                 );
                 if let UpdatePerpetualType::Remove = perpetual_tag {
                     let instance_key = instance.get_instance_key();
@@ -1460,6 +1563,7 @@ impl Handler<NamingCmd> for NamingActor {
                         &instance.get_service_key(),
                         &instance.get_short_key(),
                         Some(&instance.client_id),
+                        REASON_CLUSTER_SYNC,
                     );
                 }
                 Ok(NamingResult::NULL)
@@ -1569,6 +1673,12 @@ impl Handler<NamingCmd> for NamingActor {
                 Ok(NamingResult::NULL)
             }
             NamingCmd::UpdateService(service_info) => {
+                let key = ServiceKey::new_by_arc(
+                    service_info.namespace_id.clone(),
+                    service_info.group_name.clone(),
+                    service_info.service_name.clone(),
+                );
+                self.check_namespace_quota(&key, None)?;
                 self.update_service(service_info.clone());
                 if let Some(node_manage) = self.cluster_node_manage.as_ref() {
                     //来源于客户端的变更通知其它节点
@@ -1595,6 +1705,14 @@ impl Handler<NamingCmd> for NamingActor {
                     Ok(NamingResult::InstanceList(vec![]))
                 }
             }
+            NamingCmd::QueryInstanceHistory(key) => {
+                let list = self
+                    .service_map
+                    .get(&key)
+                    .map(|service| service.get_instance_history())
+                    .unwrap_or_default();
+                Ok(NamingResult::InstanceHistory(list))
+            }
             NamingCmd::SelectOneInstance(service_key) => {
                 let v = if let Some(service) = self.service_map.get(&service_key) {
                     service.select_one_instance(true, true)
@@ -1610,6 +1728,18 @@ impl Handler<NamingCmd> for NamingActor {
                 }
                 Ok(NamingResult::ClientInstanceCount(client_instance_count))
             }
+            NamingCmd::QueryClientDetail(client_id) => {
+                let subscribe_services = self.subscriber.get_client_keys(&client_id);
+                let instances = self
+                    .client_instance_set
+                    .get(&client_id)
+                    .map(|set| set.iter().cloned().collect())
+                    .unwrap_or_default();
+                Ok(NamingResult::ClientDetail {
+                    subscribe_services,
+                    instances,
+                })
+            }
             NamingCmd::QuerySnapshot(ranges) => {
                 let res = self.build_snapshot_data(ranges);
                 Ok(NamingResult::Snapshot(res))
@@ -1661,6 +1791,28 @@ impl Handler<NamingCmd> for NamingActor {
                 self.remove_instance_to_raft(instance_key, ctx);
                 Ok(NamingResult::NULL)
             }
+            NamingCmd::SetNamespaceQuota(namespace_id, quota) => {
+                if let Some(quota) = quota {
+                    self.namespace_quota.insert(namespace_id, quota);
+                } else {
+                    self.namespace_quota.remove(&namespace_id);
+                }
+                Ok(NamingResult::NULL)
+            }
+            NamingCmd::QueryNamespaceUsage => {
+                Ok(NamingResult::NamespaceUsage(self.query_namespace_usage()))
+            }
+            NamingCmd::QueryPrometheusTargets(param) => {
+                let namespace_id = param.get_namespace_id();
+                let services = self
+                    .service_map
+                    .iter()
+                    .filter(|(key, _)| param.match_service(namespace_id.as_deref(), key))
+                    .map(|(key, service)| (key, service.instances.values().cloned().collect()));
+                Ok(NamingResult::PrometheusTargets(build_target_groups(
+                    &param, services,
+                )))
+            }
         }
     }
 }
@@ -1784,8 +1936,53 @@ fn test_remove_has_instance_service() {
     assert!(naming.remove_empty_service(service_key.clone()).is_err());
     assert!(naming.namespace_index.service_size == 1);
 
-    naming.remove_instance(&service_key, &instance.get_short_key(), None);
+    naming.remove_instance(
+        &service_key,
+        &instance.get_short_key(),
+        None,
+        REASON_DEREGISTER,
+    );
     assert!(naming.namespace_index.service_size == 1);
     assert!(naming.remove_empty_service(service_key.clone()).is_ok());
     assert!(naming.namespace_index.service_size == 0);
 }
+
+#[test]
+fn test_namespace_quota() {
+    let mut naming = NamingActor::new();
+    let namespace_id = Arc::new("quota".to_owned());
+    naming.namespace_quota.insert(
+        namespace_id.clone(),
+        Arc::new(NamespaceQuota {
+            max_service_count: Some(1),
+            max_instance_count: Some(1),
+            ..Default::default()
+        }),
+    );
+    let mut instance = Instance::new("127.0.0.1".to_owned(), 8080);
+    instance.namespace_id = namespace_id.clone();
+    instance.service_name = Arc::new("foo".to_owned());
+    instance.group_name = Arc::new("DEFUALT".to_owned());
+    instance.cluster_name = "DEFUALT".to_owned();
+    instance.init();
+    let service_key = instance.get_service_key();
+    assert!(naming
+        .check_namespace_quota(&service_key, Some(&instance))
+        .is_ok());
+    naming.update_instance(&service_key, instance.clone(), None, false, None);
+    // 已存在的实例更新不受配额限制
+    assert!(naming
+        .check_namespace_quota(&service_key, Some(&instance))
+        .is_ok());
+    let mut other = instance.clone();
+    other.port = 8081;
+    assert!(naming
+        .check_namespace_quota(&service_key, Some(&other))
+        .is_err());
+    let other_key = ServiceKey::new_by_arc(
+        namespace_id,
+        service_key.group_name.clone(),
+        Arc::new("bar".to_owned()),
+    );
+    assert!(naming.check_namespace_quota(&other_key, None).is_err());
+}
@@ -0,0 +1,268 @@
+use super::model::{Instance, InstanceShortKey};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+pub const REASON_REGISTER: &str = "register";
+pub const REASON_DEREGISTER: &str = "deregister";
+pub const REASON_CLIENT_DISCONNECT: &str = "client disconnect";
+pub const REASON_HEARTBEAT: &str = "heartbeat";
+pub const REASON_HEARTBEAT_TIMEOUT: &str = "heartbeat timeout";
+pub const REASON_INSTANCE_TIMEOUT: &str = "instance timeout";
+pub const REASON_PROBE_SUCCESS: &str = "probe success";
+pub const REASON_PROBE_FAILED: &str = "probe failed";
+pub const REASON_CLUSTER_SYNC: &str = "cluster sync";
+pub const REASON_UPDATE: &str = "update";
+pub const REASON_DEBUG: &str = "debug";
+pub const REASON_FLAPPING: &str = "health status flips too frequently";
+
+/// 每个实例保留的最近事件数
+const MAX_EVENT_SIZE: usize = 50;
+/// 实例下线后历史的保留时间
+const REMOVED_HISTORY_KEEP_MILLIS: i64 = 60 * 60 * 1000;
+const CLEAR_INTERVAL_MILLIS: i64 = 60 * 1000;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InstanceEventType {
+    Registered,
+    Deregistered,
+    Healthy,
+    Unhealthy,
+    Enabled,
+    Disabled,
+    /// 窗口内健康状态切换次数超过阈值
+    Flapping,
+    /// 抖动实例被临时隔离,隔离期内保持不健康
+    Quarantined,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceEvent {
+    pub event_type: InstanceEventType,
+    pub reason: Arc<String>,
+    pub time: i64,
+}
+
+///
+/// 实例抖动检测配置;threshold为0表示不检测
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FlapConfig {
+    pub threshold: usize,
+    pub window_millis: i64,
+    /// 抖动后的隔离时长,0表示只标记不隔离
+    pub quarantine_millis: i64,
+}
+
+#[derive(Debug, Default)]
+struct InstanceHistoryItem {
+    events: VecDeque<InstanceEvent>,
+    flip_times: VecDeque<i64>,
+    quarantine_until: i64,
+    last_time: i64,
+}
+
+impl InstanceHistoryItem {
+    fn push(&mut self, event_type: InstanceEventType, reason: &str, now: i64) {
+        if self.events.len() >= MAX_EVENT_SIZE {
+            self.events.pop_front();
+        }
+        self.events.push_back(InstanceEvent {
+            event_type,
+            reason: Arc::new(reason.to_owned()),
+            time: now,
+        });
+        self.last_time = now;
+    }
+
+    fn flip_count(&self, config: &FlapConfig, now: i64) -> usize {
+        let start = now - config.window_millis;
+        self.flip_times.iter().filter(|e| **e > start).count()
+    }
+
+    fn is_flapping(&self, config: &FlapConfig, now: i64) -> bool {
+        config.threshold > 0 && self.flip_count(config, now) > config.threshold
+    }
+}
+
+///
+/// 服务下各实例的状态变更历史与抖动检测
+#[derive(Debug, Default)]
+pub struct InstanceHealthHistory {
+    pub(crate) config: FlapConfig,
+    items: HashMap<InstanceShortKey, InstanceHistoryItem>,
+    last_clear_time: i64,
+}
+
+impl InstanceHealthHistory {
+    pub fn new(config: FlapConfig) -> Self {
+        Self {
+            config,
+            items: HashMap::new(),
+            last_clear_time: 0,
+        }
+    }
+
+    fn get_item(&mut self, key: &InstanceShortKey) -> &mut InstanceHistoryItem {
+        self.items.entry(key.clone()).or_default()
+    }
+
+    pub fn record(
+        &mut self,
+        key: &InstanceShortKey,
+        event_type: InstanceEventType,
+        reason: &str,
+        now: i64,
+    ) {
+        self.get_item(key).push(event_type, reason, now);
+    }
+
+    pub fn is_quarantined(&self, key: &InstanceShortKey, now: i64) -> bool {
+        self.items
+            .get(key)
+            .map(|e| e.quarantine_until > now)
+            .unwrap_or(false)
+    }
+
+    ///
+    /// 记录健康状态切换并检测抖动;
+    /// 返回false表示实例处于隔离期,本次恢复健康不生效
+    pub fn record_health_change(
+        &mut self,
+        key: &InstanceShortKey,
+        healthy: bool,
+        reason: &str,
+        now: i64,
+    ) -> bool {
+        let config = self.config;
+        let item = self.get_item(key);
+        if healthy && item.quarantine_until > now {
+            return false;
+        }
+        let was_flapping = item.is_flapping(&config, now);
+        item.flip_times.push_back(now);
+        while item.flip_times.len() > config.threshold + 1 {
+            item.flip_times.pop_front();
+        }
+        let flapping = item.is_flapping(&config, now);
+        if flapping && !was_flapping {
+            item.push(InstanceEventType::Flapping, REASON_FLAPPING, now);
+        }
+        if flapping && healthy && config.quarantine_millis > 0 {
+            item.quarantine_until = now + config.quarantine_millis;
+            item.push(InstanceEventType::Quarantined, REASON_FLAPPING, now);
+            return false;
+        }
+        let event_type = if healthy {
+            InstanceEventType::Healthy
+        } else {
+            InstanceEventType::Unhealthy
+        };
+        item.push(event_type, reason, now);
+        true
+    }
+
+    ///
+    /// 清理已下线且超过保留时间的实例历史,每分钟最多执行一次
+    pub fn clear_expired(
+        &mut self,
+        instances: &HashMap<InstanceShortKey, Arc<Instance>>,
+        now: i64,
+    ) {
+        if now - self.last_clear_time < CLEAR_INTERVAL_MILLIS {
+            return;
+        }
+        self.last_clear_time = now;
+        let expired_time = now - REMOVED_HISTORY_KEEP_MILLIS;
+        self.items
+            .retain(|key, item| instances.contains_key(key) || item.last_time > expired_time);
+    }
+
+    pub fn get_history_list(
+        &self,
+        instances: &HashMap<InstanceShortKey, Arc<Instance>>,
+        now: i64,
+    ) -> Vec<InstanceHistoryDto> {
+        let mut list: Vec<InstanceHistoryDto> = self
+            .items
+            .iter()
+            .map(|(key, item)| {
+                let instance = instances.get(key);
+                InstanceHistoryDto {
+                    ip: key.ip.clone(),
+                    port: key.port,
+                    online: instance.is_some(),
+                    healthy: instance.map(|e| e.healthy),
+                    enabled: instance.map(|e| e.enabled),
+                    flapping: item.is_flapping(&self.config, now),
+                    flip_count: item.flip_count(&self.config, now),
+                    quarantine_until: if item.quarantine_until > now {
+                        Some(item.quarantine_until)
+                    } else {
+                        None
+                    },
+                    events: item.events.iter().rev().cloned().collect(),
+                }
+            })
+            .collect();
+        list.sort_by(|a, b| a.ip.cmp(&b.ip).then(a.port.cmp(&b.port)));
+        list
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceHistoryDto {
+    pub ip: Arc<String>,
+    pub port: u32,
+    /// 实例当前是否在线
+    pub online: bool,
+    pub healthy: Option<bool>,
+    pub enabled: Option<bool>,
+    pub flapping: bool,
+    /// 检测窗口内健康状态切换次数
+    pub flip_count: usize,
+    pub quarantine_until: Option<i64>,
+    /// 按时间倒序
+    pub events: Vec<InstanceEvent>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flapping_quarantine() {
+        let mut history = InstanceHealthHistory::new(FlapConfig {
+            threshold: 3,
+            window_millis: 60_000,
+            quarantine_millis: 30_000,
+        });
+        let key = InstanceShortKey::new(Arc::new("127.0.0.1".to_owned()), 8080);
+        let mut now = 1_000_000;
+        assert!(history.record_health_change(&key, false, REASON_HEARTBEAT_TIMEOUT, now));
+        now += 1000;
+        assert!(history.record_health_change(&key, true, REASON_HEARTBEAT, now));
+        now += 1000;
+        assert!(history.record_health_change(&key, false, REASON_HEARTBEAT_TIMEOUT, now));
+        assert!(!history.is_quarantined(&key, now));
+        now += 1000;
+        // 第4次切换超过阈值,恢复健康被隔离
+        assert!(!history.record_health_change(&key, true, REASON_HEARTBEAT, now));
+        assert!(history.is_quarantined(&key, now));
+        assert!(!history.record_health_change(&key, true, REASON_HEARTBEAT, now + 1000));
+        let dto = &history.get_history_list(&HashMap::new(), now)[0];
+        assert!(dto.flapping);
+        assert_eq!(dto.events[0].event_type, InstanceEventType::Quarantined);
+        assert_eq!(dto.events[1].event_type, InstanceEventType::Flapping);
+        // 隔离结束后可以恢复
+        now += 30_000;
+        assert!(!history.is_quarantined(&key, now));
+        // 隔离结束后仍在窗口内,继续视为抖动并再次隔离
+        assert!(!history.record_health_change(&key, true, REASON_HEARTBEAT, now));
+        now += 120_000;
+        assert!(history.record_health_change(&key, true, REASON_HEARTBEAT, now));
+        assert!(!history.get_history_list(&HashMap::new(), now)[0].flapping);
+    }
+}
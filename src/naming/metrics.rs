@@ -1,7 +1,18 @@
+use crate::metrics::dump::{
+    entry_bytes, str_bytes, top_n_items, ActorDump, DebugDumpQuery, StructureSize,
+};
+use crate::metrics::labeled::MetricsLabels;
 use crate::metrics::metrics_key::MetricsKey;
-use crate::metrics::model::{MetricsItem, MetricsQuery, MetricsRecord};
+use crate::metrics::model::{
+    LabeledMetricsItem, LabeledMetricsQuery, MetricsItem, MetricsQuery, MetricsRecord,
+};
 use crate::naming::core::NamingActor;
+use crate::naming::model::{InstanceKey, ServiceKey};
+use crate::naming::service::Service;
 use actix::Handler;
+use std::collections::HashSet;
+use std::mem::size_of;
+use std::sync::Arc;
 
 impl Handler<MetricsQuery> for NamingActor {
     type Result = anyhow::Result<Vec<MetricsItem>>;
@@ -89,3 +100,86 @@ impl Handler<MetricsQuery> for NamingActor {
         Ok(list)
     }
 }
+
+impl Handler<LabeledMetricsQuery> for NamingActor {
+    type Result = anyhow::Result<Vec<LabeledMetricsItem>>;
+
+    fn handle(&mut self, _: LabeledMetricsQuery, _ctx: &mut Self::Context) -> Self::Result {
+        let mut list = vec![];
+        for (namespace_id, usage) in self.query_namespace_usage() {
+            let labels =
+                MetricsLabels::default().with("namespace", namespace_id.as_ref().to_owned());
+            list.push(LabeledMetricsItem::new(
+                MetricsKey::NamingNamespaceServiceSize,
+                labels.clone(),
+                MetricsRecord::Gauge(usage.service_count as f32),
+            ));
+            list.push(LabeledMetricsItem::new(
+                MetricsKey::NamingNamespaceInstanceSize,
+                labels,
+                MetricsRecord::Gauge(usage.instance_count as f32),
+            ));
+        }
+        Ok(list)
+    }
+}
+
+impl Handler<DebugDumpQuery> for NamingActor {
+    type Result = anyhow::Result<ActorDump>;
+
+    fn handle(&mut self, msg: DebugDumpQuery, _ctx: &mut Self::Context) -> Self::Result {
+        let mut service_bytes = 0;
+        let mut service_sizes = Vec::with_capacity(self.service_map.len());
+        for (key, service) in &self.service_map {
+            service_bytes += entry_bytes::<ServiceKey, Service>()
+                + str_bytes(&key.namespace_id)
+                + str_bytes(&key.group_name)
+                + str_bytes(&key.service_name)
+                + service.get_approx_bytes();
+            service_sizes.push((
+                service.instances.len(),
+                format!(
+                    "{}#{}#{}",
+                    &key.namespace_id, &key.group_name, &key.service_name
+                ),
+            ));
+        }
+        let client_instance_bytes = self
+            .client_instance_set
+            .keys()
+            .map(|e| str_bytes(e) + entry_bytes::<Arc<String>, HashSet<InstanceKey>>())
+            .sum::<usize>()
+            + self.get_client_instance_set_item_size() * size_of::<InstanceKey>();
+        let (group_size, service_size) = self.namespace_index.get_service_count();
+        let subscriber_key_size = self.subscriber.get_listener_key_size();
+        let subscriber_value_size =
+            self.subscriber.get_listener_value_size() + self.subscriber.get_client_value_size();
+        let structures = vec![
+            StructureSize::new("service_map", self.service_map.len(), service_bytes),
+            StructureSize::new(
+                "client_instance_set",
+                self.client_instance_set.len(),
+                client_instance_bytes,
+            ),
+            StructureSize::new(
+                "namespace_index",
+                service_size,
+                group_size * entry_bytes::<Arc<String>, HashSet<Arc<String>>>()
+                    + service_size * size_of::<Arc<String>>(),
+            ),
+            StructureSize::new(
+                "naming_subscriber",
+                subscriber_key_size,
+                subscriber_key_size * entry_bytes::<ServiceKey, HashSet<Arc<String>>>()
+                    + subscriber_value_size * entry_bytes::<Arc<String>, ServiceKey>(),
+            ),
+            StructureSize::new(
+                "instance_metadata_set",
+                self.instance_metadate_set.item_size(),
+                self.instance_metadate_set.item_size() * size_of::<InstanceKey>(),
+            ),
+        ];
+        Ok(ActorDump::new("NamingActor", &msg, structures)
+            .with_top_items(top_n_items(service_sizes.into_iter(), msg.top_n)))
+    }
+}
@@ -3,6 +3,7 @@ use std::collections::HashMap;
 pub mod api_model;
 pub mod core;
 pub(crate) mod filter;
+pub mod health_history;
 pub mod listener;
 pub mod model;
 pub mod naming_delay_nofity;
@@ -14,6 +15,7 @@ pub mod udp_actor;
 pub mod cluster;
 pub mod metrics;
 pub mod ops;
+pub mod prometheus_sd;
 pub mod service_index;
 
 #[cfg(feature = "debug")]
@@ -1,4 +1,5 @@
 use crate::naming::core::NamingActor;
+use crate::naming::health_history::REASON_DEBUG;
 use actix::prelude::*;
 
 #[derive(Debug, Message)]
@@ -28,7 +29,7 @@ impl NamingActor {
         self.disable_notify = true;
         for (service_key, short_key) in &keys {
             if let Some(service) = self.service_map.get_mut(service_key) {
-                service.update_instance_healthy_invalid(&short_key);
+                service.update_instance_healthy_invalid(&short_key, REASON_DEBUG);
             }
         }
         self.disable_notify = false;
@@ -46,7 +47,7 @@ impl NamingActor {
         self.disable_notify = true;
         for (service_key, short_key) in &keys {
             if let Some(service) = self.service_map.get_mut(service_key) {
-                service.remove_instance(&short_key, None);
+                service.remove_instance(&short_key, None, REASON_DEBUG);
             }
         }
         self.disable_notify = false;
@@ -63,7 +64,7 @@ impl NamingActor {
         }
         self.disable_notify = true;
         for (service_key, short_key) in &keys {
-            self.remove_instance(service_key, short_key, None);
+            self.remove_instance(service_key, short_key, None, REASON_DEBUG);
         }
         self.disable_notify = false;
     }
@@ -79,7 +80,7 @@ impl NamingActor {
         }
         self.disable_notify = true;
         for (service_key, short_key) in &keys {
-            self.remove_instance(service_key, short_key, None);
+            self.remove_instance(service_key, short_key, None, REASON_DEBUG);
         }
         self.disable_notify = false;
     }
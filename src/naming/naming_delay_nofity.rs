@@ -1,6 +1,10 @@
 #![allow(unused_imports)]
 
-use std::{collections::HashSet, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 
 use actix::prelude::*;
 use bean_factory::{bean, Inject};
@@ -9,6 +13,10 @@ use crate::{
     common::delay_notify::{DelayNotify, NotifyEvent},
     grpc::bistream_manage::{BiStreamManage, BiStreamManageCmd},
     now_millis,
+    webhook::{
+        core::WebhookManager,
+        model::{WebhookEvent, WebhookEventType, WebhookReq},
+    },
 };
 
 use super::{
@@ -26,6 +34,9 @@ pub struct NamingDelayEvent {
 
 impl NotifyEvent for NamingDelayEvent {
     fn on_event(self) -> anyhow::Result<()> {
+        if self.client_id_set.is_empty() {
+            return Ok(());
+        }
         if let (Some(conn_manage), Some(service_info)) =
             (self.conn_manage.as_ref(), self.service_info)
         {
@@ -51,6 +62,9 @@ pub struct DelayNotifyActor {
     inner_delay_notify: DelayNotify<ServiceKey, NamingDelayEvent>,
     conn_manage: Option<Addr<BiStreamManage>>,
     naming_addr: Option<Addr<NamingActor>>,
+    webhook_manager: Option<Addr<WebhookManager>>,
+    /// 最近一次通知时各服务的健康实例,用于计算实例上下线
+    healthy_instances: HashMap<ServiceKey, HashSet<(Arc<String>, u32)>>,
     delay: u64,
 }
 
@@ -66,6 +80,8 @@ impl DelayNotifyActor {
             inner_delay_notify: Default::default(),
             conn_manage: None,
             naming_addr: None,
+            webhook_manager: None,
+            healthy_instances: Default::default(),
             delay: 500,
         }
     }
@@ -74,21 +90,24 @@ impl DelayNotifyActor {
         ctx.run_later(Duration::from_millis(500), |act, ctx| {
             let events = act.inner_delay_notify.timeout().unwrap_or_default();
             let naming_addr = act.naming_addr.clone();
-            async move {
-                Self::fill_event_data_and_notify(naming_addr, events).await;
-            }
-            .into_actor(act)
-            .map(|_, act, ctx| {
-                act.notify_heartbeat(ctx);
-            })
-            .wait(ctx);
+            async move { Self::fill_event_data(naming_addr, events).await }
+                .into_actor(act)
+                .map(|events, act, ctx| {
+                    for event in events {
+                        act.notify_webhook(&event);
+                        event.on_event().ok();
+                    }
+                    act.notify_heartbeat(ctx);
+                })
+                .wait(ctx);
         });
     }
 
-    async fn fill_event_data_and_notify(
+    async fn fill_event_data(
         naming_addr: Option<Addr<NamingActor>>,
         events: Vec<NamingDelayEvent>,
-    ) {
+    ) -> Vec<NamingDelayEvent> {
+        let mut list = Vec::with_capacity(events.len());
         if let Some(naming_addr) = naming_addr {
             for mut event in events {
                 //println!("fill_event_data_and_notify, {:?}",&event.key);
@@ -105,9 +124,59 @@ impl DelayNotifyActor {
                         log::error!("fill_event_data_and_notify error");
                     }
                 };
-                event.on_event().ok();
+                list.push(event);
             }
         }
+        list
+    }
+
+    ///
+    /// 对比上次通知时的健康实例,触发实例上下线及服务无健康实例的webhook事件
+    fn notify_webhook(&mut self, event: &NamingDelayEvent) {
+        let (webhook_manager, service_info) =
+            match (self.webhook_manager.as_ref(), event.service_info.as_ref()) {
+                (Some(webhook_manager), Some(service_info)) => (webhook_manager, service_info),
+                _ => return,
+            };
+        let current: HashSet<(Arc<String>, u32)> = service_info
+            .hosts
+            .as_ref()
+            .map(|hosts| {
+                hosts
+                    .iter()
+                    .filter(|e| e.healthy && e.enabled)
+                    .map(|e| (e.ip.clone(), e.port))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let previous = if current.is_empty() {
+            self.healthy_instances.remove(&event.key)
+        } else {
+            self.healthy_instances
+                .insert(event.key.clone(), current.clone())
+        }
+        .unwrap_or_default();
+        for item in current.difference(&previous) {
+            webhook_manager.do_send(WebhookReq::Fire(WebhookEvent::new_naming_event(
+                WebhookEventType::InstanceUp,
+                &event.key,
+                Some(item.clone()),
+            )));
+        }
+        for item in previous.difference(&current) {
+            webhook_manager.do_send(WebhookReq::Fire(WebhookEvent::new_naming_event(
+                WebhookEventType::InstanceDown,
+                &event.key,
+                Some(item.clone()),
+            )));
+        }
+        if current.is_empty() && !previous.is_empty() {
+            webhook_manager.do_send(WebhookReq::Fire(WebhookEvent::new_naming_event(
+                WebhookEventType::ServiceEmpty,
+                &event.key,
+                None,
+            )));
+        }
     }
 }
 
@@ -131,6 +200,7 @@ impl Inject for DelayNotifyActor {
     ) {
         self.conn_manage = factory_data.get_actor();
         self.naming_addr = factory_data.get_actor();
+        self.webhook_manager = factory_data.get_actor();
         log::info!(" DelayNotifyActor inject complete");
     }
 }
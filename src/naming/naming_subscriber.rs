@@ -154,13 +154,21 @@ impl Subscriber {
     pub fn notify(&self, key: ServiceKey) {
         //log::info!("naming_subscriber notify {:?}",&key);
         if let Some(notify_addr) = &self.notify_addr {
-            if let Some(set) = self.listener.get(&key) {
-                let mut client_id_set = HashSet::new();
-                for item in set.keys() {
-                    client_id_set.insert(item.clone());
-                }
-                notify_addr.do_send(DelayNotifyCmd::Notify(key, client_id_set));
-            }
+            // 没有订阅者也要通知,用于判断实例上下线并触发webhook
+            let client_id_set: HashSet<Arc<String>> = self
+                .listener
+                .get(&key)
+                .map(|set| set.keys().cloned().collect())
+                .unwrap_or_default();
+            notify_addr.do_send(DelayNotifyCmd::Notify(key, client_id_set));
+        }
+    }
+
+    pub fn get_client_keys(&self, client_id: &Arc<String>) -> Vec<ServiceKey> {
+        if let Some(set) = self.client_keys.get(client_id) {
+            set.iter().cloned().collect()
+        } else {
+            vec![]
         }
     }
 
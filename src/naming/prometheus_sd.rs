@@ -0,0 +1,163 @@
+use crate::naming::model::{Instance, ServiceKey};
+use crate::naming::NamingUtils;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+pub const METRICS_PATH_KEY: &str = "metrics_path";
+const LABEL_PREFIX: &str = "__meta_rnacos_";
+const METRICS_PATH_LABEL: &str = "__metrics_path__";
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrometheusSdParam {
+    pub namespace_id: Option<String>,
+    pub group_name: Option<String>,
+    /// 只返回包含该元数据的实例,默认为metrics_path
+    pub metadata_key: Option<String>,
+    pub healthy_only: Option<bool>,
+}
+
+impl PrometheusSdParam {
+    pub fn get_namespace_id(&self) -> Option<String> {
+        self.namespace_id
+            .as_ref()
+            .filter(|e| !e.is_empty())
+            .map(|e| NamingUtils::default_namespace(e.to_owned()))
+    }
+
+    pub fn get_metadata_key(&self) -> &str {
+        match &self.metadata_key {
+            Some(v) if !v.is_empty() => v.as_str(),
+            _ => METRICS_PATH_KEY,
+        }
+    }
+
+    pub fn match_service(&self, namespace_id: Option<&str>, key: &ServiceKey) -> bool {
+        if let Some(namespace_id) = namespace_id {
+            if key.namespace_id.as_str() != namespace_id {
+                return false;
+            }
+        }
+        if let Some(group_name) = self.group_name.as_ref().filter(|e| !e.is_empty()) {
+            if key.group_name.as_str() != group_name {
+                return false;
+            }
+        }
+        true
+    }
+
+    pub fn match_instance(&self, instance: &Instance) -> bool {
+        instance.enabled
+            && (instance.healthy || !self.healthy_only.unwrap_or(false))
+            && instance.metadata.contains_key(self.get_metadata_key())
+    }
+}
+
+///
+/// prometheus http_sd_config的目标组
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PrometheusTargetGroup {
+    pub targets: Vec<String>,
+    pub labels: BTreeMap<String, String>,
+}
+
+///
+/// 标签名只能包含字母、数字与下划线
+fn to_label_name(key: &str) -> String {
+    key.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+impl PrometheusTargetGroup {
+    ///
+    /// 每个实例的元数据不同,一个实例对应一个目标组
+    pub fn from_instance(key: &ServiceKey, instance: &Instance) -> Self {
+        let mut labels = BTreeMap::new();
+        labels.insert(
+            format!("{}namespace", LABEL_PREFIX),
+            key.namespace_id.as_ref().to_owned(),
+        );
+        labels.insert(
+            format!("{}group", LABEL_PREFIX),
+            key.group_name.as_ref().to_owned(),
+        );
+        labels.insert(
+            format!("{}service", LABEL_PREFIX),
+            key.service_name.as_ref().to_owned(),
+        );
+        labels.insert(
+            format!("{}cluster", LABEL_PREFIX),
+            instance.cluster_name.to_owned(),
+        );
+        labels.insert(
+            format!("{}healthy", LABEL_PREFIX),
+            instance.healthy.to_string(),
+        );
+        for (k, v) in instance.metadata.iter() {
+            labels.insert(
+                format!("{}metadata_{}", LABEL_PREFIX, to_label_name(k)),
+                v.to_owned(),
+            );
+        }
+        if let Some(path) = instance.metadata.get(METRICS_PATH_KEY) {
+            labels.insert(METRICS_PATH_LABEL.to_owned(), path.to_owned());
+        }
+        Self {
+            targets: vec![format!("{}:{}", &instance.ip, instance.port)],
+            labels,
+        }
+    }
+}
+
+pub fn build_target_groups<'a, I>(
+    param: &PrometheusSdParam,
+    services: I,
+) -> Vec<PrometheusTargetGroup>
+where
+    I: Iterator<Item = (&'a ServiceKey, Vec<Arc<Instance>>)>,
+{
+    let mut list = vec![];
+    for (key, instances) in services {
+        for instance in instances {
+            if param.match_instance(&instance) {
+                list.push(PrometheusTargetGroup::from_instance(key, &instance));
+            }
+        }
+    }
+    list
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn build_prometheus_target_group() {
+        let key = ServiceKey::new("dev", "DEFAULT_GROUP", "foo");
+        let mut instance = Instance::new("10.0.0.1".to_owned(), 8080);
+        instance.enabled = true;
+        instance.healthy = true;
+        instance.cluster_name = "DEFAULT".to_owned();
+        let mut metadata = HashMap::new();
+        metadata.insert("metrics_path".to_owned(), "/actuator/prometheus".to_owned());
+        metadata.insert("app.version".to_owned(), "1.0".to_owned());
+        instance.metadata = Arc::new(metadata);
+        let other = Arc::new(Instance::new("10.0.0.2".to_owned(), 8080));
+        let param = PrometheusSdParam::default();
+        let list = build_target_groups(
+            &param,
+            vec![(&key, vec![Arc::new(instance), other])].into_iter(),
+        );
+        assert_eq!(list.len(), 1);
+        let group = &list[0];
+        assert_eq!(group.targets, vec!["10.0.0.1:8080".to_owned()]);
+        assert_eq!(group.labels["__meta_rnacos_namespace"], "dev");
+        assert_eq!(group.labels["__meta_rnacos_metadata_app_version"], "1.0");
+        assert_eq!(group.labels["__metrics_path__"], "/actuator/prometheus");
+        assert!(param.match_service(Some("dev"), &key));
+        assert!(!param.match_service(Some("public"), &key));
+    }
+}
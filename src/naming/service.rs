@@ -8,9 +8,15 @@ use super::{
     },
 };
 use crate::common::constant::EMPTY_ARC_STRING;
+use crate::metrics::dump::{entry_bytes, str_bytes};
 use crate::naming::cluster::model::ProcessRange;
+use crate::naming::health_history::{
+    InstanceEventType, InstanceHealthHistory, InstanceHistoryDto, REASON_CLUSTER_SYNC,
+    REASON_HEARTBEAT, REASON_HEARTBEAT_TIMEOUT, REASON_INSTANCE_TIMEOUT, REASON_PROBE_SUCCESS,
+    REASON_REGISTER, REASON_UPDATE,
+};
 use crate::naming::model::UpdatePerpetualType;
-use crate::now_millis;
+use crate::{now_millis, now_millis_i64};
 use actix_web::rt;
 use inner_mem_cache::TimeoutSet;
 use rand::prelude::IteratorRandom;
@@ -52,6 +58,8 @@ pub struct Service {
     /// 不健康状态过期记录，过期后反实例删除
     pub(crate) unhealthy_timeout_set: TimeoutSet<InstanceShortKey>,
     pub(crate) perpetual_host_set: HashSet<InstanceShortKey>,
+    /// 实例状态变更历史与抖动检测
+    pub(crate) health_history: InstanceHealthHistory,
 }
 
 impl Service {
@@ -85,6 +93,14 @@ impl Service {
         let mut rtype = UpdateInstanceType::None;
         let mut perpetua_type = UpdatePerpetualType::None;
         let short_key = instance.get_short_key();
+        let now = now_millis_i64();
+        let health_reason = if from_sync {
+            REASON_CLUSTER_SYNC
+        } else if update_tag.as_ref().map(|e| e.from_update).unwrap_or(false) {
+            REASON_UPDATE
+        } else {
+            REASON_HEARTBEAT
+        };
         let old_instance = self.instances.get(&key);
         let mut replace_old_client_id = None;
         let mut mark_add_perpetual_instance = false;
@@ -119,6 +135,18 @@ impl Service {
             if !old_instance.client_id.is_empty() && instance.client_id != old_instance.client_id {
                 replace_old_client_id = Some(old_instance.client_id.clone());
             }
+            if old_instance.healthy != instance.healthy
+                && !self.health_history.record_health_change(
+                    &short_key,
+                    instance.healthy,
+                    health_reason,
+                    now,
+                )
+                && !from_sync
+            {
+                // 抖动隔离期内保持不健康
+                instance.healthy = false;
+            }
             if !old_instance.healthy && instance.healthy {
                 self.healthy_instance_size += 1;
                 #[cfg(feature = "debug")]
@@ -172,6 +200,19 @@ impl Service {
                     rtype = UpdateInstanceType::UpdateTime;
                 }
             }
+            if old_instance.enabled != instance.enabled {
+                let event_type = if instance.enabled {
+                    InstanceEventType::Enabled
+                } else {
+                    InstanceEventType::Disabled
+                };
+                let reason = if from_sync {
+                    REASON_CLUSTER_SYNC
+                } else {
+                    REASON_UPDATE
+                };
+                self.health_history.record(&key, event_type, reason, now);
+            }
             mark_add_perpetual_instance = !instance.ephemeral && old_instance.ephemeral;
             mark_remove_perpetual_instance = instance.ephemeral && !old_instance.ephemeral;
         } else {
@@ -181,6 +222,17 @@ impl Service {
             if let Some(priority_metadata) = self.instance_metadata_map.get(&short_key) {
                 instance.metadata = priority_metadata.clone();
             }
+            if instance.healthy && !from_sync && self.health_history.is_quarantined(&short_key, now)
+            {
+                instance.healthy = false;
+            }
+            let reason = if from_sync {
+                REASON_CLUSTER_SYNC
+            } else {
+                REASON_REGISTER
+            };
+            self.health_history
+                .record(&short_key, InstanceEventType::Registered, reason, now);
             self.instance_size += 1;
             if instance.healthy {
                 self.healthy_instance_size += 1;
@@ -258,7 +310,7 @@ impl Service {
                     continue;
                 }
             }
-            self.remove_instance(&key, None);
+            self.remove_instance(&key, None, REASON_INSTANCE_TIMEOUT);
             remove_list.push(key);
         }
         let mut update_list = vec![];
@@ -268,9 +320,11 @@ impl Service {
                     continue;
                 }
             }
-            self.update_instance_healthy_invalid(&key);
+            self.update_instance_healthy_invalid(&key, REASON_HEARTBEAT_TIMEOUT);
             update_list.push(key);
         }
+        self.health_history
+            .clear_expired(&self.instances, now_millis_i64());
         (remove_list, update_list)
     }
 
@@ -278,6 +332,7 @@ impl Service {
         &mut self,
         instance_key: &InstanceShortKey,
         client_id: Option<&Arc<String>>,
+        reason: &str,
     ) -> Option<Arc<Instance>> {
         #[cfg(feature = "debug")]
         log::info!(
@@ -297,6 +352,12 @@ impl Service {
             }
         }
         if let Some(old) = self.instances.remove(instance_key) {
+            self.health_history.record(
+                instance_key,
+                InstanceEventType::Deregistered,
+                reason,
+                now_millis_i64(),
+            );
             if !old.ephemeral {
                 // 删除永久实例
                 self.perpetual_host_set.remove(instance_key);
@@ -314,7 +375,11 @@ impl Service {
         }
     }
 
-    pub(crate) fn update_instance_healthy_invalid(&mut self, instance_id: &InstanceShortKey) {
+    pub(crate) fn update_instance_healthy_invalid(
+        &mut self,
+        instance_id: &InstanceShortKey,
+        reason: &str,
+    ) {
         if let Some(i) = self.instances.remove(instance_id) {
             if i.healthy {
                 self.healthy_instance_size -= 1;
@@ -322,6 +387,8 @@ impl Service {
                 self.instances.insert(instance_id.clone(), i);
                 return;
             }
+            self.health_history
+                .record_health_change(instance_id, false, reason, now_millis_i64());
             let mut i = i.as_ref().clone();
             i.healthy = false;
             self.unhealthy_timeout_set
@@ -335,7 +402,15 @@ impl Service {
         instance_id: &InstanceShortKey,
     ) {
         if let Some(i) = self.instances.remove(instance_id) {
-            if !i.healthy && !i.ephemeral {
+            if !i.healthy
+                && !i.ephemeral
+                && self.health_history.record_health_change(
+                    instance_id,
+                    true,
+                    REASON_PROBE_SUCCESS,
+                    now_millis_i64(),
+                )
+            {
                 self.healthy_instance_size += 1;
             } else {
                 self.instances.insert(instance_id.clone(), i);
@@ -454,6 +529,11 @@ impl Service {
         self.instance_metadata_map.contains_key(instance_key)
     }
 
+    pub(crate) fn get_instance_history(&self) -> Vec<InstanceHistoryDto> {
+        self.health_history
+            .get_history_list(&self.instances, now_millis_i64())
+    }
+
     pub(crate) fn get_healthy_timeout_set_item_size(&self) -> usize {
         self.healthy_timeout_set.item_size()
     }
@@ -461,6 +541,45 @@ impl Service {
     pub(crate) fn get_unhealthy_timeout_set_item_size(&self) -> usize {
         self.unhealthy_timeout_set.item_size()
     }
+
+    ///
+    /// 估算服务及其实例占用的内存
+    pub(crate) fn get_approx_bytes(&self) -> usize {
+        let metadata_bytes = |metadata: &HashMap<String, String>| -> usize {
+            metadata
+                .iter()
+                .map(|(k, v)| str_bytes(k) + str_bytes(v))
+                .sum::<usize>()
+        };
+        let instance_bytes: usize = self
+            .instances
+            .values()
+            .map(|e| {
+                entry_bytes::<InstanceShortKey, Arc<Instance>>()
+                    + std::mem::size_of::<Instance>()
+                    + str_bytes(&e.id)
+                    + str_bytes(&e.ip)
+                    + e.cluster_name.len()
+                    + e.app_name.len()
+                    + str_bytes(&e.client_id)
+                    + metadata_bytes(&e.metadata)
+            })
+            .sum();
+        let instance_metadata_bytes: usize = self
+            .instance_metadata_map
+            .values()
+            .map(|e| entry_bytes::<InstanceShortKey, InstanceMetaData>() + metadata_bytes(e))
+            .sum();
+        let timeout_set_bytes = (self.get_healthy_timeout_set_item_size()
+            + self.get_unhealthy_timeout_set_item_size()
+            + self.perpetual_host_set.len())
+            * std::mem::size_of::<InstanceShortKey>();
+        std::mem::size_of::<Service>()
+            + metadata_bytes(&self.metadata)
+            + instance_bytes
+            + instance_metadata_bytes
+            + timeout_set_bytes
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -1,10 +1,12 @@
 use crate::oauth2::model::actor_model::{OAuth2MsgReq, OAuth2MsgResult};
 use crate::oauth2::model::OAuth2Config;
 use crate::oauth2::oauth2_msg_actor::OAuth2MsgActor;
+use crate::oauth2::oidc::OidcMetadata;
 use crate::user::UserManager;
 use actix::prelude::*;
 use bean_factory::{bean, BeanFactory, FactoryData, Inject};
 use std::sync::Arc;
+use std::time::Duration;
 
 #[bean(inject)]
 pub struct OAuth2Manager {
@@ -24,11 +26,39 @@ impl OAuth2Manager {
         }
     }
 
-    pub fn init(&mut self, _ctx: &mut Context<Self>) {
+    pub fn init(&mut self, ctx: &mut Context<Self>) {
         if !self.enable_oauth2 {
             return;
         }
-        let oauth2_config = self.oauth2_config.clone();
+        if self.oauth2_config.is_oidc() {
+            self.init_by_oidc_discovery(ctx);
+            return;
+        }
+        self.start_msg_actor(self.oauth2_config.clone());
+    }
+
+    fn init_by_oidc_discovery(&mut self, ctx: &mut Context<Self>) {
+        let issuer = self.oauth2_config.oauth2_oidc_issuer.clone();
+        async move { OidcMetadata::discover(&reqwest::Client::new(), &issuer).await }
+            .into_actor(self)
+            .map(|res, act, ctx| match res {
+                Ok(metadata) => {
+                    log::info!("OIDC discovery success, issuer:{}", &metadata.issuer);
+                    let config = Arc::new(act.oauth2_config.apply_oidc_metadata(metadata));
+                    act.oauth2_config = config.clone();
+                    act.start_msg_actor(config);
+                }
+                Err(err) => {
+                    log::error!("OIDC discovery error:{}, retry after 10s", err);
+                    ctx.run_later(Duration::from_secs(10), |act, ctx| {
+                        act.init_by_oidc_discovery(ctx);
+                    });
+                }
+            })
+            .spawn(ctx);
+    }
+
+    fn start_msg_actor(&mut self, oauth2_config: Arc<OAuth2Config>) {
         let user_manager_addr = self.user_manager_addr.clone();
         match OAuth2MsgActor::new(oauth2_config, user_manager_addr) {
             Ok(actor) => {
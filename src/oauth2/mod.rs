@@ -1,3 +1,4 @@
 pub mod core;
 pub mod model;
 mod oauth2_msg_actor;
+pub mod oidc;
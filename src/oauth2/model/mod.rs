@@ -1,4 +1,7 @@
 use crate::common::model::privilege::PrivilegeGroup;
+use crate::oauth2::oidc::OidcMetadata;
+use crate::user::permission;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 pub mod actor_model;
@@ -16,6 +19,96 @@ pub struct OAuth2Config {
     pub oauth2_username_claim_name: Arc<String>,
     pub oauth2_nickname_claim_name: Arc<String>,
     pub oauth2_user_default_role: Arc<String>,
+    /// OIDC issuer,设置后通过discovery获取端点并校验id_token
+    pub oauth2_oidc_issuer: Arc<String>,
+    pub oauth2_jwks_url: Arc<String>,
+    pub oauth2_groups_claim_name: Arc<String>,
+    pub oauth2_developer_groups: Arc<HashSet<String>>,
+    pub oauth2_admin_groups: Arc<HashSet<String>>,
+    /// 用户组 -> 可访问的命名空间白名单
+    pub oauth2_group_namespaces: Arc<HashMap<String, Vec<Arc<String>>>>,
+}
+
+impl OAuth2Config {
+    ///
+    /// 解析用户组与命名空间映射,格式: group1:ns1|ns2,group2:ns3
+    pub fn parse_group_namespaces(input: &str) -> HashMap<String, Vec<Arc<String>>> {
+        let mut map = HashMap::new();
+        for item in input.split(',') {
+            if let Some((group, namespaces)) = item.rsplit_once(':') {
+                let group = group.trim().trim_start_matches('/');
+                if group.is_empty() {
+                    continue;
+                }
+                let namespaces: Vec<Arc<String>> = namespaces
+                    .split('|')
+                    .map(|s| s.trim())
+                    .filter(|s| !s.is_empty())
+                    .map(|s| Arc::new(s.to_owned()))
+                    .collect();
+                map.insert(group.to_owned(), namespaces);
+            }
+        }
+        map
+    }
+
+    pub fn is_oidc(&self) -> bool {
+        !self.oauth2_oidc_issuer.is_empty()
+    }
+
+    /// 是否配置了用户组映射;配置后每次登录都按用户组重新同步角色与命名空间
+    pub fn has_group_mapping(&self) -> bool {
+        !self.oauth2_developer_groups.is_empty()
+            || !self.oauth2_admin_groups.is_empty()
+            || !self.oauth2_group_namespaces.is_empty()
+    }
+
+    /// 用discovery结果补全未显式配置的端点
+    pub fn apply_oidc_metadata(&self, metadata: OidcMetadata) -> Self {
+        let mut config = self.clone();
+        if config.oauth2_authorization_url.is_empty() {
+            config.oauth2_authorization_url = Arc::new(metadata.authorization_endpoint);
+        }
+        if config.oauth2_token_url.is_empty() {
+            config.oauth2_token_url = Arc::new(metadata.token_endpoint);
+        }
+        if config.oauth2_userinfo_url.is_empty() {
+            if let Some(v) = metadata.userinfo_endpoint {
+                config.oauth2_userinfo_url = Arc::new(v);
+            }
+        }
+        config.oauth2_oidc_issuer = Arc::new(metadata.issuer);
+        config.oauth2_jwks_url = Arc::new(metadata.jwks_uri);
+        config
+    }
+
+    /// 按用户组计算角色,管理员组优先于开发者组,都不匹配时使用默认角色
+    pub fn get_role_by_groups(&self, groups: &[String]) -> Arc<String> {
+        if groups.iter().any(|g| self.oauth2_admin_groups.contains(g)) {
+            permission::USER_ROLE_MANAGER.clone()
+        } else if groups
+            .iter()
+            .any(|g| self.oauth2_developer_groups.contains(g))
+        {
+            permission::USER_ROLE_DEVELOPER.clone()
+        } else {
+            self.oauth2_user_default_role.clone()
+        }
+    }
+
+    /// 按用户组计算命名空间白名单,未配置命名空间映射时返回None
+    pub fn get_namespaces_by_groups(&self, groups: &[String]) -> Option<HashSet<Arc<String>>> {
+        if self.oauth2_group_namespaces.is_empty() {
+            return None;
+        }
+        let mut namespaces = HashSet::new();
+        for group in groups {
+            if let Some(list) = self.oauth2_group_namespaces.get(group) {
+                namespaces.extend(list.iter().cloned());
+            }
+        }
+        Some(namespaces)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -44,3 +137,37 @@ impl OAuth2UserMeta {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_mapping() {
+        let config = OAuth2Config {
+            oauth2_user_default_role: permission::USER_ROLE_VISITOR.clone(),
+            oauth2_developer_groups: Arc::new(HashSet::from(["dev".to_owned()])),
+            oauth2_admin_groups: Arc::new(HashSet::from(["rnacos-admin".to_owned()])),
+            oauth2_group_namespaces: Arc::new(OAuth2Config::parse_group_namespaces(
+                "/team-a:dev|test, team-b:prod,bad",
+            )),
+            ..Default::default()
+        };
+        let groups = vec!["dev".to_owned(), "team-a".to_owned()];
+        assert_eq!(
+            config.get_role_by_groups(&groups),
+            permission::USER_ROLE_DEVELOPER.clone()
+        );
+        assert_eq!(
+            config.get_role_by_groups(&["rnacos-admin".to_owned(), "dev".to_owned()]),
+            permission::USER_ROLE_MANAGER.clone()
+        );
+        assert_eq!(
+            config.get_role_by_groups(&[]),
+            permission::USER_ROLE_VISITOR.clone()
+        );
+        let namespaces = config.get_namespaces_by_groups(&groups).unwrap();
+        assert_eq!(namespaces.len(), 2);
+        assert!(namespaces.contains(&Arc::new("test".to_owned())));
+    }
+}
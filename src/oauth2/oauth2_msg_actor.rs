@@ -1,15 +1,17 @@
 use crate::common::get_app_version;
+use crate::common::model::privilege::{PrivilegeGroup, PrivilegeGroupOptionParam};
 use crate::oauth2::model::actor_model::{OAuth2MsgReq, OAuth2MsgResult};
 use crate::oauth2::model::OAuth2Config;
+use crate::oauth2::oidc::{get_claim, get_claim_groups, IdTokenVerifier, OidcClient};
 use crate::user::model::{UserDto, UserSourceType};
 use crate::user::{UserManager, UserManagerReq, UserManagerResult};
 use actix::prelude::*;
 use oauth2::{
-    basic::BasicClient, reqwest::async_http_client, AuthorizationCode, ClientId, ClientSecret,
-    RedirectUrl, Scope, TokenResponse,
+    reqwest::async_http_client, AuthorizationCode, ClientId, ClientSecret, RedirectUrl, Scope,
+    TokenResponse,
 };
 use reqwest::Client;
-use serde_json::Value;
+use serde_json::{Map, Value};
 use std::sync::Arc;
 
 #[derive(Clone)]
@@ -17,8 +19,9 @@ pub struct OAuth2MsgActor {
     oauth2_config: Arc<OAuth2Config>,
     user_manager_addr: Option<Addr<UserManager>>,
     http_client: Client,
-    oauth2_client: BasicClient,
+    oauth2_client: OidcClient,
     scopes: Vec<Scope>,
+    id_token_verifier: Option<IdTokenVerifier>,
 }
 
 impl OAuth2MsgActor {
@@ -34,7 +37,7 @@ impl OAuth2MsgActor {
 
         let redirect_url = RedirectUrl::new(oauth2_config.oauth2_redirect_uri.as_ref().clone())?;
 
-        let oauth2_client = BasicClient::new(
+        let oauth2_client = OidcClient::new(
             client_id,
             Some(client_secret),
             oauth2::AuthUrl::new(auth_url)?,
@@ -49,9 +52,20 @@ impl OAuth2MsgActor {
             .map(|s| Scope::new(s.to_string()))
             .collect();
 
+        let id_token_verifier = if oauth2_config.oauth2_jwks_url.is_empty() {
+            None
+        } else {
+            Some(IdTokenVerifier::new(
+                oauth2_config.oauth2_oidc_issuer.clone(),
+                oauth2_config.oauth2_client_id.clone(),
+                oauth2_config.oauth2_jwks_url.clone(),
+            ))
+        };
+
         Ok(Self {
             oauth2_config,
             user_manager_addr,
+            id_token_verifier,
             http_client: Client::builder()
                 .danger_accept_invalid_certs(true)
                 .build()
@@ -61,6 +75,124 @@ impl OAuth2MsgActor {
         })
     }
 
+    async fn query_userinfo(
+        &self,
+        userinfo_url: &str,
+        access_token: &str,
+    ) -> anyhow::Result<Map<String, Value>> {
+        // Build request with bearer token
+        // Use bearer_auth which sets: Authorization: Bearer <token>
+        // GitHub and some OAuth2 providers require User-Agent header
+        let user_agent = format!("r-nacos/{}", get_app_version());
+        let request = self
+            .http_client
+            .get(userinfo_url)
+            .bearer_auth(access_token)
+            .header("Accept", "application/json")
+            .header("User-Agent", user_agent);
+
+        let userinfo_response = request.send().await?;
+
+        // Check response status
+        let status = userinfo_response.status();
+        if !status.is_success() {
+            let error_text = userinfo_response.text().await.unwrap_or_default();
+            log::error!(
+                "OAuth2 userinfo request failed: status={}, url={}, body={}",
+                status,
+                userinfo_url,
+                error_text
+            );
+            return Err(anyhow::anyhow!(
+                "OAuth2 userinfo request failed: status={}, body={}",
+                status,
+                error_text
+            ));
+        }
+
+        // Parse JSON directly
+        let userinfo: Value = userinfo_response.json().await.map_err(|e| {
+            log::error!("OAuth2 userinfo JSON parse error: {}", e);
+            anyhow::anyhow!("Failed to parse OAuth2 userinfo response: {}", e)
+        })?;
+
+        // Log the complete userinfo response for debugging
+        log::info!(
+            "OAuth2 userinfo response: {}",
+            serde_json::to_string_pretty(&userinfo)
+                .unwrap_or_else(|_| "Failed to serialize".to_string())
+        );
+        match userinfo {
+            Value::Object(map) => Ok(map),
+            _ => Err(anyhow::anyhow!(
+                "OAuth2 userinfo response is not a json object"
+            )),
+        }
+    }
+
+    ///
+    /// 初始化用户;配置了用户组映射时,每次登录按用户组重新同步角色与命名空间白名单
+    async fn sync_user(
+        &self,
+        user_name: String,
+        nickname: String,
+        role: Arc<String>,
+        groups: &[String],
+    ) -> Option<PrivilegeGroup<Arc<String>>> {
+        let user_manager_addr = self.user_manager_addr.as_ref()?;
+        let username = Arc::new(user_name);
+        let mut namespace_privilege = None;
+        let user = UserDto {
+            username: username.clone(),
+            nickname: Some(nickname),
+            source: Some(UserSourceType::Inner.to_str().to_owned()),
+            roles: Some(vec![role.clone()]),
+            ..Default::default()
+        };
+        if let Ok(Ok(UserManagerResult::QueryUser(Some(user_dto)))) = user_manager_addr
+            .send(UserManagerReq::InitUser {
+                user,
+                namespace_privilege_param: None,
+            })
+            .await
+        {
+            namespace_privilege = user_dto.namespace_privilege;
+        }
+        if !self.oauth2_config.has_group_mapping() {
+            return namespace_privilege;
+        }
+        let namespace_privilege_param =
+            self.oauth2_config
+                .get_namespaces_by_groups(groups)
+                .map(|namespaces| PrivilegeGroupOptionParam {
+                    whitelist_is_all: Some(false),
+                    whitelist: Some(Arc::new(namespaces)),
+                    blacklist_is_all: None,
+                    blacklist: None,
+                });
+        let user = UserDto {
+            username: username.clone(),
+            roles: Some(vec![role]),
+            ..Default::default()
+        };
+        if let Err(e) = user_manager_addr
+            .send(UserManagerReq::UpdateUser {
+                user,
+                namespace_privilege_param,
+            })
+            .await
+        {
+            log::error!("OAuth2 sync user error: {}", e);
+        }
+        if let Ok(Ok(UserManagerResult::QueryUser(Some(user_dto)))) = user_manager_addr
+            .send(UserManagerReq::Query { name: username })
+            .await
+        {
+            namespace_privilege = user_dto.namespace_privilege;
+        }
+        namespace_privilege
+    }
+
     async fn handle_req(&self, msg: OAuth2MsgReq) -> anyhow::Result<OAuth2MsgResult> {
         match msg {
             OAuth2MsgReq::GetAuthorizeUrl => {
@@ -86,56 +218,34 @@ impl OAuth2MsgActor {
 
                 let access_token = token.access_token().secret();
 
-                // Get user info - use full URL directly
-                let userinfo_url = self.oauth2_config.oauth2_userinfo_url.as_ref().clone();
-
-                // Build request with bearer token
-                // Use bearer_auth which sets: Authorization: Bearer <token>
-                // GitHub and some OAuth2 providers require User-Agent header
-                let user_agent = format!("r-nacos/{}", get_app_version());
-                let request = self
-                    .http_client
-                    .get(&userinfo_url)
-                    .bearer_auth(access_token.clone())
-                    .header("Accept", "application/json")
-                    .header("User-Agent", user_agent);
-
-                let userinfo_response = request.send().await?;
-
-                // Check response status
-                let status = userinfo_response.status();
-                if !status.is_success() {
-                    let error_text = userinfo_response.text().await.unwrap_or_default();
-                    log::error!(
-                        "OAuth2 userinfo request failed: status={}, url={}, body={}",
-                        status,
-                        userinfo_url,
-                        error_text
-                    );
-                    return Err(anyhow::anyhow!(
-                        "OAuth2 userinfo request failed: status={}, body={}",
-                        status,
-                        error_text
-                    ));
+                // OIDC: 校验id_token,并以其中的claims为准
+                let mut claims = Map::new();
+                if let Some(verifier) = &self.id_token_verifier {
+                    let id_token = token.extra_fields().id_token.as_ref().ok_or_else(|| {
+                        log::error!("OIDC token response has no id_token");
+                        anyhow::anyhow!("OIDC token response has no id_token")
+                    })?;
+                    claims = verifier
+                        .verify(&self.http_client, id_token)
+                        .await
+                        .map_err(|e| {
+                            log::error!("OIDC id_token validate failed: {}", e);
+                            anyhow::anyhow!("OIDC id_token validate failed: {}", e)
+                        })?;
                 }
 
-                // Parse JSON directly
-                let userinfo: Value = userinfo_response.json().await.map_err(|e| {
-                    log::error!("OAuth2 userinfo JSON parse error: {}", e);
-                    anyhow::anyhow!("Failed to parse OAuth2 userinfo response: {}", e)
-                })?;
-
-                // Log the complete userinfo response for debugging
-                log::info!(
-                    "OAuth2 userinfo response: {}",
-                    serde_json::to_string_pretty(&userinfo)
-                        .unwrap_or_else(|_| "Failed to serialize".to_string())
-                );
+                let userinfo_url = self.oauth2_config.oauth2_userinfo_url.as_ref();
+                if !userinfo_url.is_empty() {
+                    let userinfo = self.query_userinfo(userinfo_url, access_token).await?;
+                    // userinfo只补充id_token中没有的claim
+                    for (k, v) in userinfo {
+                        claims.entry(k).or_insert(v);
+                    }
+                }
 
                 // Extract username from claims
                 let username_claim_name = self.oauth2_config.oauth2_username_claim_name.as_ref();
-                let user_name = userinfo
-                    .get(username_claim_name)
+                let user_name = get_claim(&claims, username_claim_name)
                     .and_then(|v| v.as_str())
                     .ok_or_else(|| {
                         log::error!("OAuth2 username claim '{}' not found", username_claim_name);
@@ -145,8 +255,7 @@ impl OAuth2MsgActor {
 
                 // Extract nickname from claims
                 let nickname_claim_name = self.oauth2_config.oauth2_nickname_claim_name.as_ref();
-                let nickname = userinfo
-                    .get(nickname_claim_name)
+                let nickname = get_claim(&claims, nickname_claim_name)
                     .and_then(|v| v.as_str())
                     .map(|s| s.to_string())
                     .ok_or_else(|| {
@@ -154,29 +263,12 @@ impl OAuth2MsgActor {
                         anyhow::anyhow!("nickname claim '{}' not found", nickname_claim_name)
                     })?;
 
-                // Use configured default role
-                let role = self.oauth2_config.oauth2_user_default_role.clone();
-                let mut namespace_privilege = None;
-
-                // Initialize user
-                if let Some(user_manager_addr) = &self.user_manager_addr {
-                    let user = UserDto {
-                        username: Arc::new(user_name.clone()),
-                        nickname: Some(nickname),
-                        source: Some(UserSourceType::Inner.to_str().to_owned()),
-                        roles: Some(vec![role.clone()]),
-                        ..Default::default()
-                    };
-                    if let Ok(Ok(UserManagerResult::QueryUser(Some(user_dto)))) = user_manager_addr
-                        .send(UserManagerReq::InitUser {
-                            user,
-                            namespace_privilege_param: None,
-                        })
-                        .await
-                    {
-                        namespace_privilege = user_dto.namespace_privilege;
-                    }
-                }
+                let groups =
+                    get_claim_groups(&claims, &self.oauth2_config.oauth2_groups_claim_name);
+                let role = self.oauth2_config.get_role_by_groups(&groups);
+                let namespace_privilege = self
+                    .sync_user(user_name.clone(), nickname, role.clone(), &groups)
+                    .await;
 
                 let meta =
                     crate::oauth2::model::OAuth2UserMeta::new(user_name, role, namespace_privilege);
@@ -0,0 +1,294 @@
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use oauth2::basic::{
+    BasicErrorResponse, BasicRevocationErrorResponse, BasicTokenIntrospectionResponse,
+    BasicTokenType,
+};
+use oauth2::{Client, ExtraTokenFields, StandardRevocableToken, StandardTokenResponse};
+use reqwest::Client as HttpClient;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+pub const OIDC_DISCOVERY_PATH: &str = "/.well-known/openid-configuration";
+
+///
+/// 兼容OIDC的token响应扩展字段,用于获取id_token
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct IdTokenFields {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
+}
+
+impl ExtraTokenFields for IdTokenFields {}
+
+pub type OidcTokenResponse = StandardTokenResponse<IdTokenFields, BasicTokenType>;
+
+pub type OidcClient = Client<
+    BasicErrorResponse,
+    OidcTokenResponse,
+    BasicTokenType,
+    BasicTokenIntrospectionResponse,
+    StandardRevocableToken,
+    BasicRevocationErrorResponse,
+>;
+
+///
+/// OIDC discovery元数据,只保留使用到的字段
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct OidcMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    #[serde(default)]
+    pub userinfo_endpoint: Option<String>,
+    pub jwks_uri: String,
+}
+
+impl OidcMetadata {
+    pub async fn discover(http_client: &HttpClient, issuer: &str) -> anyhow::Result<Self> {
+        let url = format!("{}{}", issuer.trim_end_matches('/'), OIDC_DISCOVERY_PATH);
+        let response = http_client.get(&url).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "OIDC discovery request failed: status={}, url={}",
+                response.status(),
+                url
+            ));
+        }
+        let metadata: Self = response.json().await?;
+        if metadata.issuer.trim_end_matches('/') != issuer.trim_end_matches('/') {
+            return Err(anyhow::anyhow!(
+                "OIDC discovery issuer mismatch: expected {}, got {}",
+                issuer,
+                metadata.issuer
+            ));
+        }
+        Ok(metadata)
+    }
+}
+
+///
+/// id_token校验器,缓存JWKS;遇到未知kid时重新拉取一次,以支持IdP轮换密钥
+#[derive(Clone)]
+pub struct IdTokenVerifier {
+    issuer: Arc<String>,
+    client_id: Arc<String>,
+    jwks_url: Arc<String>,
+    jwks: Arc<RwLock<Option<Arc<JwkSet>>>>,
+}
+
+impl IdTokenVerifier {
+    pub fn new(issuer: Arc<String>, client_id: Arc<String>, jwks_url: Arc<String>) -> Self {
+        Self {
+            issuer,
+            client_id,
+            jwks_url,
+            jwks: Default::default(),
+        }
+    }
+
+    async fn fetch_jwks(&self, http_client: &HttpClient) -> anyhow::Result<Arc<JwkSet>> {
+        let response = http_client.get(self.jwks_url.as_str()).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!(
+                "OIDC jwks request failed: status={}, url={}",
+                response.status(),
+                &self.jwks_url
+            ));
+        }
+        let jwks = Arc::new(response.json::<JwkSet>().await?);
+        if let Ok(mut cache) = self.jwks.write() {
+            *cache = Some(jwks.clone());
+        }
+        Ok(jwks)
+    }
+
+    fn get_cache_jwks(&self) -> Option<Arc<JwkSet>> {
+        self.jwks.read().ok().and_then(|v| v.clone())
+    }
+
+    /// 校验id_token签名、issuer、audience与有效期,返回其中的claims
+    pub async fn verify(
+        &self,
+        http_client: &HttpClient,
+        id_token: &str,
+    ) -> anyhow::Result<Map<String, Value>> {
+        let header = decode_header(id_token)?;
+        let kid = header.kid.clone().unwrap_or_default();
+        let jwks = match self.get_cache_jwks() {
+            Some(jwks) if find_jwk(&jwks, &kid).is_some() => jwks,
+            _ => self.fetch_jwks(http_client).await?,
+        };
+        let jwk = find_jwk(&jwks, &kid)
+            .ok_or_else(|| anyhow::anyhow!("OIDC jwk not found, kid:{}", &kid))?;
+        if let Some(key_algorithm) = jwk.common.key_algorithm {
+            if Algorithm::from_str(&key_algorithm.to_string()).ok() != Some(header.alg) {
+                return Err(anyhow::anyhow!(
+                    "OIDC id_token alg {:?} not match jwk alg {:?}",
+                    header.alg,
+                    key_algorithm
+                ));
+            }
+        }
+        let key = DecodingKey::from_jwk(jwk)?;
+        let mut validation = Validation::new(header.alg);
+        validation.set_audience(&[self.client_id.as_str()]);
+        if !self.issuer.is_empty() {
+            validation.set_issuer(&[self.issuer.as_str()]);
+        }
+        let data = decode::<Map<String, Value>>(id_token, &key, &validation)?;
+        Ok(data.claims)
+    }
+}
+
+/// kid为空时,只有一个key则直接使用
+fn find_jwk<'a>(jwks: &'a JwkSet, kid: &str) -> Option<&'a jsonwebtoken::jwk::Jwk> {
+    if kid.is_empty() && jwks.keys.len() == 1 {
+        return jwks.keys.first();
+    }
+    jwks.find(kid)
+}
+
+///
+/// 按claim名称获取值,支持用`.`访问嵌套字段,如: realm_access.roles
+pub fn get_claim<'a>(claims: &'a Map<String, Value>, name: &str) -> Option<&'a Value> {
+    if let Some(v) = claims.get(name) {
+        return Some(v);
+    }
+    let mut iter = name.split('.');
+    let mut value = claims.get(iter.next()?)?;
+    for key in iter {
+        value = value.get(key)?;
+    }
+    Some(value)
+}
+
+///
+/// 获取用户组claim,兼容数组与逗号分隔字符串;
+/// keycloak组名带路径前缀(如/admin),统一去除开头的`/`
+pub fn get_claim_groups(claims: &Map<String, Value>, name: &str) -> Vec<String> {
+    let groups: Vec<String> = match get_claim(claims, name) {
+        Some(Value::Array(list)) => list
+            .iter()
+            .filter_map(|v| v.as_str().map(|s| s.to_owned()))
+            .collect(),
+        Some(Value::String(s)) => s.split(',').map(|s| s.to_owned()).collect(),
+        _ => vec![],
+    };
+    groups
+        .iter()
+        .map(|s| s.trim().trim_start_matches('/').to_owned())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{web, App, HttpResponse, HttpServer};
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::json;
+
+    const SECRET: &[u8] = b"rnacos-oidc-mock-secret-for-test";
+    const CLIENT_ID: &str = "rnacos";
+
+    fn jwks_json() -> Value {
+        use base64::Engine;
+        let k = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(SECRET);
+        json!({"keys":[{"kty":"oct","kid":"k1","alg":"HS256","k":k}]})
+    }
+
+    /// 启动本地mock IdP,返回issuer
+    fn start_mock_idp() -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let issuer = format!("http://{}", listener.local_addr().unwrap());
+        let metadata = json!({
+            "issuer": issuer,
+            "authorization_endpoint": format!("{}/auth", issuer),
+            "token_endpoint": format!("{}/token", issuer),
+            "userinfo_endpoint": format!("{}/userinfo", issuer),
+            "jwks_uri": format!("{}/jwks", issuer),
+        });
+        let server = HttpServer::new(move || {
+            let metadata = metadata.clone();
+            App::new()
+                .route(
+                    OIDC_DISCOVERY_PATH,
+                    web::get().to(move || {
+                        let metadata = metadata.clone();
+                        async move { HttpResponse::Ok().json(metadata) }
+                    }),
+                )
+                .route(
+                    "/jwks",
+                    web::get().to(|| async { HttpResponse::Ok().json(jwks_json()) }),
+                )
+        })
+        .workers(1)
+        .listen(listener)
+        .unwrap()
+        .run();
+        actix_web::rt::spawn(server);
+        issuer
+    }
+
+    fn build_id_token(issuer: &str, aud: &str) -> String {
+        let mut header = Header::new(jsonwebtoken::Algorithm::HS256);
+        header.kid = Some("k1".to_owned());
+        let claims = json!({
+            "iss": issuer,
+            "aud": aud,
+            "sub": "u1",
+            "exp": crate::now_second_i32() as i64 + 300,
+            "preferred_username": "alice",
+            "groups": ["/rnacos-admin", "/team-a"],
+        });
+        encode(&header, &claims, &EncodingKey::from_secret(SECRET)).unwrap()
+    }
+
+    #[actix_web::test]
+    async fn discover_and_verify_id_token() {
+        let issuer = start_mock_idp();
+        let http_client = HttpClient::new();
+        let metadata = OidcMetadata::discover(&http_client, &issuer).await.unwrap();
+        assert_eq!(metadata.jwks_uri, format!("{}/jwks", issuer));
+        let verifier = IdTokenVerifier::new(
+            Arc::new(metadata.issuer.clone()),
+            Arc::new(CLIENT_ID.to_owned()),
+            Arc::new(metadata.jwks_uri.clone()),
+        );
+        let claims = verifier
+            .verify(&http_client, &build_id_token(&issuer, CLIENT_ID))
+            .await
+            .unwrap();
+        assert_eq!(
+            get_claim(&claims, "preferred_username").and_then(|v| v.as_str()),
+            Some("alice")
+        );
+        assert_eq!(
+            get_claim_groups(&claims, "groups"),
+            vec!["rnacos-admin".to_owned(), "team-a".to_owned()]
+        );
+        assert!(verifier
+            .verify(&http_client, &build_id_token(&issuer, "other-client"))
+            .await
+            .is_err());
+        assert!(verifier
+            .verify(&http_client, &build_id_token("http://evil", CLIENT_ID))
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn nested_claim() {
+        let claims = json!({"realm_access":{"roles":["dev","ops"]}});
+        let claims = claims.as_object().unwrap();
+        assert_eq!(
+            get_claim_groups(claims, "realm_access.roles"),
+            vec!["dev".to_owned(), "ops".to_owned()]
+        );
+        assert!(get_claim_groups(claims, "groups").is_empty());
+    }
+}
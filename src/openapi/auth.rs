@@ -97,6 +97,7 @@ async fn do_login(
                 username: user.username.clone(),
                 roles: user.roles.unwrap_or_default(),
                 extend_infos: user.extend_info.unwrap_or_default(),
+                access_scopes: None,
             });
             let cache_req = CacheManagerReq::Set {
                 key: CacheKey::new(CacheType::ApiTokenSession, token.clone()),
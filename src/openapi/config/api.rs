@@ -17,6 +17,7 @@ use crate::config::core::{
     ConfigActor, ConfigCmd, ConfigInfoDto, ConfigKey, ConfigResult, ListenerItem, ListenerResult,
 };
 use crate::config::utils::param_utils;
+use crate::config::validate::check_publish_content;
 use crate::config::ConfigUtils;
 use crate::console::v2::ERROR_CODE_SYSTEM_ERROR;
 use crate::merge_web_param;
@@ -33,7 +34,12 @@ pub(super) fn service() -> Scope {
                 .route(web::put().to(add_config))
                 .route(web::delete().to(del_config)),
         )
-        .service(web::resource("/listener").route(web::post().to(listener_config)))
+        .service(
+            web::resource("/listener")
+                .route(web::get().to(query_config_listener))
+                .route(web::post().to(listener_config)),
+        )
+        .service(web::resource("/watch").route(web::get().to(super::watch::config_watch)))
 }
 
 #[derive(Serialize, Deserialize)]
@@ -210,6 +216,11 @@ pub(crate) async fn add_config(
             );
             req.config_type = config_type.map(|v| ConfigType::new_by_value(v.as_ref()).get_value());
             req.desc = desc.map(Arc::new);
+            if let Err(err) =
+                check_publish_content(&appdata, &req.config_key, &req.config_type, &req.value).await
+            {
+                return HttpResponse::BadRequest().body(err.to_string());
+            }
             match appdata.config_route.set_config(req).await {
                 Ok(_) => HttpResponse::Ok()
                     .content_type("text/html; charset=utf-8")
@@ -277,7 +288,7 @@ pub(crate) async fn get_config(
     let param = web_param.to_confirmed_param();
     match param {
         Ok(p) => {
-            let cmd = ConfigCmd::GET(ConfigKey::new(&p.data_id, &p.group, &p.tenant));
+            let cmd = ConfigCmd::GetResolved(ConfigKey::new(&p.data_id, &p.group, &p.tenant));
             match appdata.config_addr.send(cmd).await {
                 Ok(res) => {
                     let r: ConfigResult = res.unwrap();
@@ -397,7 +408,12 @@ pub(super) async fn listener_config(
         }
     }
     //println!("timeout header:{:?},time_out:{}",_req.headers().get("Long-Pulling-Timeout") ,time_out);
-    let cmd = ConfigCmd::LISTENER(list, tx, time_out);
+    let client_ip = Arc::new(
+        _req.peer_addr()
+            .map(|addr| addr.ip().to_string())
+            .unwrap_or_default(),
+    );
+    let cmd = ConfigCmd::LISTENER(list, tx, time_out, client_ip);
     let _ = config_addr.send(cmd).await;
     let res = rx.await.unwrap();
     let v = match res {
@@ -418,3 +434,84 @@ pub(super) async fn listener_config(
         .content_type("text/html; charset=utf-8")
         .body(v)
 }
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListenerQueryParams {
+    pub data_id: Option<String>,
+    pub group: Option<String>,
+    pub tenant: Option<String>,
+    pub ip: Option<String>,
+}
+
+///
+/// 监听查询结果,与nacos的GroupkeyListenserStatus保持兼容
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListenerStatusResult {
+    pub collection_status: i32,
+    pub lisenters_groupkey_status: HashMap<String, String>,
+}
+
+/// 查询监听指定配置的客户端ip及其md5
+pub(super) async fn query_config_listener(
+    a: web::Query<ListenerQueryParams>,
+    config_addr: web::Data<Addr<ConfigActor>>,
+) -> impl Responder {
+    let param = a.into_inner();
+    let data_id = param.data_id.unwrap_or_default();
+    let group = param.group.unwrap_or_default();
+    if data_id.is_empty() || group.is_empty() {
+        return HttpResponse::BadRequest().body("dataId and group can't be empty");
+    }
+    let tenant = ConfigUtils::default_tenant(param.tenant.unwrap_or_default());
+    let key = ConfigKey::new(&data_id, &group, &tenant);
+    match config_addr.send(ConfigCmd::QueryListenerByKey(key)).await {
+        Ok(Ok(ConfigResult::ListenerInfoList(list))) => {
+            let status = list
+                .into_iter()
+                .map(|e| (e.client_ip.as_ref().to_owned(), e.md5.as_ref().to_owned()))
+                .collect();
+            HttpResponse::Ok().json(ListenerStatusResult {
+                collection_status: 200,
+                lisenters_groupkey_status: status,
+            })
+        }
+        _ => HttpResponse::InternalServerError().body("query config listener error"),
+    }
+}
+
+/// 查询指定客户端ip监听的配置及其md5
+pub(super) async fn query_ip_listener(
+    a: web::Query<ListenerQueryParams>,
+    config_addr: web::Data<Addr<ConfigActor>>,
+) -> impl Responder {
+    let ip = a.into_inner().ip.unwrap_or_default();
+    if ip.is_empty() {
+        return HttpResponse::BadRequest().body("ip can't be empty");
+    }
+    match config_addr
+        .send(ConfigCmd::QueryListenerByIp(Arc::new(ip)))
+        .await
+    {
+        Ok(Ok(ConfigResult::ListenerInfoList(list))) => {
+            let status = list
+                .into_iter()
+                .map(|e| {
+                    //nacos groupKey格式: dataId+group[+tenant]
+                    let group_key = if e.tenant.is_empty() {
+                        format!("{}+{}", e.data_id, e.group)
+                    } else {
+                        format!("{}+{}+{}", e.data_id, e.group, e.tenant)
+                    };
+                    (group_key, e.md5.as_ref().to_owned())
+                })
+                .collect();
+            HttpResponse::Ok().json(ListenerStatusResult {
+                collection_status: 200,
+                lisenters_groupkey_status: status,
+            })
+        }
+        _ => HttpResponse::InternalServerError().body("query ip listener error"),
+    }
+}
@@ -1,4 +1,7 @@
-use crate::openapi::config::api::{add_config, del_config, get_config, listener_config};
+use crate::openapi::config::api::{
+    add_config, del_config, get_config, listener_config, query_config_listener, query_ip_listener,
+};
+use crate::openapi::config::watch::config_watch;
 use crate::openapi::constant::{CONFIG_V1_BASE_PATH, EMPTY};
 use crate::openapi::RouteConf;
 use actix_web::web::{scope, ServiceConfig};
@@ -6,6 +9,7 @@ use actix_web::{web, Scope};
 
 pub mod api;
 pub mod v2;
+pub mod watch;
 
 /// current implement for version 1
 pub fn openapi_service(conf: RouteConf) -> Vec<Scope> {
@@ -13,19 +17,28 @@ pub fn openapi_service(conf: RouteConf) -> Vec<Scope> {
 }
 
 pub fn openapi_v1_route(_conf: RouteConf) -> Scope {
-    web::scope(CONFIG_V1_BASE_PATH).service(api::service())
+    web::scope(CONFIG_V1_BASE_PATH)
+        .service(api::service())
+        .service(web::resource("/listener").route(web::get().to(query_ip_listener)))
 }
 
 pub fn config_v1_route(config: &mut ServiceConfig) {
-    config.service(
-        scope("/nacos/v1/cs/configs")
-            .service(
-                web::resource(EMPTY)
-                    .route(web::get().to(get_config))
-                    .route(web::post().to(add_config))
-                    .route(web::put().to(add_config))
-                    .route(web::delete().to(del_config)),
-            )
-            .service(web::resource("/listener").route(web::post().to(listener_config))),
-    );
+    config
+        .service(
+            scope("/nacos/v1/cs/configs")
+                .service(
+                    web::resource(EMPTY)
+                        .route(web::get().to(get_config))
+                        .route(web::post().to(add_config))
+                        .route(web::put().to(add_config))
+                        .route(web::delete().to(del_config)),
+                )
+                .service(
+                    web::resource("/listener")
+                        .route(web::get().to(query_config_listener))
+                        .route(web::post().to(listener_config)),
+                )
+                .service(web::resource("/watch").route(web::get().to(config_watch))),
+        )
+        .service(web::resource("/nacos/v1/cs/listener").route(web::get().to(query_ip_listener)));
 }
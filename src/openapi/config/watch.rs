@@ -0,0 +1,149 @@
+use crate::common::appdata::AppShareData;
+use crate::config::config_subscribe::ConfigSseWatcher;
+use crate::config::core::{ConfigCmd, ConfigKey};
+use crate::config::ConfigUtils;
+use crate::mcp::model::sse_model::{
+    SseConnMetaInfo, SseConnType, SseStreamManageAsyncCmd, SseStreamManageCmd,
+};
+use crate::mcp::sse_manage::SseConnUtils;
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpResponse};
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const DEFAULT_GROUP: &str = "DEFAULT_GROUP";
+/// 配置key中dataId与分组的分隔符
+const KEY_SPLIT: &str = "@@";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigWatchParams {
+    pub tenant: Option<String>,
+    /// 监听的配置列表,格式: dataId@@group,多个用逗号分割;分组为空时为DEFAULT_GROUP
+    pub keys: Option<String>,
+    /// 分组匹配规则,支持`*`通配符
+    pub group: Option<String>,
+    /// dataId匹配规则,支持`*`通配符
+    pub data_id: Option<String>,
+    /// 事件是否包含配置内容
+    pub with_content: Option<bool>,
+}
+
+impl ConfigWatchParams {
+    pub fn to_watcher(&self) -> Result<ConfigSseWatcher, String> {
+        let tenant = Arc::new(ConfigUtils::default_tenant(
+            self.tenant.clone().unwrap_or_default(),
+        ));
+        let mut keys = HashSet::new();
+        for item in self.keys.as_deref().unwrap_or_default().split(',') {
+            let item = item.trim();
+            if item.is_empty() {
+                continue;
+            }
+            let (data_id, group) = item.split_once(KEY_SPLIT).unwrap_or((item, DEFAULT_GROUP));
+            if data_id.is_empty() {
+                return Err(format!("invalid key: {}", item));
+            }
+            let group = if group.is_empty() {
+                DEFAULT_GROUP
+            } else {
+                group
+            };
+            keys.insert(ConfigKey::new(data_id, group, &tenant));
+        }
+        let watcher = ConfigSseWatcher {
+            tenant,
+            keys,
+            group_pattern: Self::get_pattern(&self.group),
+            data_id_pattern: Self::get_pattern(&self.data_id),
+            with_content: self.with_content.unwrap_or(false),
+        };
+        if watcher.keys.is_empty() && !watcher.has_pattern() {
+            return Err("keys, group or dataId is required".to_owned());
+        }
+        Ok(watcher)
+    }
+
+    fn get_pattern(v: &Option<String>) -> Option<Arc<String>> {
+        v.as_ref()
+            .filter(|e| !e.is_empty())
+            .map(|e| Arc::new(e.to_owned()))
+    }
+}
+
+///
+/// 通过SSE推送配置变更事件,供脚本、sidecar及浏览器等非SDK客户端监听配置
+pub(crate) async fn config_watch(
+    web::Query(param): web::Query<ConfigWatchParams>,
+    appdata: web::Data<Arc<AppShareData>>,
+) -> actix_web::Result<HttpResponse> {
+    let watcher = match param.to_watcher() {
+        Ok(v) => Arc::new(v),
+        Err(err) => return Ok(HttpResponse::BadRequest().body(err)),
+    };
+    let (tx, rx) = tokio::sync::mpsc::channel::<anyhow::Result<Bytes>>(10);
+    let session_id = Arc::new(Uuid::new_v4().simple().to_string());
+    let meta = SseConnMetaInfo {
+        session_id: session_id.clone(),
+        mcp_server_key: Default::default(),
+        conn_type: SseConnType::ConfigWatch,
+    };
+    appdata
+        .sse_stream_manager
+        .send(SseStreamManageCmd::AddConn(meta, tx))
+        .await
+        .ok();
+    let init_message = SseConnUtils::create_sse_event_message(
+        "ready",
+        &serde_json::json!({ "sessionId": session_id.as_str() }),
+    );
+    appdata
+        .sse_stream_manager
+        .send(SseStreamManageAsyncCmd::SendMessage(
+            session_id.clone(),
+            init_message,
+        ))
+        .await
+        .ok();
+    appdata
+        .config_addr
+        .send(ConfigCmd::AddSseWatcher(session_id, watcher))
+        .await
+        .ok();
+
+    let r_stream = tokio_stream::wrappers::ReceiverStream::new(rx);
+    let mut resp_builder = HttpResponse::build(StatusCode::OK);
+    resp_builder
+        .insert_header(("content-type", "text/event-stream; charset=utf-8"))
+        .insert_header(("cache-control", "no-cache"))
+        .insert_header(("x-accel-buffering", "no"))
+        .insert_header(("server", "r-nacos"));
+    Ok(resp_builder.streaming(r_stream))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_watch_params() {
+        let param = ConfigWatchParams {
+            tenant: Some("public".to_owned()),
+            keys: Some("app.yaml@@order, db.yaml,".to_owned()),
+            ..Default::default()
+        };
+        let watcher = param.to_watcher().unwrap();
+        assert_eq!(watcher.tenant.as_str(), "");
+        assert!(watcher
+            .keys
+            .contains(&ConfigKey::new("app.yaml", "order", "")));
+        assert!(watcher
+            .keys
+            .contains(&ConfigKey::new("db.yaml", DEFAULT_GROUP, "")));
+        assert!(!watcher.has_pattern());
+        assert!(ConfigWatchParams::default().to_watcher().is_err());
+    }
+}
@@ -10,7 +10,9 @@ pub(crate) async fn health_info(appdata: web::Data<Arc<AppShareData>>) -> impl R
         .await
     {
         match v {
-            CheckHealthResult::Success => HttpResponse::Ok().body("success"),
+            CheckHealthResult::Success | CheckHealthResult::Warning(_) => {
+                HttpResponse::Ok().body("success")
+            }
             CheckHealthResult::Error(msg) => {
                 HttpResponse::ServiceUnavailable().body(format!("error: {}", msg))
             }
@@ -20,9 +22,52 @@ pub(crate) async fn health_info(appdata: web::Data<Arc<AppShareData>>) -> impl R
     }
 }
 
+///
+/// 存活检查,只要求本地模块可用,不依赖raft集群状态
+pub(crate) async fn liveness(appdata: web::Data<Arc<AppShareData>>) -> impl Responder {
+    if let Ok(Ok(HealthManagerResponse::DetailResult(detail))) = appdata
+        .health_manager
+        .send(HealthManagerRequest::Detail)
+        .await
+    {
+        if detail.live {
+            HttpResponse::Ok().json(detail)
+        } else {
+            HttpResponse::ServiceUnavailable().json(detail)
+        }
+    } else {
+        HttpResponse::InternalServerError().body("request health_manager error")
+    }
+}
+
+///
+/// 就绪检查,数据追赶或降级时返回503
+pub(crate) async fn readiness(appdata: web::Data<Arc<AppShareData>>) -> impl Responder {
+    if let Ok(Ok(HealthManagerResponse::DetailResult(detail))) = appdata
+        .health_manager
+        .send(HealthManagerRequest::Detail)
+        .await
+    {
+        if detail.ready {
+            HttpResponse::Ok().json(detail)
+        } else {
+            HttpResponse::ServiceUnavailable().json(detail)
+        }
+    } else {
+        HttpResponse::InternalServerError().body("request health_manager error")
+    }
+}
+
 pub fn health_config(config: &mut web::ServiceConfig) {
-    config
-        .service(web::resource("/health").route(web::get().to(health_info)))
-        .service(web::resource("/nacos/health").route(web::get().to(health_info)))
-        .service(web::resource("/rnacos/health").route(web::get().to(health_info)));
+    for prefix in ["", "/nacos", "/rnacos"] {
+        config
+            .service(web::resource(format!("{}/health", prefix)).route(web::get().to(health_info)))
+            .service(
+                web::resource(format!("{}/health/liveness", prefix)).route(web::get().to(liveness)),
+            )
+            .service(
+                web::resource(format!("{}/health/readiness", prefix))
+                    .route(web::get().to(readiness)),
+            );
+    }
 }
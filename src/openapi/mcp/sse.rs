@@ -2,7 +2,9 @@ use crate::common::appdata::AppShareData;
 use crate::grpc::handler::NAMING_ROUTE_REQUEST;
 use crate::grpc::PayloadUtils;
 use crate::mcp::model::actor_model::{McpManagerReq, McpManagerResult};
-use crate::mcp::model::sse_model::{SseConnMetaInfo, SseStreamManageAsyncCmd, SseStreamManageCmd};
+use crate::mcp::model::sse_model::{
+    SseConnMetaInfo, SseConnType, SseStreamManageAsyncCmd, SseStreamManageCmd,
+};
 use crate::mcp::sse_manage::SseConnUtils;
 use crate::naming::cluster::model::{NamingRouteRequest, NamingRouterResponse};
 use crate::openapi::mcp::model::{JsonRpcRequest, McpPath, SseMessagePath};
@@ -53,6 +55,7 @@ pub async fn sse_connect(
     let meta = SseConnMetaInfo {
         session_id: session_id.clone(),
         mcp_server_key: path.server_key.clone(),
+        conn_type: SseConnType::Mcp,
     };
     app_share_data
         .sse_stream_manager
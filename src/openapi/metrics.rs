@@ -1,5 +1,7 @@
 use crate::common::appdata::AppShareData;
 use crate::metrics::model::{MetricsRequest, MetricsResponse};
+use crate::naming::core::{NamingCmd, NamingResult};
+use crate::naming::prometheus_sd::PrometheusSdParam;
 use actix_web::{web, HttpResponse, Responder};
 use std::sync::Arc;
 
@@ -16,9 +18,32 @@ pub(crate) async fn metrics_info(appdata: web::Data<Arc<AppShareData>>) -> impl
     }
 }
 
+///
+/// prometheus http_sd_config服务发现,把注册中心中带metrics_path元数据的实例转为抓取目标
+pub(crate) async fn prometheus_http_sd(
+    appdata: web::Data<Arc<AppShareData>>,
+    web::Query(param): web::Query<PrometheusSdParam>,
+) -> impl Responder {
+    match appdata
+        .naming_addr
+        .send(NamingCmd::QueryPrometheusTargets(param))
+        .await
+    {
+        Ok(Ok(NamingResult::PrometheusTargets(list))) => HttpResponse::Ok().json(list),
+        _ => HttpResponse::InternalServerError().body("request naming_actor error"),
+    }
+}
+
 pub fn metrics_config(config: &mut web::ServiceConfig) {
     config
         .service(web::resource("/metrics").route(web::get().to(metrics_info)))
         .service(web::resource("/nacos/metrics").route(web::get().to(metrics_info)))
-        .service(web::resource("/rnacos/metrics").route(web::get().to(metrics_info)));
+        .service(web::resource("/rnacos/metrics").route(web::get().to(metrics_info)))
+        .service(web::resource("/prometheus/http_sd").route(web::get().to(prometheus_http_sd)))
+        .service(
+            web::resource("/nacos/prometheus/http_sd").route(web::get().to(prometheus_http_sd)),
+        )
+        .service(
+            web::resource("/rnacos/prometheus/http_sd").route(web::get().to(prometheus_http_sd)),
+        );
 }
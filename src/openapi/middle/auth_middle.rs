@@ -1,17 +1,23 @@
+use crate::access_token::model::{
+    check_scopes, is_access_token, AccessTokenReq, AccessTokenResult,
+};
 use crate::common::appdata::AppShareData;
 use crate::common::constant::{AUTHORIZATION_HEADER, EMPTY_ARC_STRING};
 use crate::common::datetime_utils;
 use crate::common::model::TokenSession;
+use crate::limiter::model::{RequestLimitReq, RequestLimitResult};
+use crate::logger::access::{self, AccessLogRecord};
 use crate::metrics::core::MetricsManager;
+use crate::metrics::labeled::MetricsLabels;
 use crate::metrics::metrics_key::MetricsKey;
-use crate::metrics::model::{MetricsItem, MetricsRecord, MetricsRequest};
+use crate::metrics::model::{LabeledMetricsItem, MetricsItem, MetricsRecord, MetricsRequest};
 use crate::raft::cache::model::{CacheKey, CacheType, CacheValue};
 use crate::raft::cache::{CacheManager, CacheManagerReq, CacheManagerResult};
 use actix::Addr;
 use actix_http::body::EitherBody;
 use actix_http::HttpMessage;
 use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
-use actix_web::{dev, web, Error, HttpResponse};
+use actix_web::{dev, web, Error, HttpRequest, HttpResponse};
 use futures_util::future::LocalBoxFuture;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
@@ -27,6 +33,7 @@ lazy_static::lazy_static! {
     pub static ref IGNORE_METRICS_PATH: Vec<&'static str> = vec![
         "/nacos/v1/cs/configs/listener"
     ];
+    pub static ref IGNORE_LIMIT_PATH: Regex = Regex::new(r"(?i)^/nacos/(metrics|v1/raft/)").unwrap();
     //pub static ref PARM_AUTH_TOKEN: Regex = Regex::new(r"accessToken=(\w*)").unwrap();
 }
 
@@ -90,11 +97,48 @@ where
             true
         };
         let ignore_metrics = IGNORE_METRICS_PATH.contains(&path);
+        let is_limit_path = API_PATH.is_match(path) && !IGNORE_LIMIT_PATH.is_match(path);
+        let client_ip = request
+            .peer_addr()
+            .map(|addr| Arc::new(addr.ip().to_string()));
+        // 访问日志只记录查询参数中的命名空间,不读取请求体
+        let namespace = if access::is_enable() {
+            serde_urlencoded::from_str::<NamespaceInfo>(request.query_string())
+                .ok()
+                .and_then(|e| e.get_namespace())
+                .unwrap_or_default()
+        } else {
+            String::new()
+        };
         let app_share_data = self.app_share_data.clone();
         let service = self.service.clone();
         Box::pin(async move {
-            let cache_manager = &app_share_data.cache_manager;
             let offset = &app_share_data.timezone_offset;
+            if let (true, Some(client_ip)) = (is_limit_path, client_ip) {
+                if is_over_limit(&app_share_data, client_ip, request.path()).await {
+                    let body=format!("{{\"timestamp\":\"{}\",\"status\":503,\"error\":\"Service Unavailable\",\"message\":\"over threshold, request is limited\",\"path\":\"{}\"}}"
+                                     ,datetime_utils::get_now_timestamp_str(offset),request.path());
+                    let response = HttpResponse::ServiceUnavailable()
+                        .insert_header(("Content-Type", "application/json;charset=UTF-8"))
+                        .body(body)
+                        .map_into_right_body();
+                    let (http_request, _pl) = request.into_parts();
+                    let res = ServiceResponse::new(http_request, response);
+                    let duration = SystemTime::now()
+                        .duration_since(start)
+                        .unwrap_or_default()
+                        .as_secs_f64();
+                    record_req_metrics(
+                        &app_share_data.metrics_manager,
+                        res.request().method().as_str(),
+                        res.request().match_pattern(),
+                        duration,
+                        res.status().as_u16(),
+                    );
+                    record_access_log(res.request(), &namespace, duration, res.status().as_u16());
+                    return Ok(res);
+                }
+            }
             let token = if enable_auth && is_check_path {
                 if let Some(v) = request.headers().get(AUTHORIZATION_HEADER) {
                     Arc::new(v.to_str().unwrap_or_default().to_owned())
@@ -108,18 +152,19 @@ where
             } else {
                 EMPTY_ARC_STRING.clone()
             };
+            let mut message = UNKNOWN_USER;
             let pass = if !enable_auth || !is_check_path {
                 true
             } else if token.is_empty() {
                 false
-            } else if let Ok(Some(session)) = get_user_session(
-                cache_manager,
-                CacheManagerReq::Get(CacheKey::new(CacheType::ApiTokenSession, token.clone())),
-            )
-            .await
-            {
-                request.extensions_mut().insert(session);
-                true
+            } else if let Some(session) = get_token_session(&app_share_data, token.clone()).await {
+                if check_access_scopes(&session, &mut request).await {
+                    request.extensions_mut().insert(session);
+                    true
+                } else {
+                    message = NO_SCOPE_PERMISSION;
+                    false
+                }
             } else {
                 false
             };
@@ -130,20 +175,31 @@ where
                 //record_req_metrics(&app_share_data.metrics_manager,duration,false);
                 //res.await.map(ServiceResponse::map_into_left_body)
                 res.await.map(move |item| {
-                    let success = item.response().status().as_u16() < 400;
                     let duration = SystemTime::now()
                         .duration_since(start)
                         .unwrap_or_default()
                         .as_secs_f64();
                     if !ignore_metrics {
-                        record_req_metrics(&app_share_data.metrics_manager, duration, success);
+                        record_req_metrics(
+                            &app_share_data.metrics_manager,
+                            item.request().method().as_str(),
+                            item.request().match_pattern(),
+                            duration,
+                            item.response().status().as_u16(),
+                        );
                     }
+                    record_access_log(
+                        item.request(),
+                        &namespace,
+                        duration,
+                        item.response().status().as_u16(),
+                    );
                     ServiceResponse::map_into_left_body(item)
                 })
             } else {
                 //没有登录
-                let body=format!("{{\"timestamp\":\"{}\",\"status\":403,\"error\":\"Forbidden\",\"message\":\"unknown user!\",\"path\":\"{}\"}}"
-                                 ,datetime_utils::get_now_timestamp_str(offset),request.path());
+                let body=format!("{{\"timestamp\":\"{}\",\"status\":403,\"error\":\"Forbidden\",\"message\":\"{}\",\"path\":\"{}\"}}"
+                                 ,datetime_utils::get_now_timestamp_str(offset),message,request.path());
                 let response = HttpResponse::Forbidden()
                     .insert_header(("Content-Type", "application/json;charset=UTF-8"))
                     .body(body)
@@ -155,19 +211,42 @@ where
                     .duration_since(start)
                     .unwrap_or_default()
                     .as_secs_f64();
-                record_req_metrics(&app_share_data.metrics_manager, duration, false);
+                record_req_metrics(
+                    &app_share_data.metrics_manager,
+                    res.request().method().as_str(),
+                    res.request().match_pattern(),
+                    duration,
+                    res.status().as_u16(),
+                );
+                record_access_log(res.request(), &namespace, duration, res.status().as_u16());
                 Ok(res)
             }
         })
     }
 }
 
+const UNKNOWN_USER: &str = "unknown user!";
+const NO_SCOPE_PERMISSION: &str = "access token has no permission of the namespace!";
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AccessInfo<'a> {
     pub access_token: &'a str,
 }
 
+#[derive(Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct NamespaceInfo {
+    tenant: Option<String>,
+    namespace_id: Option<String>,
+}
+
+impl NamespaceInfo {
+    fn get_namespace(self) -> Option<String> {
+        self.tenant.or(self.namespace_id)
+    }
+}
+
 async fn peek_body_token(request: &mut ServiceRequest) -> Arc<String> {
     let mut result = EMPTY_ARC_STRING.clone();
     if request.method().as_str() == "GET" {
@@ -192,6 +271,56 @@ fn bytes_to_payload(buf: web::Bytes) -> dev::Payload {
     dev::Payload::from(pl)
 }
 
+async fn get_token_session(
+    app_share_data: &AppShareData,
+    token: Arc<String>,
+) -> Option<Arc<TokenSession>> {
+    if is_access_token(&token) {
+        if let Ok(Ok(AccessTokenResult::Session(session))) = app_share_data
+            .access_token_manager
+            .send(AccessTokenReq::Check(token))
+            .await
+        {
+            return session;
+        }
+        return None;
+    }
+    get_user_session(
+        &app_share_data.cache_manager,
+        CacheManagerReq::Get(CacheKey::new(CacheType::ApiTokenSession, token)),
+    )
+    .await
+    .ok()
+    .flatten()
+}
+
+///
+/// 校验个人访问令牌的命名空间读写权限;
+/// GET请求与配置监听请求视为读,其它请求视为写
+async fn check_access_scopes(session: &TokenSession, request: &mut ServiceRequest) -> bool {
+    let scopes = if let Some(scopes) = &session.access_scopes {
+        scopes.clone()
+    } else {
+        return true;
+    };
+    let is_get = request.method().as_str() == "GET";
+    let is_write = !is_get && !request.path().ends_with("/listener");
+    let mut namespace = serde_urlencoded::from_str::<NamespaceInfo>(request.query_string())
+        .ok()
+        .and_then(|e| e.get_namespace());
+    if namespace.is_none() && !is_get {
+        if let Ok(p) = request.extract::<web::Payload>().await {
+            if let Ok(v) = p.to_bytes().await {
+                namespace = serde_urlencoded::from_bytes::<NamespaceInfo>(v.as_ref())
+                    .ok()
+                    .and_then(|e| e.get_namespace());
+                request.set_payload(bytes_to_payload(v));
+            }
+        }
+    }
+    check_scopes(&scopes, &namespace.unwrap_or_default(), is_write)
+}
+
 async fn get_user_session(
     cache_manager: &Addr<CacheManager>,
     req: CacheManagerReq,
@@ -202,15 +331,86 @@ async fn get_user_session(
     }
 }
 
-fn record_req_metrics(metrics_manager: &Addr<MetricsManager>, duration: f64, _success: bool) {
+async fn is_over_limit(app_share_data: &AppShareData, client_ip: Arc<String>, path: &str) -> bool {
+    let req = RequestLimitReq::Acquire {
+        client_ip: client_ip.clone(),
+        request_type: Arc::new(path.to_owned()),
+    };
+    if let Ok(Ok(RequestLimitResult::Limited(limit_type))) =
+        app_share_data.request_limit_manager.send(req).await
+    {
+        log::warn!(
+            "http request over limit,client_ip:{},path:{},limit_type:{:?}",
+            &client_ip,
+            path,
+            limit_type
+        );
+        app_share_data
+            .metrics_manager
+            .do_send(MetricsRequest::Record(MetricsItem::new(
+                MetricsKey::HttpRequestLimitedCount,
+                MetricsRecord::CounterInc(1),
+            )));
+        true
+    } else {
+        false
+    }
+}
+
+fn record_access_log(request: &HttpRequest, namespace: &str, duration: f64, status: u16) {
+    if !access::is_enable() {
+        return;
+    }
+    let client_ip = request
+        .peer_addr()
+        .map(|addr| addr.ip().to_string())
+        .unwrap_or_default();
+    access::write_access_log(&AccessLogRecord {
+        protocol: "http",
+        client_ip: &client_ip,
+        method: request.method().as_str(),
+        request_type: request.path(),
+        namespace,
+        rt: duration * 1000f64,
+        code: status,
+    });
+}
+
+/// 未匹配到路由时使用的路由标签,避免原始路径导致标签基数过高
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+fn record_req_metrics(
+    metrics_manager: &Addr<MetricsManager>,
+    method: &str,
+    route: Option<String>,
+    duration: f64,
+    status: u16,
+) {
+    let rt = duration as f32 * 1000f32;
     metrics_manager.do_send(MetricsRequest::BatchRecord(vec![
         MetricsItem::new(
             MetricsKey::HttpRequestHandleRtHistogram,
-            MetricsRecord::HistogramRecord(duration as f32 * 1000f32),
+            MetricsRecord::HistogramRecord(rt),
         ),
         MetricsItem::new(
             MetricsKey::HttpRequestTotalCount,
             MetricsRecord::CounterInc(1),
         ),
     ]));
+    let labels = MetricsLabels::default()
+        .with("method", method.to_owned())
+        .with("route", route.unwrap_or(UNMATCHED_ROUTE.to_owned()))
+        .with("status", status.to_string());
+    metrics_manager.do_send(MetricsRequest::BatchLabeledRecord(vec![
+        LabeledMetricsItem::new(
+            MetricsKey::HttpRequestRouteHandleRtHistogram,
+            labels.clone(),
+            MetricsRecord::HistogramRecord(rt),
+        ),
+        LabeledMetricsItem::new(
+            MetricsKey::HttpRequestRouteTotalCount,
+            labels,
+            MetricsRecord::CounterInc(1),
+        ),
+    ]));
 }
@@ -1 +1,2 @@
 pub mod auth_middle;
+pub mod trace_middle;
@@ -0,0 +1,93 @@
+use crate::openapi::middle::auth_middle::IGNORE_METRICS_PATH;
+use crate::otel::{self, HttpHeaderExtractor};
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::{dev, Error};
+use futures_util::future::LocalBoxFuture;
+use opentelemetry::trace::{FutureExt, SpanKind, Status, TraceContextExt};
+use opentelemetry::KeyValue;
+use std::future::{ready, Ready};
+use std::sync::Arc;
+
+///
+/// http请求链路追踪,请求头中带W3C traceparent时沿用上游链路
+#[derive(Clone, Default)]
+pub struct OtelTrace;
+
+impl<S, B> Transform<S, ServiceRequest> for OtelTrace
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = OtelTraceMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(OtelTraceMiddleware {
+            service: Arc::new(service),
+        }))
+    }
+}
+
+#[derive(Clone)]
+pub struct OtelTraceMiddleware<S> {
+    service: Arc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for OtelTraceMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    dev::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if !otel::is_enable() || IGNORE_METRICS_PATH.contains(&req.path()) {
+            return Box::pin(self.service.call(req));
+        }
+        let parent_cx = otel::extract_context(&HttpHeaderExtractor(req.headers()));
+        let method = req.method().to_string();
+        let route = req.match_pattern().unwrap_or_else(|| req.path().to_owned());
+        let span = otel::start_span(
+            format!("{} {}", &method, &route),
+            SpanKind::Server,
+            vec![
+                KeyValue::new("http.request.method", method),
+                KeyValue::new("http.route", route),
+                KeyValue::new("url.path", req.path().to_owned()),
+            ],
+            &parent_cx,
+        );
+        let cx = match span {
+            Some(span) => parent_cx.with_span(span),
+            None => parent_cx,
+        };
+        let fut = self.service.call(req).with_context(cx.clone());
+        Box::pin(async move {
+            let res = fut.await;
+            let span = cx.span();
+            match &res {
+                Ok(item) => {
+                    let status = item.response().status();
+                    span.set_attribute(KeyValue::new(
+                        "http.response.status_code",
+                        status.as_u16() as i64,
+                    ));
+                    if status.is_server_error() {
+                        span.set_status(Status::error(status.to_string()));
+                    }
+                }
+                Err(err) => span.set_status(Status::error(err.to_string())),
+            }
+            res
+        })
+    }
+}
@@ -85,6 +85,7 @@ impl From<NamespaceParam> for NamespaceInfo {
             ),
             namespace_name: OptionUtils::select(value.namespace_show_name, value.namespace_name),
             r#type: None,
+            ..Default::default()
         }
     }
 }
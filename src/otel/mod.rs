@@ -0,0 +1,216 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use opentelemetry::global::{self, BoxedSpan};
+use opentelemetry::propagation::Extractor;
+use opentelemetry::trace::{FutureExt, SpanKind, Status, TraceContextExt, Tracer};
+use opentelemetry::{Context, KeyValue};
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{Sampler, TracerProvider};
+use opentelemetry_sdk::{runtime, Resource};
+
+use crate::common::AppSysConfig;
+
+pub const TRACER_NAME: &str = "rnacos";
+const DEFAULT_HTTP_ENDPOINT: &str = "http://127.0.0.1:4318";
+const DEFAULT_GRPC_ENDPOINT: &str = "http://127.0.0.1:4317";
+const HTTP_TRACES_PATH: &str = "/v1/traces";
+
+static OTEL_ENABLE: AtomicBool = AtomicBool::new(false);
+
+pub fn is_enable() -> bool {
+    OTEL_ENABLE.load(Ordering::Relaxed)
+}
+
+///
+/// 初始化OpenTelemetry链路导出,未开启时所有埋点都直接跳过
+pub fn init_tracer(sys_config: &AppSysConfig) -> anyhow::Result<()> {
+    if !sys_config.otel_enable {
+        return Ok(());
+    }
+    let exporter = if sys_config.otel_protocol.eq_ignore_ascii_case("grpc") {
+        let endpoint = if sys_config.otel_endpoint.is_empty() {
+            DEFAULT_GRPC_ENDPOINT.to_owned()
+        } else {
+            sys_config.otel_endpoint.as_ref().to_owned()
+        };
+        SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(endpoint)
+            .build()?
+    } else {
+        let endpoint = if sys_config.otel_endpoint.is_empty() {
+            DEFAULT_HTTP_ENDPOINT
+        } else {
+            sys_config.otel_endpoint.as_str()
+        };
+        SpanExporter::builder()
+            .with_http()
+            .with_endpoint(build_http_traces_endpoint(endpoint))
+            .build()?
+    };
+    let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+        sys_config.otel_sample_ratio,
+    )));
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, runtime::TokioCurrentThread)
+        .with_sampler(sampler)
+        .with_resource(Resource::new_with_defaults(vec![KeyValue::new(
+            "service.name",
+            sys_config.otel_service_name.as_ref().to_owned(),
+        )]))
+        .build();
+    global::set_tracer_provider(provider);
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    OTEL_ENABLE.store(true, Ordering::Relaxed);
+    log::info!(
+        "opentelemetry enable, protocol:{}, sample_ratio:{}",
+        &sys_config.otel_protocol,
+        sys_config.otel_sample_ratio
+    );
+    Ok(())
+}
+
+pub fn shutdown_tracer() {
+    if is_enable() {
+        global::shutdown_tracer_provider();
+    }
+}
+
+/// http方式上报时,endpoint未指定路径则补充traces路径
+fn build_http_traces_endpoint(endpoint: &str) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+    if endpoint.ends_with(HTTP_TRACES_PATH) {
+        endpoint.to_owned()
+    } else {
+        format!("{}{}", endpoint, HTTP_TRACES_PATH)
+    }
+}
+
+///
+/// 从请求头中解析W3C traceparent,作为服务端span的父节点
+pub fn extract_context(extractor: &dyn Extractor) -> Context {
+    global::get_text_map_propagator(|propagator| propagator.extract(extractor))
+}
+
+pub fn start_span(
+    name: impl Into<Cow<'static, str>>,
+    kind: SpanKind,
+    attributes: Vec<KeyValue>,
+    parent: &Context,
+) -> Option<BoxedSpan> {
+    if !is_enable() {
+        return None;
+    }
+    let tracer = global::tracer(TRACER_NAME);
+    Some(
+        tracer
+            .span_builder(name)
+            .with_kind(kind)
+            .with_attributes(attributes)
+            .start_with_context(&tracer, parent),
+    )
+}
+
+///
+/// 以当前上下文为父节点创建span,span在返回值drop时结束
+pub fn start_internal_span(name: &'static str, attributes: Vec<KeyValue>) -> Option<BoxedSpan> {
+    start_span(name, SpanKind::Internal, attributes, &Context::current())
+}
+
+///
+/// 在子span中执行future,future内部创建的span都会挂在该span下
+pub async fn in_span<F, T, E>(name: &'static str, attributes: Vec<KeyValue>, fut: F) -> F::Output
+where
+    F: Future<Output = Result<T, E>>,
+    E: std::fmt::Display,
+{
+    let span = match start_internal_span(name, attributes) {
+        Some(span) => span,
+        None => return fut.await,
+    };
+    let cx = Context::current_with_span(span);
+    let r = fut.with_context(cx.clone()).await;
+    if let Err(err) = &r {
+        cx.span().set_status(Status::error(err.to_string()));
+    }
+    r
+}
+
+///
+/// grpc请求的traceparent可以放在grpc metadata中,也可以放在nacos payload的headers中
+pub struct GrpcHeaderExtractor<'a> {
+    pub metadata: &'a tonic::metadata::MetadataMap,
+    pub headers: Option<&'a HashMap<String, String>>,
+}
+
+impl Extractor for GrpcHeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.metadata
+            .get(key)
+            .and_then(|v| v.to_str().ok())
+            .or_else(|| self.headers.and_then(|e| e.get(key).map(|v| v.as_str())))
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self
+            .metadata
+            .keys()
+            .filter_map(|e| match e {
+                tonic::metadata::KeyRef::Ascii(v) => Some(v.as_str()),
+                tonic::metadata::KeyRef::Binary(_) => None,
+            })
+            .collect();
+        if let Some(headers) = self.headers {
+            keys.extend(headers.keys().map(|e| e.as_str()));
+        }
+        keys
+    }
+}
+
+pub struct HttpHeaderExtractor<'a>(pub &'a actix_web::http::header::HeaderMap);
+
+impl Extractor for HttpHeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|v| v.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|e| e.as_str()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::propagation::TextMapPropagator;
+
+    #[test]
+    fn extract_traceparent() {
+        assert_eq!(
+            build_http_traces_endpoint("http://127.0.0.1:4318/"),
+            "http://127.0.0.1:4318/v1/traces"
+        );
+        let mut headers = HashMap::new();
+        headers.insert(
+            "traceparent".to_owned(),
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_owned(),
+        );
+        let metadata = tonic::metadata::MetadataMap::new();
+        let extractor = GrpcHeaderExtractor {
+            metadata: &metadata,
+            headers: Some(&headers),
+        };
+        let cx = TraceContextPropagator::new().extract(&extractor);
+        let span_context = cx.span().span_context().clone();
+        assert!(span_context.is_remote());
+        assert!(span_context.is_sampled());
+        assert_eq!(
+            span_context.trace_id().to_string(),
+            "4bf92f3577b34da6a3ce929d0e0e4736"
+        );
+    }
+}
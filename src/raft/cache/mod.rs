@@ -16,7 +16,9 @@ use super::db::{
 };
 use crate::common::constant::CACHE_TREE_NAME;
 use crate::common::model::UserSession;
+use crate::metrics::dump::{entry_bytes, ActorDump, DebugDumpQuery, StructureSize};
 use crate::{common::limiter_utils::LimiterData, now_millis_i64, now_second_i32};
+use std::mem::size_of;
 
 pub mod api;
 pub mod model;
@@ -400,3 +402,22 @@ impl Handler<CacheUserChangeReq> for CacheManager {
         Ok(CacheManagerResult::None)
     }
 }
+
+impl Handler<DebugDumpQuery> for CacheManager {
+    type Result = anyhow::Result<ActorDump>;
+
+    fn handle(&mut self, msg: DebugDumpQuery, _ctx: &mut Self::Context) -> Self::Result {
+        // 缓存不支持遍历,值按会话对象大小估算
+        let cache_bytes =
+            self.cache.len() * (entry_bytes::<CacheKey, CacheValue>() + size_of::<UserSession>());
+        let structures = vec![
+            StructureSize::new("raft_cache", self.cache.len(), cache_bytes),
+            StructureSize::new(
+                "user_privilege_change_time",
+                self.user_privilege_change_time.len(),
+                self.user_privilege_change_time.len() * entry_bytes::<Arc<String>, u32>(),
+            ),
+        ];
+        Ok(ActorDump::new("CacheManager", &msg, structures))
+    }
+}
@@ -2,8 +2,8 @@ use std::sync::Arc;
 
 use async_raft_ext::raft::ClientWriteRequest;
 
-use self::model::{RouterRequest, RouterResponse};
-use super::{db::table::TableManagerAsyncReq, join_node, store::ClientRequest};
+use self::model::{RouterRequest, RouterResponse, CONFIG_CLONE_FROM_KEY, CONFIG_ROLLBACK_FROM_KEY};
+use super::{client_write, db::table::TableManagerAsyncReq, join_node, store::ClientRequest};
 use crate::grpc::handler::RAFT_ROUTE_REQUEST;
 use crate::grpc::PayloadUtils;
 use crate::namespace::model::NamespaceRaftResult;
@@ -25,7 +25,7 @@ pub async fn handle_route(
 ) -> anyhow::Result<RouterResponse> {
     match req {
         RouterRequest::RaftRequest(req) => {
-            let r = app.raft.client_write(ClientWriteRequest::new(req)).await?;
+            let r = client_write(&app.raft, req).await?;
             Ok(RouterResponse::RaftResponse(r.data))
         }
         RouterRequest::ConfigSet {
@@ -34,9 +34,15 @@ pub async fn handle_route(
             op_user,
             config_type,
             desc,
-            extend_info: _,
+            extend_info,
         } => {
             let config_key: ConfigKey = (&key as &str).into();
+            let rollback_from = extend_info
+                .get(CONFIG_ROLLBACK_FROM_KEY)
+                .and_then(|v| v.parse().ok());
+            let clone_from = extend_info
+                .get(CONFIG_CLONE_FROM_KEY)
+                .map(|v| Arc::new(v.to_owned()));
             app.config_addr
                 .send(ConfigAsyncCmd::Add {
                     key: config_key,
@@ -44,6 +50,8 @@ pub async fn handle_route(
                     op_user,
                     config_type,
                     desc,
+                    rollback_from,
+                    clone_from,
                 })
                 .await??;
             Ok(RouterResponse::None)
@@ -88,10 +96,7 @@ pub async fn handle_route(
             Ok(RouterResponse::CacheManagerResult { result })
         }
         RouterRequest::NamespaceReq { req } => {
-            let resp = app
-                .raft
-                .client_write(ClientWriteRequest::new(ClientRequest::NamespaceReq(req)))
-                .await?;
+            let resp = client_write(&app.raft, ClientRequest::NamespaceReq(req)).await?;
             if let ClientResponse::Success = resp.data {
                 return Ok(RouterResponse::NamespaceResult {
                     result: NamespaceRaftResult::None,
@@ -106,6 +111,13 @@ pub async fn handle_route(
                 .await??;
             Ok(RouterResponse::ImportResult { result })
         }
+        RouterRequest::RaftNodeMetrics => {
+            let metrics = app.raft.metrics().borrow().clone();
+            Ok(RouterResponse::RaftNodeMetrics {
+                last_log_index: metrics.last_log_index,
+                last_applied: metrics.last_applied,
+            })
+        }
     }
 }
 
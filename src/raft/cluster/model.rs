@@ -27,6 +27,10 @@ pub struct SetConfigReq {
     pub op_user: Option<Arc<String>>,
     pub config_type: Option<Arc<String>>,
     pub desc: Option<Arc<String>>,
+    /// 回滚时记录来源的历史版本id
+    pub rollback_from: Option<u64>,
+    /// 克隆时记录来源配置
+    pub clone_from: Option<Arc<String>>,
     //pub can_route_to_remote: bool,
     //pub extend_info: Option<HashMap<String,String>>,
 }
@@ -39,6 +43,8 @@ impl SetConfigReq {
             op_user: None,
             config_type: None,
             desc: None,
+            rollback_from: None,
+            clone_from: None,
         }
     }
 
@@ -53,6 +59,8 @@ impl SetConfigReq {
             op_user: Some(op_user),
             config_type: None,
             desc: None,
+            rollback_from: None,
+            clone_from: None,
         }
     }
 
@@ -82,6 +90,11 @@ impl DelConfigReq {
     }
 }
 
+/// 配置路由请求扩展信息中的回滚来源版本key
+pub const CONFIG_ROLLBACK_FROM_KEY: &str = "rollbackFrom";
+/// 配置路由请求扩展信息中的克隆来源key
+pub const CONFIG_CLONE_FROM_KEY: &str = "cloneFrom";
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum RouterRequest {
     RaftRequest(ClientRequest),
@@ -117,17 +130,32 @@ pub enum RouterRequest {
         data: Vec<u8>,
         param: TransferImportParam,
     },
+    /// 查询节点的raft日志位置,用于leader计算复制延迟
+    RaftNodeMetrics,
 }
 
 impl From<SetConfigReq> for RouterRequest {
     fn from(req: SetConfigReq) -> Self {
+        let mut extend_info = HashMap::new();
+        if let Some(rollback_from) = req.rollback_from {
+            extend_info.insert(
+                CONFIG_ROLLBACK_FROM_KEY.to_owned(),
+                rollback_from.to_string(),
+            );
+        }
+        if let Some(clone_from) = req.clone_from {
+            extend_info.insert(
+                CONFIG_CLONE_FROM_KEY.to_owned(),
+                clone_from.as_ref().to_owned(),
+            );
+        }
         Self::ConfigSet {
             key: req.config_key.build_key(),
             value: req.value,
             op_user: req.op_user,
             config_type: req.config_type,
             desc: req.desc,
-            extend_info: Default::default(),
+            extend_info,
         }
     }
 }
@@ -163,10 +191,22 @@ impl From<ClientRequest> for RouterRequest {
 pub enum RouterResponse {
     None,
     RaftResponse(ClientResponse),
-    TableManagerResult { result: TableManagerResult },
-    CacheManagerResult { result: CacheManagerResult },
-    NamespaceResult { result: NamespaceRaftResult },
-    ImportResult { result: TransferImportResponse },
+    TableManagerResult {
+        result: TableManagerResult,
+    },
+    CacheManagerResult {
+        result: CacheManagerResult,
+    },
+    NamespaceResult {
+        result: NamespaceRaftResult,
+    },
+    ImportResult {
+        result: TransferImportResponse,
+    },
+    RaftNodeMetrics {
+        last_log_index: u64,
+        last_applied: u64,
+    },
 }
 
 impl From<ClientResponse> for RouterResponse {
@@ -1,6 +1,7 @@
 use super::model::{DelConfigReq, RouteAddr, RouterRequest, RouterResponse, SetConfigReq};
 use crate::grpc::handler::RAFT_ROUTE_REQUEST;
 use crate::namespace::model::{NamespaceRaftReq, NamespaceRaftResult};
+use crate::raft::client_write;
 use crate::raft::cluster::router_request;
 use crate::raft::filestore::core::FileStore;
 use crate::raft::store::{ClientRequest, ClientResponse};
@@ -12,7 +13,6 @@ use crate::{
     raft::{network::factory::RaftClusterRequestSender, NacosRaft},
 };
 use actix::prelude::*;
-use async_raft_ext::raft::ClientWriteRequest;
 use std::convert::TryInto;
 use std::{fmt::Debug, sync::Arc};
 
@@ -88,8 +88,10 @@ impl ConfigRoute {
                     op_user: req.op_user,
                     config_type: req.config_type,
                     desc: req.desc,
+                    rollback_from: req.rollback_from,
+                    clone_from: req.clone_from,
                 };
-                self.config_addr.send(cmd).await?.ok();
+                self.config_addr.send(cmd).await??;
             }
             RouteAddr::Remote(_, addr) => {
                 let source_req = req.clone();
@@ -115,7 +117,7 @@ impl ConfigRoute {
         match self.raft_addr_route.get_route_addr().await? {
             RouteAddr::Local => {
                 let cmd = ConfigAsyncCmd::Delete(req.config_key);
-                self.config_addr.send(cmd).await?.ok();
+                self.config_addr.send(cmd).await??;
             }
             RouteAddr::Remote(_, addr) => {
                 let req: RouterRequest = req.into();
@@ -170,10 +172,7 @@ impl RaftRequestRoute {
     ) -> anyhow::Result<NamespaceRaftResult> {
         match self.raft_addr_route.get_route_addr().await? {
             RouteAddr::Local => {
-                let resp = self
-                    .raft
-                    .client_write(ClientWriteRequest::new(ClientRequest::NamespaceReq(req)))
-                    .await?;
+                let resp = client_write(&self.raft, ClientRequest::NamespaceReq(req)).await?;
                 if let ClientResponse::Success = resp.data {
                     Ok(NamespaceRaftResult::None)
                 } else {
@@ -226,7 +225,7 @@ impl RaftRequestRoute {
     pub async fn request(&self, req: ClientRequest) -> anyhow::Result<ClientResponse> {
         match self.raft_addr_route.get_route_addr().await? {
             RouteAddr::Local => {
-                let resp = self.raft.client_write(ClientWriteRequest::new(req)).await?;
+                let resp = client_write(&self.raft, req).await?;
                 Ok(resp.data)
             }
             RouteAddr::Remote(_, addr) => {
@@ -4,14 +4,28 @@ use std::{
     sync::{Arc, Weak},
 };
 
-use async_raft_ext::raft::ClientWriteRequest;
 use bean_factory::{bean, Inject};
 use serde::{Deserialize, Serialize};
 
 use actix::prelude::*;
 
-use crate::common::constant::{CACHE_TREE_NAME, USER_TREE_NAME};
+use crate::access_token::core::AccessTokenManager;
+use crate::access_token::model::AccessTokenReq;
+use crate::alert::core::AlertManager;
+use crate::alert::model::AlertReq;
+use crate::common::constant::{
+    ACCESS_TOKEN_TABLE_NAME, ALERT_HISTORY_TABLE_NAME, ALERT_RULE_TABLE_NAME, CACHE_TREE_NAME,
+    CONFIG_CHANGE_TABLE_NAME, CONFIG_LOCK_TABLE_NAME, CONFIG_SCHEMA_TABLE_NAME,
+    SYS_CONFIG_TABLE_NAME, USER_TREE_NAME, WEBHOOK_TABLE_NAME,
+};
 use crate::common::sequence_utils::SimpleSequence;
+use crate::config::approval::core::ConfigChangeManager;
+use crate::config::approval::model::ConfigChangeReq;
+use crate::config::core::{ConfigActor, ConfigCmd};
+use crate::config::schema::core::ConfigSchemaManager;
+use crate::config::schema::model::ConfigSchemaReq;
+use crate::limiter::core::RequestLimitManager;
+use crate::limiter::model::{RequestLimitConfig, RequestLimitReq, REQUEST_LIMIT_CONFIG_KEY};
 use crate::raft::filestore::model::SnapshotRecordDto;
 use crate::raft::filestore::raftsnapshot::{SnapshotWriterActor, SnapshotWriterRequest};
 use crate::transfer::model::{
@@ -21,6 +35,8 @@ use crate::transfer::model::{
 use crate::transfer::writer::TransferWriterActor;
 use crate::user::build_password_hash;
 use crate::user::model::UserDo;
+use crate::webhook::core::WebhookManager;
+use crate::webhook::model::WebhookReq;
 use crate::{
     common::string_utils::StringUtils,
     raft::{
@@ -86,6 +102,13 @@ pub struct TableManager {
     pub table_map: HashMap<Arc<String>, TableInfo>,
     raft: Option<Weak<NacosRaft>>,
     cache_manager: Option<Addr<CacheManager>>,
+    request_limit_manager: Option<Addr<RequestLimitManager>>,
+    access_token_manager: Option<Addr<AccessTokenManager>>,
+    config_schema_manager: Option<Addr<ConfigSchemaManager>>,
+    config_change_manager: Option<Addr<ConfigChangeManager>>,
+    webhook_manager: Option<Addr<WebhookManager>>,
+    alert_manager: Option<Addr<AlertManager>>,
+    config_addr: Option<Addr<ConfigActor>>,
 }
 
 impl TableManager {
@@ -95,6 +118,23 @@ impl TableManager {
 
     fn init_table(&mut self, _name: Arc<String>, _sequence_step: u32) {}
 
+    ///
+    /// 系统运行时配置变更后通知对应的管理器
+    fn notify_sys_config_change(&self, key: &[u8], value: Option<&[u8]>) {
+        if key == REQUEST_LIMIT_CONFIG_KEY.as_bytes() {
+            if let Some(request_limit_manager) = &self.request_limit_manager {
+                let config = value.and_then(|v| match RequestLimitConfig::from_bytes(v) {
+                    Ok(config) => Some(config),
+                    Err(err) => {
+                        log::error!("parse request limit config error:{}", err);
+                        None
+                    }
+                });
+                request_limit_manager.do_send(RequestLimitReq::UpdateConfig(config));
+            }
+        }
+    }
+
     pub fn drop_table(&mut self, name: &Arc<String>) {
         if let Some(mut table) = self.table_map.remove(name) {
             if let Some(seq) = table.seq.as_mut() {
@@ -237,7 +277,7 @@ impl TableManager {
     ) -> anyhow::Result<()> {
         if let Some(weak_raft) = raft {
             if let Some(raft) = weak_raft.upgrade() {
-                raft.client_write(ClientWriteRequest::new(req)).await?;
+                crate::raft::client_write(&raft, req).await?;
             }
         }
         Ok(())
@@ -299,6 +339,16 @@ impl TableManager {
                     };
                     writer.do_send(TransferWriterRequest::AddRecord(record));
                 }
+            } else if param.config && key.as_str() == CONFIG_LOCK_TABLE_NAME.as_str() {
+                for (key, value) in &table_info.table_data {
+                    let record = TransferRecordDto {
+                        table_name: Some(table_info.name.clone()),
+                        key: key.to_owned(),
+                        value: value.to_owned(),
+                        table_id: 0,
+                    };
+                    writer.do_send(TransferWriterRequest::AddRecord(record));
+                }
             }
         }
         Ok(())
@@ -340,6 +390,13 @@ impl Inject for TableManager {
         let raft: Option<Arc<NacosRaft>> = factory_data.get_bean();
         self.raft = raft.map(|e| Arc::downgrade(&e));
         self.cache_manager = factory_data.get_actor();
+        self.request_limit_manager = factory_data.get_actor();
+        self.access_token_manager = factory_data.get_actor();
+        self.config_schema_manager = factory_data.get_actor();
+        self.config_change_manager = factory_data.get_actor();
+        self.webhook_manager = factory_data.get_actor();
+        self.alert_manager = factory_data.get_actor();
+        self.config_addr = factory_data.get_actor();
     }
 }
 
@@ -464,6 +521,57 @@ impl Handler<TableManagerReq> for TableManager {
                         };
                         cache_manager.do_send(req);
                     }
+                } else if table_name.as_str() == SYS_CONFIG_TABLE_NAME.as_str() {
+                    self.notify_sys_config_change(&key, Some(&value));
+                } else if table_name.as_str() == ACCESS_TOKEN_TABLE_NAME.as_str() {
+                    if let Some(access_token_manager) = &self.access_token_manager {
+                        access_token_manager.do_send(AccessTokenReq::NotifyChange {
+                            key: key.clone(),
+                            value: Some(value.clone()),
+                        });
+                    }
+                } else if table_name.as_str() == CONFIG_SCHEMA_TABLE_NAME.as_str() {
+                    if let Some(config_schema_manager) = &self.config_schema_manager {
+                        config_schema_manager.do_send(ConfigSchemaReq::NotifyChange {
+                            key: key.clone(),
+                            value: Some(value.clone()),
+                        });
+                    }
+                } else if table_name.as_str() == CONFIG_CHANGE_TABLE_NAME.as_str() {
+                    if let Some(config_change_manager) = &self.config_change_manager {
+                        config_change_manager.do_send(ConfigChangeReq::NotifyChange {
+                            key: key.clone(),
+                            value: Some(value.clone()),
+                        });
+                    }
+                } else if table_name.as_str() == WEBHOOK_TABLE_NAME.as_str() {
+                    if let Some(webhook_manager) = &self.webhook_manager {
+                        webhook_manager.do_send(WebhookReq::NotifyChange {
+                            key: key.clone(),
+                            value: Some(value.clone()),
+                        });
+                    }
+                } else if table_name.as_str() == CONFIG_LOCK_TABLE_NAME.as_str() {
+                    if let Some(config_addr) = &self.config_addr {
+                        config_addr.do_send(ConfigCmd::NotifyLock {
+                            key: key.clone(),
+                            value: Some(value.clone()),
+                        });
+                    }
+                } else if table_name.as_str() == ALERT_RULE_TABLE_NAME.as_str() {
+                    if let Some(alert_manager) = &self.alert_manager {
+                        alert_manager.do_send(AlertReq::NotifyRuleChange {
+                            key: key.clone(),
+                            value: Some(value.clone()),
+                        });
+                    }
+                } else if table_name.as_str() == ALERT_HISTORY_TABLE_NAME.as_str() {
+                    if let Some(alert_manager) = &self.alert_manager {
+                        alert_manager.do_send(AlertReq::NotifyHistoryChange {
+                            key: key.clone(),
+                            value: Some(value.clone()),
+                        });
+                    }
                 }
                 self.insert(table_name, key, value, last_seq_id);
                 Ok(TableManagerResult::None)
@@ -474,6 +582,57 @@ impl Handler<TableManagerReq> for TableManager {
                         let req = CacheManagerReq::NotifyRemove { key: key.clone() };
                         cache_manager.do_send(req);
                     }
+                } else if table_name.as_str() == SYS_CONFIG_TABLE_NAME.as_str() {
+                    self.notify_sys_config_change(&key, None);
+                } else if table_name.as_str() == ACCESS_TOKEN_TABLE_NAME.as_str() {
+                    if let Some(access_token_manager) = &self.access_token_manager {
+                        access_token_manager.do_send(AccessTokenReq::NotifyChange {
+                            key: key.clone(),
+                            value: None,
+                        });
+                    }
+                } else if table_name.as_str() == CONFIG_SCHEMA_TABLE_NAME.as_str() {
+                    if let Some(config_schema_manager) = &self.config_schema_manager {
+                        config_schema_manager.do_send(ConfigSchemaReq::NotifyChange {
+                            key: key.clone(),
+                            value: None,
+                        });
+                    }
+                } else if table_name.as_str() == CONFIG_CHANGE_TABLE_NAME.as_str() {
+                    if let Some(config_change_manager) = &self.config_change_manager {
+                        config_change_manager.do_send(ConfigChangeReq::NotifyChange {
+                            key: key.clone(),
+                            value: None,
+                        });
+                    }
+                } else if table_name.as_str() == WEBHOOK_TABLE_NAME.as_str() {
+                    if let Some(webhook_manager) = &self.webhook_manager {
+                        webhook_manager.do_send(WebhookReq::NotifyChange {
+                            key: key.clone(),
+                            value: None,
+                        });
+                    }
+                } else if table_name.as_str() == CONFIG_LOCK_TABLE_NAME.as_str() {
+                    if let Some(config_addr) = &self.config_addr {
+                        config_addr.do_send(ConfigCmd::NotifyLock {
+                            key: key.clone(),
+                            value: None,
+                        });
+                    }
+                } else if table_name.as_str() == ALERT_RULE_TABLE_NAME.as_str() {
+                    if let Some(alert_manager) = &self.alert_manager {
+                        alert_manager.do_send(AlertReq::NotifyRuleChange {
+                            key: key.clone(),
+                            value: None,
+                        });
+                    }
+                } else if table_name.as_str() == ALERT_HISTORY_TABLE_NAME.as_str() {
+                    if let Some(alert_manager) = &self.alert_manager {
+                        alert_manager.do_send(AlertReq::NotifyHistoryChange {
+                            key: key.clone(),
+                            value: None,
+                        });
+                    }
                 }
                 match self.remove(table_name, key) {
                     Some(v) => Ok(TableManagerResult::Value(v.to_vec())),
@@ -11,6 +11,11 @@ use super::{
     },
     StoreUtils,
 };
+use crate::health::core::HealthManager;
+use crate::health::model::HealthBackRequest;
+use crate::metrics::dump::{ActorDump, DebugDumpQuery, StructureSize};
+use crate::metrics::metrics_key::MetricsKey;
+use crate::metrics::model::{MetricsItem, MetricsQuery, MetricsRecord};
 use crate::raft::filestore::model::SnapshotRecordDto;
 use crate::raft::filestore::raftdata::RaftDataHandler;
 use crate::raft::filestore::raftsnapshot::SnapshotWriterActor;
@@ -58,8 +63,11 @@ pub struct StateApplyManager {
     snapshot_manager: Option<Addr<RaftSnapshotManager>>,
     log_manager: Option<Addr<RaftLogManager>>,
     data_wrap: Option<Arc<RaftDataHandler>>,
+    health_manager: Option<Addr<HealthManager>>,
     snapshot_next_index: u64,
     last_applied_log: u64,
+    //已提交但还未应用到状态机的日志数量
+    apply_queue_depth: u64,
 }
 
 impl Default for StateApplyManager {
@@ -75,8 +83,10 @@ impl StateApplyManager {
             snapshot_manager: None,
             log_manager: None,
             data_wrap: None,
+            health_manager: None,
             snapshot_next_index: 1,
             last_applied_log: 0,
+            apply_queue_depth: 0,
         }
     }
 
@@ -187,6 +197,7 @@ impl StateApplyManager {
 
     fn load_log(&mut self, ctx: &mut Context<Self>) {
         if self.last_applied_log == 0 || self.log_manager.is_none() || self.data_wrap.is_none() {
+            self.notify_load_finished();
             return;
         }
         let start_index = self.snapshot_next_index;
@@ -219,6 +230,13 @@ impl StateApplyManager {
             data_wrap.load_complete().ok();
         }
         log::info!("raft data load finished.");
+        self.notify_load_finished();
+    }
+
+    fn notify_load_finished(&self) {
+        if let Some(health_manager) = self.health_manager.as_ref() {
+            health_manager.do_send(HealthBackRequest::RaftDataLoaded);
+        }
     }
 
     fn apply_request_to_state_machine(&mut self, request: ApplyRequestDto) -> anyhow::Result<()> {
@@ -334,6 +352,7 @@ impl Inject for StateApplyManager {
         self.snapshot_manager = factory_data.get_actor();
         self.log_manager = factory_data.get_actor();
         self.data_wrap = factory_data.get_bean();
+        self.health_manager = factory_data.get_actor();
 
         self.init(ctx);
     }
@@ -427,8 +446,10 @@ impl Handler<StateApplyAsyncRequest> for StateApplyManager {
             StateApplyAsyncRequest::BuildSnapshot => {}
             StateApplyAsyncRequest::ApplyRequest(req) => {
                 self.last_applied_log = req.index;
+                self.apply_queue_depth += 1;
             }
         };
+        let is_apply_request = matches!(&msg, StateApplyAsyncRequest::ApplyRequest(_));
         let last_index = self.last_applied_log;
         let fut = async move {
             match msg {
@@ -452,7 +473,36 @@ impl Handler<StateApplyAsyncRequest> for StateApplyManager {
             }
         }
         .into_actor(self)
-        .map(|r, _act, _ctx| r);
+        .map(move |r, act, _ctx| {
+            if is_apply_request {
+                act.apply_queue_depth = act.apply_queue_depth.saturating_sub(1);
+            }
+            r
+        });
         Box::pin(fut)
     }
 }
+
+impl Handler<MetricsQuery> for StateApplyManager {
+    type Result = anyhow::Result<Vec<MetricsItem>>;
+
+    fn handle(&mut self, _msg: MetricsQuery, _ctx: &mut Self::Context) -> Self::Result {
+        Ok(vec![MetricsItem {
+            metrics_type: MetricsKey::RaftApplyQueueDepth,
+            record: MetricsRecord::Gauge(self.apply_queue_depth as f32),
+        }])
+    }
+}
+
+impl Handler<DebugDumpQuery> for StateApplyManager {
+    type Result = anyhow::Result<ActorDump>;
+
+    fn handle(&mut self, msg: DebugDumpQuery, _ctx: &mut Self::Context) -> Self::Result {
+        let structures = vec![StructureSize::new(
+            "raft_apply_queue",
+            self.apply_queue_depth as usize,
+            0,
+        )];
+        Ok(ActorDump::new("StateApplyManager", &msg, structures))
+    }
+}
@@ -1,7 +1,9 @@
 use crate::common::byte_utils::bin_to_id;
 use crate::common::constant::{
-    CACHE_TREE_NAME, CONFIG_TREE_NAME, MCP_SERVER_TABLE_NAME, MCP_TOOL_SPEC_TABLE_NAME,
-    NAMESPACE_TREE_NAME, NAMING_INSTANCE_TABLE, SEQUENCE_TREE_NAME, SEQ_KEY_CONFIG, USER_TREE_NAME,
+    ACCESS_TOKEN_TABLE_NAME, ALERT_HISTORY_TABLE_NAME, ALERT_RULE_TABLE_NAME, CACHE_TREE_NAME,
+    CONFIG_CHANGE_TABLE_NAME, CONFIG_LOCK_TABLE_NAME, CONFIG_SCHEMA_TABLE_NAME, CONFIG_TREE_NAME,
+    MCP_SERVER_TABLE_NAME, MCP_TOOL_SPEC_TABLE_NAME, NAMESPACE_TREE_NAME, NAMING_INSTANCE_TABLE,
+    SEQUENCE_TREE_NAME, SEQ_KEY_CONFIG, SYS_CONFIG_TABLE_NAME, USER_TREE_NAME, WEBHOOK_TABLE_NAME,
 };
 use crate::config::core::{ConfigActor, ConfigCmd, ConfigKey, ConfigValue};
 use crate::config::model::{ConfigRaftCmd, ConfigValueDO};
@@ -89,6 +91,22 @@ impl RaftDataHandler {
                 last_seq_id: None,
             };
             self.table.send(req).await??;
+        } else if record.tree.as_str() == SYS_CONFIG_TABLE_NAME.as_str()
+            || record.tree.as_str() == ACCESS_TOKEN_TABLE_NAME.as_str()
+            || record.tree.as_str() == CONFIG_SCHEMA_TABLE_NAME.as_str()
+            || record.tree.as_str() == CONFIG_CHANGE_TABLE_NAME.as_str()
+            || record.tree.as_str() == WEBHOOK_TABLE_NAME.as_str()
+            || record.tree.as_str() == CONFIG_LOCK_TABLE_NAME.as_str()
+            || record.tree.as_str() == ALERT_RULE_TABLE_NAME.as_str()
+            || record.tree.as_str() == ALERT_HISTORY_TABLE_NAME.as_str()
+        {
+            let req = TableManagerReq::Set {
+                table_name: record.tree,
+                key: record.key,
+                value: record.value,
+                last_seq_id: None,
+            };
+            self.table.send(req).await??;
         } else if record.tree.as_str() == NAMESPACE_TREE_NAME.as_str() {
             let req = RaftApplyDataRequest::LoadSnapshotRecord(record);
             self.namespace.send(req).await??;
@@ -156,6 +174,8 @@ impl RaftDataHandler {
                 history_table_id,
                 op_time,
                 op_user,
+                rollback_from,
+                clone_from,
             } => {
                 let cmd = ConfigRaftCmd::ConfigAdd {
                     key,
@@ -166,6 +186,8 @@ impl RaftDataHandler {
                     history_table_id,
                     op_time,
                     op_user,
+                    rollback_from,
+                    clone_from,
                 };
                 self.config.send(cmd).await.ok();
             }
@@ -237,6 +259,8 @@ impl RaftDataHandler {
                 history_table_id,
                 op_time,
                 op_user,
+                rollback_from,
+                clone_from,
             } => {
                 let cmd = ConfigRaftCmd::ConfigAdd {
                     key,
@@ -247,6 +271,8 @@ impl RaftDataHandler {
                     history_table_id,
                     op_time,
                     op_user,
+                    rollback_from,
+                    clone_from,
                 };
                 self.config.send(cmd).await??;
                 Ok(ClientResponse::Success)
@@ -320,6 +346,8 @@ impl RaftDataHandler {
                 history_table_id,
                 op_time,
                 op_user,
+                rollback_from,
+                clone_from,
             } => {
                 let cmd = ConfigRaftCmd::ConfigAdd {
                     key,
@@ -330,6 +358,8 @@ impl RaftDataHandler {
                     history_table_id,
                     op_time,
                     op_user,
+                    rollback_from,
+                    clone_from,
                 };
                 self.config.do_send(cmd);
             }
@@ -10,6 +10,7 @@ use std::{
 use actix::prelude::*;
 use bean_factory::{bean, Inject};
 use binrw::{BinReaderExt, BinWriterExt};
+use opentelemetry::KeyValue;
 use quick_protobuf::{BytesReader, Writer};
 use tokio::{
     fs::OpenOptions,
@@ -25,6 +26,9 @@ use crate::{
         inner_sizeof_varint, read_varint64_offset, write_varint64, FileMessageReader,
         MessageBufReader,
     },
+    metrics::metrics_key::MetricsKey,
+    metrics::model::{MetricsItem, MetricsQuery, MetricsRecord},
+    otel,
     raft::filestore::model::LOG_INDEX_HEADER_LEN,
 };
 
@@ -668,11 +672,13 @@ impl RaftLogActor {
             let mut inner =
                 LogInnerManager::init(log_path, start_index, pre_term, split_off_index).await?;
             while let Some(Some(req)) = rx.recv().await {
-                if req
-                    .sender
-                    .send(inner.handle_request(req.request).await)
-                    .is_err()
-                {
+                let result = match req.request.get_trace_span() {
+                    Some((name, attributes)) => {
+                        otel::in_span(name, attributes, inner.handle_request(req.request)).await
+                    }
+                    None => inner.handle_request(req.request).await,
+                };
+                if req.sender.send(result).is_err() {
                     log::error!("RaftLogActor receive_req, send response error");
                     break;
                 }
@@ -717,6 +723,30 @@ pub enum RaftLogRequest {
     Flush,
 }
 
+impl RaftLogRequest {
+    /// 日志写入与刷盘请求记录链路span
+    fn get_trace_span(&self) -> Option<(&'static str, Vec<KeyValue>)> {
+        if !otel::is_enable() {
+            return None;
+        }
+        match self {
+            RaftLogRequest::Write(record) => Some((
+                "raft.log.write",
+                vec![KeyValue::new("raft.log.index", record.index as i64)],
+            )),
+            RaftLogRequest::WriteBatch(list, record_start_index) => Some((
+                "raft.log.write_batch",
+                vec![KeyValue::new(
+                    "raft.log.count",
+                    list.len().saturating_sub(*record_start_index) as i64,
+                )],
+            )),
+            RaftLogRequest::Flush => Some(("raft.log.flush", vec![])),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Message)]
 #[rtype(result = "anyhow::Result<RaftLogResponse>")]
 pub enum RaftLogCmd {
@@ -1477,3 +1507,31 @@ impl Handler<RaftLogManagerAsyncRequest> for RaftLogManager {
         Box::pin(fut)
     }
 }
+
+impl Handler<MetricsQuery> for RaftLogManager {
+    type Result = anyhow::Result<Vec<MetricsItem>>;
+
+    fn handle(&mut self, _msg: MetricsQuery, _ctx: &mut Self::Context) -> Self::Result {
+        let mut segment_count = 0u64;
+        let mut log_bytes = 0u64;
+        for item in &self.logs {
+            if let Ok(meta) =
+                std::fs::metadata(Self::get_log_path(&self.base_path, &item.log_range))
+            {
+                segment_count += 1;
+                log_bytes += meta.len();
+            }
+        }
+        let list = vec![
+            MetricsItem {
+                metrics_type: MetricsKey::RaftLogSegmentCount,
+                record: MetricsRecord::Gauge(segment_count as f32),
+            },
+            MetricsItem {
+                metrics_type: MetricsKey::RaftLogBytes,
+                record: MetricsRecord::Gauge(log_bytes as f32),
+            },
+        ];
+        Ok(list)
+    }
+}
@@ -10,6 +10,9 @@ use tokio::{
 };
 
 use crate::common::protobuf_utils::MessageBufReader;
+use crate::metrics::metrics_key::MetricsKey;
+use crate::metrics::model::{MetricsItem, MetricsQuery, MetricsRecord};
+use crate::now_millis;
 
 use super::{
     log::{LogSnapshotItem, SnapshotHeader, SnapshotRange},
@@ -249,6 +252,9 @@ pub struct RaftSnapshotManager {
     building: Option<SnapshotRange>,
     index_manager: Option<Addr<RaftIndexManager>>,
     is_init: bool,
+    //正在构建的镜像开始时间
+    build_start_time: Option<u64>,
+    last_build_duration: u64,
 }
 
 impl RaftSnapshotManager {
@@ -260,6 +266,8 @@ impl RaftSnapshotManager {
             building: None,
             index_manager,
             is_init: false,
+            build_start_time: None,
+            last_build_duration: 0,
         }
     }
 
@@ -475,6 +483,7 @@ impl Handler<RaftSnapshotRequest> for RaftSnapshotManager {
                 let next_id = self.get_next_id()?;
                 let path = Arc::new(Self::get_snapshot_path(&self.base_path, next_id));
                 let writer = self.new_writer(ctx, header, path.clone());
+                self.build_start_time = Some(now_millis());
                 Ok(RaftSnapshotResponse::NewSnapshot(writer, next_id, path))
             }
             RaftSnapshotRequest::NewSnapshotForLoad => {
@@ -483,6 +492,9 @@ impl Handler<RaftSnapshotRequest> for RaftSnapshotManager {
                 Ok(RaftSnapshotResponse::NewSnapshotForLoad(path, next_id))
             }
             RaftSnapshotRequest::CompleteSnapshot(snapshot_range) => {
+                if let Some(start_time) = self.build_start_time.take() {
+                    self.last_build_duration = now_millis().saturating_sub(start_time);
+                }
                 self.complete_snapshot(ctx, snapshot_range).ok();
                 Ok(RaftSnapshotResponse::None)
             }
@@ -496,3 +508,31 @@ impl Handler<RaftSnapshotRequest> for RaftSnapshotManager {
         }
     }
 }
+
+impl Handler<MetricsQuery> for RaftSnapshotManager {
+    type Result = anyhow::Result<Vec<MetricsItem>>;
+
+    fn handle(&mut self, _msg: MetricsQuery, _ctx: &mut Self::Context) -> Self::Result {
+        let snapshot_bytes: u64 = self
+            .snapshots
+            .iter()
+            .filter_map(|e| std::fs::metadata(Self::get_snapshot_path(&self.base_path, e.id)).ok())
+            .map(|e| e.len())
+            .sum();
+        let list = vec![
+            MetricsItem {
+                metrics_type: MetricsKey::RaftSnapshotCount,
+                record: MetricsRecord::Gauge(self.snapshots.len() as f32),
+            },
+            MetricsItem {
+                metrics_type: MetricsKey::RaftSnapshotBytes,
+                record: MetricsRecord::Gauge(snapshot_bytes as f32),
+            },
+            MetricsItem {
+                metrics_type: MetricsKey::RaftLastSnapshotDuration,
+                record: MetricsRecord::Gauge(self.last_build_duration as f32),
+            },
+        ];
+        Ok(list)
+    }
+}
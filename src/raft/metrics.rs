@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use actix::prelude::*;
+
+use crate::metrics::labeled::MetricsLabels;
+use crate::metrics::metrics_key::MetricsKey;
+use crate::metrics::model::{LabeledMetricsItem, MetricsItem, MetricsQuery, MetricsRecord};
+use crate::raft::cluster::model::{RouterRequest, RouterResponse};
+use crate::raft::cluster::router_request;
+use crate::raft::filestore::core::FileStore;
+use crate::raft::filestore::raftapply::StateApplyManager;
+use crate::raft::filestore::raftlog::RaftLogManager;
+use crate::raft::filestore::raftsnapshot::RaftSnapshotManager;
+use crate::raft::network::factory::RaftClusterRequestSender;
+use crate::raft::NacosRaft;
+
+///
+/// raft及存储指标采集
+/// 复制延迟只在leader节点上采集,通过集群路由查询各follower的日志位置
+pub struct RaftMetricsCollector {
+    raft: Arc<NacosRaft>,
+    raft_store: Arc<FileStore>,
+    cluster_sender: Arc<RaftClusterRequestSender>,
+    apply_manager: Addr<StateApplyManager>,
+    log_manager: Addr<RaftLogManager>,
+    snapshot_manager: Addr<RaftSnapshotManager>,
+}
+
+impl std::fmt::Debug for RaftMetricsCollector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RaftMetricsCollector").finish()
+    }
+}
+
+impl RaftMetricsCollector {
+    pub fn new(
+        raft: Arc<NacosRaft>,
+        raft_store: Arc<FileStore>,
+        cluster_sender: Arc<RaftClusterRequestSender>,
+        apply_manager: Addr<StateApplyManager>,
+        log_manager: Addr<RaftLogManager>,
+        snapshot_manager: Addr<RaftSnapshotManager>,
+    ) -> Self {
+        Self {
+            raft,
+            raft_store,
+            cluster_sender,
+            apply_manager,
+            log_manager,
+            snapshot_manager,
+        }
+    }
+
+    pub async fn collect(&self) -> anyhow::Result<(Vec<MetricsItem>, Vec<LabeledMetricsItem>)> {
+        let metrics = self.raft.metrics().borrow().clone();
+        let mut list = vec![
+            MetricsItem {
+                metrics_type: MetricsKey::RaftCurrentTerm,
+                record: MetricsRecord::Gauge(metrics.current_term as f32),
+            },
+            MetricsItem {
+                metrics_type: MetricsKey::RaftLeaderId,
+                record: MetricsRecord::Gauge(metrics.current_leader.unwrap_or_default() as f32),
+            },
+            MetricsItem {
+                metrics_type: MetricsKey::RaftLastLogIndex,
+                record: MetricsRecord::Gauge(metrics.last_log_index as f32),
+            },
+            MetricsItem {
+                metrics_type: MetricsKey::RaftLastAppliedIndex,
+                record: MetricsRecord::Gauge(metrics.last_applied as f32),
+            },
+        ];
+        let mut t = self.apply_manager.send(MetricsQuery).await??;
+        list.append(&mut t);
+        let mut t = self.log_manager.send(MetricsQuery).await??;
+        list.append(&mut t);
+        let mut t = self.snapshot_manager.send(MetricsQuery).await??;
+        list.append(&mut t);
+
+        let mut labeled_list = vec![];
+        if metrics.state.is_leader() {
+            for node_id in metrics.membership_config.all_nodes() {
+                if node_id == metrics.id {
+                    continue;
+                }
+                match self.query_follower_log_index(node_id).await {
+                    Ok(follower_index) => {
+                        let lag = metrics.last_log_index.saturating_sub(follower_index);
+                        labeled_list.push(LabeledMetricsItem::new(
+                            MetricsKey::RaftReplicationLag,
+                            MetricsLabels::default().with("node_id", node_id.to_string()),
+                            MetricsRecord::Gauge(lag as f32),
+                        ));
+                    }
+                    Err(err) => {
+                        log::warn!("query raft follower {} metrics error,{}", node_id, err);
+                    }
+                }
+            }
+        }
+        Ok((list, labeled_list))
+    }
+
+    async fn query_follower_log_index(&self, node_id: u64) -> anyhow::Result<u64> {
+        let addr = self.raft_store.get_target_addr(node_id).await?;
+        match router_request(RouterRequest::RaftNodeMetrics, addr, &self.cluster_sender).await? {
+            RouterResponse::RaftNodeMetrics { last_log_index, .. } => Ok(last_log_index),
+            _ => Err(anyhow::anyhow!("response type is error!")),
+        }
+    }
+}
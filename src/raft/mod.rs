@@ -1,6 +1,7 @@
+use crate::otel;
 use crate::raft::filestore::core::FileStore;
-use async_raft_ext::raft::ClientWriteRequest;
-use async_raft_ext::{Raft, RaftStorage};
+use async_raft_ext::raft::{ClientWriteRequest, ClientWriteResponse};
+use async_raft_ext::{ClientWriteError, Raft, RaftStorage};
 
 use self::network::core::RaftRouter;
 use self::store::{ClientRequest, ClientResponse};
@@ -9,11 +10,26 @@ pub mod cache;
 pub mod cluster;
 pub mod db;
 pub mod filestore;
+pub mod metrics;
 pub mod network;
 pub mod store;
 
 pub type NacosRaft = Raft<ClientRequest, ClientResponse, RaftRouter, FileStore>;
 
+///
+/// 写入raft日志并等待应用到状态机,开启链路追踪时记录写入耗时
+pub async fn client_write(
+    raft: &NacosRaft,
+    req: ClientRequest,
+) -> Result<ClientWriteResponse<ClientResponse>, ClientWriteError<ClientRequest>> {
+    otel::in_span(
+        "raft.client_write",
+        vec![],
+        raft.client_write(ClientWriteRequest::new(req)),
+    )
+    .await
+}
+
 pub async fn join_node(
     raft: &NacosRaft,
     raft_store: &FileStore,
@@ -32,6 +32,10 @@ pub enum ClientRequest {
         history_table_id: Option<u64>,
         op_time: i64,
         op_user: Option<Arc<String>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        rollback_from: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        clone_from: Option<Arc<String>>,
     },
     ConfigFullValue {
         key: Vec<u8>,
@@ -1,9 +1,14 @@
 use std::{collections::HashSet, sync::Arc, time::Duration};
 
+use crate::access_token::core::AccessTokenManager;
+use crate::alert::core::AlertManager;
 use crate::common::actor_utils::{create_actor_at_thread, create_actor_at_thread2};
+use crate::config::approval::core::ConfigChangeManager;
+use crate::config::schema::core::ConfigSchemaManager;
 use crate::grpc::handler::RAFT_ROUTE_REQUEST;
 use crate::health::core::HealthManager;
 use crate::ldap::core::LdapManager;
+use crate::limiter::core::RequestLimitManager;
 use crate::mcp::core::McpManager;
 use crate::mcp::sse_manage::SseStreamManager;
 use crate::metrics::core::MetricsManager;
@@ -17,10 +22,12 @@ use crate::raft::filestore::raftdata::RaftDataHandler;
 use crate::raft::filestore::raftindex::RaftIndexManager;
 use crate::raft::filestore::raftlog::RaftLogManager;
 use crate::raft::filestore::raftsnapshot::RaftSnapshotManager;
+use crate::raft::metrics::RaftMetricsCollector;
 use crate::sequence::core::SequenceDbManager;
 use crate::sequence::SequenceManager;
 use crate::transfer::reader::TransferImportManager;
 use crate::transfer::writer::TransferWriterManager;
+use crate::webhook::core::WebhookManager;
 use crate::{
     common::{appdata::AppShareData, AppSysConfig},
     config::core::ConfigActor,
@@ -107,13 +114,22 @@ pub async fn config_factory(sys_config: Arc<AppSysConfig>) -> anyhow::Result<Fac
     let store = Arc::new(FileStore::new(
         sys_config.raft_node_id.to_owned(),
         index_manager,
-        snapshot_manager,
-        log_manager,
-        apply_manager,
+        snapshot_manager.clone(),
+        log_manager.clone(),
+        apply_manager.clone(),
     ));
     factory.register(BeanDefinition::from_obj(store.clone()));
     let raft = build_raft(&sys_config, store.clone(), cluster_sender.clone()).await?;
     factory.register(BeanDefinition::from_obj(raft.clone()));
+    let raft_metrics_collector = Arc::new(RaftMetricsCollector::new(
+        raft.clone(),
+        store.clone(),
+        cluster_sender.clone(),
+        apply_manager,
+        log_manager,
+        snapshot_manager,
+    ));
+    factory.register(BeanDefinition::from_obj(raft_metrics_collector));
     let table_manage = TableManager::new().start();
     factory.register(BeanDefinition::actor_with_inject_from_obj(
         table_manage.clone(),
@@ -228,6 +244,23 @@ pub async fn config_factory(sys_config: Arc<AppSysConfig>) -> anyhow::Result<Fac
     factory.register(BeanDefinition::actor_with_inject_from_obj(oauth2_manager));
     let sse_manager = SseStreamManager::new().start();
     factory.register(BeanDefinition::actor_with_inject_from_obj(sse_manager));
+    let request_limit_manager =
+        RequestLimitManager::new(sys_config.get_request_limit_config()).start();
+    factory.register(BeanDefinition::actor_with_inject_from_obj(
+        request_limit_manager,
+    ));
+    let access_token_manager = AccessTokenManager::new().start();
+    factory.register(BeanDefinition::actor_with_inject_from_obj(
+        access_token_manager,
+    ));
+    let config_schema_manager = ConfigSchemaManager::new().start();
+    factory.register(BeanDefinition::actor_from_obj(config_schema_manager));
+    let config_change_manager = ConfigChangeManager::new().start();
+    factory.register(BeanDefinition::actor_from_obj(config_change_manager));
+    let webhook_manager = WebhookManager::new(sys_config.webhook_max_retry).start();
+    factory.register(BeanDefinition::actor_with_inject_from_obj(webhook_manager));
+    let alert_manager = AlertManager::new(&sys_config).start();
+    factory.register(BeanDefinition::actor_with_inject_from_obj(alert_manager));
     Ok(factory.init().await)
 }
 
@@ -278,6 +311,12 @@ pub fn build_share_data(factory_data: FactoryData) -> anyhow::Result<Arc<AppShar
         sequence_db_manager: factory_data.get_actor().unwrap(),
         mcp_manager: factory_data.get_actor().unwrap(),
         sse_stream_manager: factory_data.get_actor().unwrap(),
+        request_limit_manager: factory_data.get_actor().unwrap(),
+        access_token_manager: factory_data.get_actor().unwrap(),
+        config_schema_manager: factory_data.get_actor().unwrap(),
+        config_change_manager: factory_data.get_actor().unwrap(),
+        webhook_manager: factory_data.get_actor().unwrap(),
+        alert_manager: factory_data.get_actor().unwrap(),
         factory_data,
         common_client: reqwest_client,
     });
@@ -177,6 +177,7 @@ async fn apply_tenant(
             namespace_id: item.tenant_id,
             namespace_name: item.tenant_name,
             r#type: Some(FROM_USER_VALUE.to_string()),
+            quota: None,
         };
         let record = TransferRecordDto {
             table_name: Some(NAMESPACE_TREE_NAME.clone()),
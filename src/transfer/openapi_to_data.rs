@@ -61,6 +61,7 @@ fn apply_tenant(
             namespace_id: item.namespace.clone(),
             namespace_name: item.namespace_show_name.clone(),
             r#type: Some(FROM_USER_VALUE.to_string()),
+            quota: None,
         };
         let record = TransferRecordDto {
             table_name: Some(NAMESPACE_TREE_NAME.clone()),
@@ -1,6 +1,7 @@
 use crate::common::constant::{
-    CACHE_TREE_NAME, CONFIG_TREE_NAME, EMPTY_ARC_STRING, MCP_SERVER_TABLE_NAME,
-    MCP_TOOL_SPEC_TABLE_NAME, NAMESPACE_TREE_NAME, NAMING_INSTANCE_TABLE, USER_TREE_NAME,
+    CACHE_TREE_NAME, CONFIG_LOCK_TABLE_NAME, CONFIG_TREE_NAME, EMPTY_ARC_STRING,
+    MCP_SERVER_TABLE_NAME, MCP_TOOL_SPEC_TABLE_NAME, NAMESPACE_TREE_NAME, NAMING_INSTANCE_TABLE,
+    USER_TREE_NAME,
 };
 use crate::common::pb::data_object::{McpServerDo, McpToolSpecDo};
 use crate::common::pb::transfer::{TransferHeader, TransferItem};
@@ -25,7 +26,6 @@ use crate::transfer::model::{
     TransferPrefix, TransferRecordRef,
 };
 use actix::prelude::*;
-use async_raft_ext::raft::ClientWriteRequest;
 use bean_factory::{bean, BeanFactory, FactoryData, Inject};
 use binrw::BinReaderExt;
 use quick_protobuf::BytesReader;
@@ -54,6 +54,8 @@ pub(crate) fn reader_transfer_record<'a>(
             MCP_SERVER_TABLE_NAME.clone()
         } else if NAMING_INSTANCE_TABLE.as_str() == record_do.table_name.as_ref() {
             NAMING_INSTANCE_TABLE.clone()
+        } else if CONFIG_LOCK_TABLE_NAME.as_str() == record_do.table_name.as_ref() {
+            CONFIG_LOCK_TABLE_NAME.clone()
         } else {
             //ignore
             EMPTY_ARC_STRING.clone()
@@ -223,6 +225,8 @@ impl TransferImportManager {
                     Self::apply_namespace(raft, record).await?;
                 } else if (param.user && record.table_name.as_str() == USER_TREE_NAME.as_str())
                     || (param.cache && record.table_name.as_str() == CACHE_TREE_NAME.as_str())
+                    || (param.config
+                        && record.table_name.as_str() == CONFIG_LOCK_TABLE_NAME.as_str())
                 {
                     Self::apply_table(raft, record).await?;
                 } else if param.naming
@@ -266,6 +270,7 @@ impl TransferImportManager {
             namespace_id: value.namespace_id,
             namespace_name: Some(value.namespace_name),
             r#type: Some(NamespaceFromFlags::get_db_type(value.flag)),
+            quota: value.quota.map(|e| e.as_ref().to_owned()),
         };
         let req = ClientRequest::NamespaceReq(NamespaceRaftReq::Update(param));
         Self::send_raft_request(raft, req).await?;
@@ -361,7 +366,7 @@ impl TransferImportManager {
     }
 
     async fn send_raft_request(raft: &Arc<NacosRaft>, req: ClientRequest) -> anyhow::Result<()> {
-        raft.client_write(ClientWriteRequest::new(req)).await?;
+        crate::raft::client_write(raft, req).await?;
         Ok(())
     }
 
@@ -1,7 +1,8 @@
 #![allow(clippy::suspicious_open_options)]
 use crate::common::constant::{
-    CACHE_TREE_NAME, CONFIG_TREE_NAME, EMPTY_STR, MCP_SERVER_TABLE_NAME, MCP_TOOL_SPEC_TABLE_NAME,
-    NAMESPACE_TREE_NAME, NAMING_INSTANCE_TABLE, SEQUENCE_TREE_NAME, USER_TREE_NAME,
+    CACHE_TREE_NAME, CONFIG_LOCK_TABLE_NAME, CONFIG_TREE_NAME, EMPTY_STR, MCP_SERVER_TABLE_NAME,
+    MCP_TOOL_SPEC_TABLE_NAME, NAMESPACE_TREE_NAME, NAMING_INSTANCE_TABLE, SEQUENCE_TREE_NAME,
+    USER_TREE_NAME,
 };
 use crate::common::tempfile::TempFile;
 use crate::raft::filestore::raftdata::RaftDataHandler;
@@ -221,6 +222,9 @@ impl TransferWriterManager {
         writer_actor.do_send(TransferWriterRequest::AddTableNameMap(
             NAMING_INSTANCE_TABLE.clone(),
         ));
+        writer_actor.do_send(TransferWriterRequest::AddTableNameMap(
+            CONFIG_LOCK_TABLE_NAME.clone(),
+        ));
         writer_actor.do_send(TransferWriterRequest::InitHeader);
         writer_actor
     }
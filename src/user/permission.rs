@@ -152,6 +152,9 @@ lazy_static::lazy_static! {
         R::Path("/rnacos/api/console/v2/user/info",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/user/web_resources",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/user/reset_password",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/user/access_token/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/user/access_token/create",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/user/access_token/remove",HTTP_METHOD_ALL),
         R::Path("/rnacos/api/console/v2/namespaces/list",HTTP_METHOD_GET),
 
     ]);
@@ -164,6 +167,8 @@ lazy_static::lazy_static! {
         R::Path("/rnacos/manage/cluster",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/cluster/cluster_node_list",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/cluster/cluster_node_list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/connection/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/connection/info",HTTP_METHOD_GET),
     ]);
 
     static ref M_NAMESPACE_VISITOR: ModuleResource = ModuleResource::new(vec![
@@ -228,6 +233,12 @@ lazy_static::lazy_static! {
         R::Path("/rnacos/api/console/v2/config/download",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/config/info",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/config/history",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/history/rollback_preview",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/schema/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/change/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/change/info",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/listener/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/listener/list_by_ip",HTTP_METHOD_GET),
     ]);
 
     static ref M_CONFIG_MANAGE: ModuleResource = ModuleResource::new(vec![
@@ -252,10 +263,24 @@ lazy_static::lazy_static! {
         R::Path("/rnacos/api/console/v2/config/download",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/config/info",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/config/history",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/history/rollback_preview",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/schema/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/history/rollback",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/config/schema/add",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/config/schema/update",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/config/schema/remove",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/config/change/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/change/info",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/change/submit",HTTP_METHOD_POST),
+        R::Path("/rnacos/api/console/v2/config/lock/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/listener/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/listener/list_by_ip",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/config/import",HTTP_METHOD_ALL),
         R::Path("/rnacos/api/console/v2/config/add",HTTP_METHOD_ALL),
         R::Path("/rnacos/api/console/v2/config/update",HTTP_METHOD_ALL),
         R::Path("/rnacos/api/console/v2/config/remove",HTTP_METHOD_ALL),
+        R::Path("/rnacos/api/console/v2/config/clone/preview",HTTP_METHOD_POST),
+        R::Path("/rnacos/api/console/v2/config/clone",HTTP_METHOD_POST),
     ]);
 
     static ref M_NAMING_VISITOR: ModuleResource = ModuleResource::new(vec![
@@ -280,6 +305,7 @@ lazy_static::lazy_static! {
         R::Path("/rnacos/api/console/v2/service/subscriber/list",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/instance/list",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/instance/info",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/instance/history",HTTP_METHOD_GET),
         R::Path("/rnacos/manage/subscriber", HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/cluster/cluster_node_list",HTTP_METHOD_GET),
     ]);
@@ -311,6 +337,7 @@ lazy_static::lazy_static! {
         R::Path("/rnacos/api/console/v2/service/remove",HTTP_METHOD_ALL),
         R::Path("/rnacos/api/console/v2/instance/list",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/instance/info",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/instance/history",HTTP_METHOD_GET),
         R::Path("/rnacos/api/console/v2/instance/add",HTTP_METHOD_ALL),
         R::Path("/rnacos/api/console/v2/instance/update",HTTP_METHOD_ALL),
         R::Path("/rnacos/api/console/v2/instance/remove",HTTP_METHOD_ALL),
@@ -339,6 +366,77 @@ lazy_static::lazy_static! {
         R::Path("/rnacos/api/console/transfer/import",HTTP_METHOD_ALL),
     ]);
 
+    static ref M_CONNECTION_MANAGE: ModuleResource = ModuleResource::new(vec![
+        //WebResource
+        R::WebResource("CONNECTION_RESET"),
+        //path
+        R::Path("/rnacos/api/console/v2/connection/reset",HTTP_METHOD_POST),
+    ]);
+
+    static ref M_SYS_CONFIG_MANAGE: ModuleResource = ModuleResource::new(vec![
+        //WebResource
+        R::WebResource("SYS_CONFIG_UPDATE"),
+        //path
+        R::Path("/rnacos/api/console/v2/sys/request_limit/info",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/sys/request_limit/update",HTTP_METHOD_POST),
+        R::Path("/rnacos/api/console/v2/sys/request_limit/reset",HTTP_METHOD_POST),
+    ]);
+
+    static ref M_CONFIG_APPROVE: ModuleResource = ModuleResource::new(vec![
+        //WebResource
+        R::WebResource("CONFIG_APPROVE"),
+        //path
+        R::Path("/rnacos/api/console/v2/config/change/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/change/info",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/change/approve",HTTP_METHOD_POST),
+        R::Path("/rnacos/api/console/v2/config/change/reject",HTTP_METHOD_POST),
+    ]);
+
+    static ref M_CONFIG_LOCK: ModuleResource = ModuleResource::new(vec![
+        //WebResource
+        R::WebResource("CONFIG_LOCK"),
+        //path
+        R::Path("/rnacos/api/console/v2/config/lock/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/config/lock/add",HTTP_METHOD_POST),
+        R::Path("/rnacos/api/console/v2/config/lock/remove",HTTP_METHOD_POST),
+    ]);
+
+    static ref M_WEBHOOK_MANAGE: ModuleResource = ModuleResource::new(vec![
+        //WebResource
+        R::WebResource("WEBHOOK_UPDATE"),
+        //path
+        R::Path("/rnacos/api/console/v2/webhook/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/webhook/add",HTTP_METHOD_POST),
+        R::Path("/rnacos/api/console/v2/webhook/update",HTTP_METHOD_POST),
+        R::Path("/rnacos/api/console/v2/webhook/remove",HTTP_METHOD_POST),
+    ]);
+
+    static ref M_ALERT_MANAGE: ModuleResource = ModuleResource::new(vec![
+        //WebResource
+        R::WebResource("ALERT_UPDATE"),
+        //path
+        R::Path("/rnacos/api/console/v2/alert/rule/list",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/alert/rule/add",HTTP_METHOD_POST),
+        R::Path("/rnacos/api/console/v2/alert/rule/update",HTTP_METHOD_POST),
+        R::Path("/rnacos/api/console/v2/alert/rule/remove",HTTP_METHOD_POST),
+        R::Path("/rnacos/api/console/v2/alert/history/list",HTTP_METHOD_GET),
+    ]);
+
+    static ref M_LOG_LEVEL_MANAGE: ModuleResource = ModuleResource::new(vec![
+        //WebResource
+        R::WebResource("LOG_LEVEL_UPDATE"),
+        //path
+        R::Path("/rnacos/api/console/v2/log/level",HTTP_METHOD_GET),
+        R::Path("/rnacos/api/console/v2/log/level/update",HTTP_METHOD_POST),
+    ]);
+
+    static ref M_DEBUG_DUMP: ModuleResource = ModuleResource::new(vec![
+        //WebResource
+        R::WebResource("DEBUG_DUMP"),
+        //path
+        R::Path("/rnacos/api/console/v2/debug/dump",HTTP_METHOD_GET),
+    ]);
+
     static ref M_MCP_TOOL_SPEC_VISITOR: ModuleResource = ModuleResource::new(vec![
         //WebResource
         R::WebResource("/manage/mcptoolspec"),
@@ -421,6 +519,7 @@ lazy_static::lazy_static! {
         &M_CONFIG_MANAGE,
         &M_NAMING_MANAGE,
         &M_METRICS_VISITOR,
+        &M_WEBHOOK_MANAGE,
         &M_MCP_TOOL_SPEC_MANAGE,
         &M_MCP_SERVER_MANAGE,
     ]));
@@ -434,6 +533,14 @@ lazy_static::lazy_static! {
         &M_USER_MANAGE,
         &M_METRICS_VISITOR,
         &M_TRASFER_DATE_MANAGE,
+        &M_CONNECTION_MANAGE,
+        &M_SYS_CONFIG_MANAGE,
+        &M_CONFIG_APPROVE,
+        &M_CONFIG_LOCK,
+        &M_WEBHOOK_MANAGE,
+        &M_ALERT_MANAGE,
+        &M_LOG_LEVEL_MANAGE,
+        &M_DEBUG_DUMP,
         &M_MCP_TOOL_SPEC_MANAGE,
         &M_MCP_SERVER_MANAGE,
     ]));
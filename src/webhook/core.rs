@@ -0,0 +1,245 @@
+use crate::raft::NacosRaft;
+use crate::webhook::model::{
+    sign_payload, WebhookDo, WebhookDto, WebhookEvent, WebhookReq, WebhookResult,
+    WEBHOOK_DELIVERY_HEADER, WEBHOOK_EVENT_HEADER, WEBHOOK_SIGNATURE_HEADER,
+};
+use actix::prelude::*;
+use bean_factory::{bean, BeanFactory, FactoryData, Inject};
+use std::collections::HashMap;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+const DELIVERY_TIMEOUT_SECOND: u64 = 5;
+/// 重试退避的最大间隔
+const MAX_BACKOFF_SECOND: u64 = 60;
+
+///
+/// webhook订阅管理器
+/// 订阅数据通过raft同步到各节点,各节点都会收到变更事件,只有主节点负责推送,保证每个事件只推送一次
+#[bean(inject)]
+pub struct WebhookManager {
+    hooks: HashMap<Arc<String>, Arc<WebhookDo>>,
+    raft: Option<Weak<NacosRaft>>,
+    client: reqwest::Client,
+    max_retry: u32,
+}
+
+impl WebhookManager {
+    pub fn new(max_retry: u32) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(DELIVERY_TIMEOUT_SECOND))
+            .build()
+            .unwrap_or_default();
+        Self {
+            hooks: Default::default(),
+            raft: None,
+            client,
+            max_retry,
+        }
+    }
+
+    fn is_leader(&self) -> bool {
+        if let Some(raft) = self.raft.as_ref().and_then(|e| e.upgrade()) {
+            raft.metrics().borrow().state.is_leader()
+        } else {
+            false
+        }
+    }
+
+    fn notify_change(&mut self, key: Vec<u8>, value: Option<Vec<u8>>) {
+        let id = Arc::new(String::from_utf8_lossy(&key).to_string());
+        match value {
+            Some(value) => match WebhookDo::from_bytes(&value) {
+                Ok(item) => {
+                    self.hooks.insert(id, Arc::new(item));
+                }
+                Err(err) => {
+                    log::error!("parse webhook error:{}", err);
+                }
+            },
+            None => {
+                self.hooks.remove(&id);
+            }
+        }
+    }
+
+    fn query_list(&self, namespace: &Option<Arc<String>>) -> Vec<WebhookDto> {
+        let mut list: Vec<WebhookDto> = self
+            .hooks
+            .values()
+            .filter(|e| namespace.is_none() || &e.namespace == namespace)
+            .map(|e| WebhookDto::from(e.as_ref()))
+            .collect();
+        list.sort_by_key(|e| std::cmp::Reverse(e.create_time));
+        list
+    }
+
+    fn match_hooks(&self, event: &WebhookEvent) -> Vec<Arc<WebhookDo>> {
+        self.hooks
+            .values()
+            .filter(|e| e.match_event(event))
+            .cloned()
+            .collect()
+    }
+
+    fn fire(&self, event: WebhookEvent, ctx: &mut Context<Self>) {
+        if self.hooks.is_empty() || !self.is_leader() {
+            return;
+        }
+        let hooks = self.match_hooks(&event);
+        if hooks.is_empty() {
+            return;
+        }
+        let payload = match serde_json::to_vec(&event) {
+            Ok(v) => Arc::new(v),
+            Err(err) => {
+                log::error!("serialize webhook event error:{}", err);
+                return;
+            }
+        };
+        for hook in hooks {
+            let client = self.client.clone();
+            let payload = payload.clone();
+            let event_type = event.event_type.get_value();
+            let max_retry = self.max_retry;
+            async move {
+                Self::deliver(client, hook, event_type, payload, max_retry).await;
+            }
+            .into_actor(self)
+            .map(|_, _, _| {})
+            .spawn(ctx);
+        }
+    }
+
+    ///
+    /// 推送事件,失败后按指数退避重试
+    async fn deliver(
+        client: reqwest::Client,
+        hook: Arc<WebhookDo>,
+        event_type: &'static str,
+        payload: Arc<Vec<u8>>,
+        max_retry: u32,
+    ) {
+        let delivery_id = uuid::Uuid::new_v4().simple().to_string();
+        let signature = hook
+            .secret
+            .as_ref()
+            .filter(|e| !e.is_empty())
+            .map(|secret| format!("sha256={}", sign_payload(secret, &payload)));
+        let mut attempt = 0;
+        loop {
+            let mut req = client
+                .post(hook.url.as_str())
+                .header("Content-Type", "application/json")
+                .header(WEBHOOK_EVENT_HEADER, event_type)
+                .header(WEBHOOK_DELIVERY_HEADER, &delivery_id)
+                .body(payload.as_ref().clone());
+            if let Some(signature) = &signature {
+                req = req.header(WEBHOOK_SIGNATURE_HEADER, signature);
+            }
+            let err = match req.send().await {
+                Ok(res) if res.status().is_success() => return,
+                Ok(res) => format!("response status {}", res.status()),
+                Err(err) => err.to_string(),
+            };
+            if attempt >= max_retry {
+                log::warn!(
+                    "webhook delivery failed, id:{}, url:{}, event:{}, error:{}",
+                    &hook.id,
+                    &hook.url,
+                    event_type,
+                    err
+                );
+                return;
+            }
+            let backoff = (1u64 << attempt.min(6)).min(MAX_BACKOFF_SECOND);
+            attempt += 1;
+            tokio::time::sleep(Duration::from_secs(backoff)).await;
+        }
+    }
+}
+
+impl Actor for WebhookManager {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _ctx: &mut Self::Context) {
+        log::info!("WebhookManager started");
+    }
+}
+
+impl Inject for WebhookManager {
+    type Context = Context<Self>;
+
+    fn inject(
+        &mut self,
+        factory_data: FactoryData,
+        _factory: BeanFactory,
+        _ctx: &mut Self::Context,
+    ) {
+        let raft: Option<Arc<NacosRaft>> = factory_data.get_bean();
+        self.raft = raft.map(|e| Arc::downgrade(&e));
+    }
+}
+
+impl Handler<WebhookReq> for WebhookManager {
+    type Result = anyhow::Result<WebhookResult>;
+
+    fn handle(&mut self, msg: WebhookReq, ctx: &mut Self::Context) -> Self::Result {
+        match msg {
+            WebhookReq::Fire(event) => {
+                self.fire(event, ctx);
+                Ok(WebhookResult::None)
+            }
+            WebhookReq::NotifyChange { key, value } => {
+                self.notify_change(key, value);
+                Ok(WebhookResult::None)
+            }
+            WebhookReq::QueryList { namespace } => {
+                Ok(WebhookResult::List(self.query_list(&namespace)))
+            }
+            WebhookReq::Query(id) => Ok(WebhookResult::Webhook(self.hooks.get(&id).cloned())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::core::ConfigKey;
+    use crate::webhook::model::WebhookEventType;
+
+    #[test]
+    fn match_webhook_event() {
+        let mut manager = WebhookManager::new(0);
+        let item = WebhookDo {
+            id: Arc::new("w1".to_owned()),
+            url: Arc::new("http://127.0.0.1/hook".to_owned()),
+            event_types: vec![WebhookEventType::ConfigPublished],
+            namespace: Some(Arc::new("prod".to_owned())),
+            enabled: true,
+            ..Default::default()
+        };
+        manager.notify_change(b"w1".to_vec(), Some(item.to_bytes().unwrap()));
+        let event = WebhookEvent::new_config_event(
+            WebhookEventType::ConfigPublished,
+            &ConfigKey::new("app.yaml", "DEFAULT_GROUP", "prod"),
+        );
+        assert_eq!(manager.match_hooks(&event).len(), 1);
+        let event = WebhookEvent::new_config_event(
+            WebhookEventType::ConfigRemoved,
+            &ConfigKey::new("app.yaml", "DEFAULT_GROUP", "prod"),
+        );
+        assert!(manager.match_hooks(&event).is_empty());
+        let event = WebhookEvent::new_config_event(
+            WebhookEventType::ConfigPublished,
+            &ConfigKey::new("app.yaml", "DEFAULT_GROUP", "dev"),
+        );
+        assert!(manager.match_hooks(&event).is_empty());
+        manager.notify_change(b"w1".to_vec(), None);
+        assert!(manager.query_list(&None).is_empty());
+        assert_eq!(
+            sign_payload("key", b"The quick brown fox jumps over the lazy dog"),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+}
@@ -0,0 +1,272 @@
+use crate::common::model::privilege::NamespacePrivilegeGroup;
+use crate::config::core::ConfigKey;
+use crate::naming::model::ServiceKey;
+use crate::now_millis_i64;
+use actix::prelude::*;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::sync::Arc;
+
+/// 签名请求头,值为`sha256=<hex>`
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-Rnacos-Signature";
+pub const WEBHOOK_EVENT_HEADER: &str = "X-Rnacos-Event";
+pub const WEBHOOK_DELIVERY_HEADER: &str = "X-Rnacos-Delivery";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookEventType {
+    ConfigPublished,
+    ConfigRemoved,
+    InstanceUp,
+    InstanceDown,
+    /// 服务的健康实例全部下线
+    ServiceEmpty,
+}
+
+impl WebhookEventType {
+    pub fn get_value(&self) -> &'static str {
+        match self {
+            WebhookEventType::ConfigPublished => "configPublished",
+            WebhookEventType::ConfigRemoved => "configRemoved",
+            WebhookEventType::InstanceUp => "instanceUp",
+            WebhookEventType::InstanceDown => "instanceDown",
+            WebhookEventType::ServiceEmpty => "serviceEmpty",
+        }
+    }
+}
+
+///
+/// webhook订阅
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDo {
+    pub id: Arc<String>,
+    pub name: Arc<String>,
+    pub url: Arc<String>,
+    pub event_types: Vec<WebhookEventType>,
+    /// 命名空间过滤,为空表示全部命名空间
+    pub namespace: Option<Arc<String>>,
+    /// 分组过滤,为空表示全部分组
+    pub group: Option<Arc<String>>,
+    /// HMAC-SHA256签名密钥
+    pub secret: Option<Arc<String>>,
+    pub enabled: bool,
+    pub op_user: Option<Arc<String>>,
+    pub create_time: i64,
+    pub update_time: i64,
+}
+
+impl WebhookDo {
+    pub fn to_bytes(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    pub fn from_bytes(v: &[u8]) -> anyhow::Result<Self> {
+        Ok(serde_json::from_slice(v)?)
+    }
+
+    pub fn match_event(&self, event: &WebhookEvent) -> bool {
+        self.enabled
+            && self.event_types.contains(&event.event_type)
+            && self
+                .namespace
+                .as_ref()
+                .map(|v| v == &event.namespace)
+                .unwrap_or(true)
+            && self
+                .group
+                .as_ref()
+                .map(|v| v == &event.group)
+                .unwrap_or(true)
+    }
+}
+
+///
+/// 查询返回的webhook订阅,不返回密钥
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookDto {
+    pub id: Arc<String>,
+    pub name: Arc<String>,
+    pub url: Arc<String>,
+    pub event_types: Vec<WebhookEventType>,
+    pub namespace: Option<Arc<String>>,
+    pub group: Option<Arc<String>>,
+    pub has_secret: bool,
+    pub enabled: bool,
+    pub op_user: Option<Arc<String>>,
+    pub create_time: i64,
+    pub update_time: i64,
+}
+
+impl From<&WebhookDo> for WebhookDto {
+    fn from(v: &WebhookDo) -> Self {
+        Self {
+            id: v.id.clone(),
+            name: v.name.clone(),
+            url: v.url.clone(),
+            event_types: v.event_types.clone(),
+            namespace: v.namespace.clone(),
+            group: v.group.clone(),
+            has_secret: v.secret.as_ref().map(|e| !e.is_empty()).unwrap_or(false),
+            enabled: v.enabled,
+            op_user: v.op_user.clone(),
+            create_time: v.create_time,
+            update_time: v.update_time,
+        }
+    }
+}
+
+///
+/// webhook事件,序列化后作为推送内容
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookEvent {
+    pub event_type: WebhookEventType,
+    pub namespace: Arc<String>,
+    pub group: Arc<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_id: Option<Arc<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_name: Option<Arc<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip: Option<Arc<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u32>,
+    pub event_time: i64,
+}
+
+impl WebhookEvent {
+    pub fn new_config_event(event_type: WebhookEventType, key: &ConfigKey) -> Self {
+        Self {
+            event_type,
+            namespace: key.tenant.clone(),
+            group: key.group.clone(),
+            data_id: Some(key.data_id.clone()),
+            service_name: None,
+            ip: None,
+            port: None,
+            event_time: now_millis_i64(),
+        }
+    }
+
+    pub fn new_naming_event(
+        event_type: WebhookEventType,
+        key: &ServiceKey,
+        instance: Option<(Arc<String>, u32)>,
+    ) -> Self {
+        let (ip, port) = match instance {
+            Some((ip, port)) => (Some(ip), Some(port)),
+            None => (None, None),
+        };
+        Self {
+            event_type,
+            namespace: key.namespace_id.clone(),
+            group: key.group_name.clone(),
+            data_id: None,
+            service_name: Some(key.service_name.clone()),
+            ip,
+            port,
+            event_time: now_millis_i64(),
+        }
+    }
+}
+
+///
+/// 计算推送内容签名,hex编码
+pub fn sign_payload(secret: &str, payload: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC can take key of any size");
+    mac.update(payload);
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookParam {
+    pub id: Option<Arc<String>>,
+    pub name: Option<Arc<String>>,
+    pub url: Option<Arc<String>>,
+    pub event_types: Option<Vec<WebhookEventType>>,
+    pub namespace: Option<Arc<String>>,
+    pub group: Option<Arc<String>>,
+    /// 更新时为空表示保留原密钥
+    pub secret: Option<Arc<String>>,
+    pub enabled: Option<bool>,
+}
+
+impl WebhookParam {
+    pub fn get_namespace(&self) -> Option<Arc<String>> {
+        match &self.namespace {
+            Some(v) if v.as_str() == "public" => Some(Arc::new(String::new())),
+            Some(v) if !v.is_empty() => Some(v.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn get_group(&self) -> Option<Arc<String>> {
+        self.group.clone().filter(|e| !e.is_empty())
+    }
+
+    pub fn check(&self, namespace_privilege: &NamespacePrivilegeGroup) -> anyhow::Result<()> {
+        match self.get_namespace() {
+            Some(namespace) => {
+                if !namespace_privilege.check_permission(&namespace) {
+                    return Err(anyhow::anyhow!(
+                        "user no such namespace permission: {}",
+                        &namespace
+                    ));
+                }
+            }
+            None => {
+                if !namespace_privilege.is_all() {
+                    return Err(anyhow::anyhow!(
+                        "user no permission to subscribe all namespaces"
+                    ));
+                }
+            }
+        }
+        if self.name.as_ref().map(|e| e.is_empty()).unwrap_or(true) {
+            return Err(anyhow::anyhow!("name is empty"));
+        }
+        match &self.url {
+            Some(url) if url.starts_with("http://") || url.starts_with("https://") => {}
+            _ => return Err(anyhow::anyhow!("url must start with http:// or https://")),
+        }
+        if self
+            .event_types
+            .as_ref()
+            .map(|e| e.is_empty())
+            .unwrap_or(true)
+        {
+            return Err(anyhow::anyhow!("eventTypes is empty"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Message, Clone, Debug)]
+#[rtype(result = "anyhow::Result<WebhookResult>")]
+pub enum WebhookReq {
+    /// 触发事件,只有主节点推送
+    Fire(WebhookEvent),
+    NotifyChange {
+        key: Vec<u8>,
+        value: Option<Vec<u8>>,
+    },
+    QueryList {
+        namespace: Option<Arc<String>>,
+    },
+    Query(Arc<String>),
+}
+
+pub enum WebhookResult {
+    None,
+    List(Vec<WebhookDto>),
+    Webhook(Option<Arc<WebhookDo>>),
+}